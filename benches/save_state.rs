@@ -0,0 +1,46 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use petty::history::{Event, MAX_LOG_ENTRIES};
+use petty::pet::Pet;
+use petty::state::{StateFile, StateFormat, save_state_to};
+use std::fs;
+use std::hint::black_box;
+
+/// A pet whose log is full, as it is after a few weeks of play
+fn old_pet() -> Pet {
+    let mut pet = Pet::new("Bench".to_string());
+    for hunger in (0..MAX_LOG_ENTRIES).map(|i| (i % 100) as u8) {
+        pet.record(Event::Fed { hunger }, chrono::Utc::now());
+    }
+    pet
+}
+
+/// An autosave after one new event, with the whole pet in one document
+/// against the core plus appended journals
+fn autosave(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("autosave");
+
+    let mut pet = old_pet();
+    let path = dir.path().join("monolithic.json");
+    group.bench_function("monolithic", |b| {
+        b.iter(|| {
+            pet.record(Event::Trained, chrono::Utc::now());
+            fs::write(&path, StateFormat::Json.encode(black_box(&pet)).unwrap()).unwrap();
+        })
+    });
+
+    let mut pet = old_pet();
+    let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+    save_state_to(&file, &pet).unwrap();
+    group.bench_function("journaled", |b| {
+        b.iter(|| {
+            pet.record(Event::Trained, chrono::Utc::now());
+            save_state_to(&file, black_box(&pet)).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, autosave);
+criterion_main!(benches);
@@ -0,0 +1,151 @@
+use crate::i18n::Lang;
+use crate::pet::{LifeStage, Pet, TickEvent, TickReport};
+use crate::skills::Skill;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Seconds every stat has to stay high for `Achievement::ThrivedForADay`
+pub const THRIVING_DAY_SECONDS: u32 = 24 * 60 * 60;
+/// Level every skill has to reach for `Achievement::WellTrained`
+pub const WELL_TRAINED_LEVEL: u8 = 5;
+
+/// Milestones a pet unlocks once and keeps for life
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Achievement {
+    ReachedElderly,
+    SurvivedWeek,
+    ThrivedForADay,
+    RecoveredFromSickness,
+    WellTrained,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::ReachedElderly,
+        Achievement::SurvivedWeek,
+        Achievement::ThrivedForADay,
+        Achievement::RecoveredFromSickness,
+        Achievement::WellTrained,
+    ];
+
+    pub fn title(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Achievement::ReachedElderly, Lang::Zh) => "颐养天年",
+            (Achievement::ReachedElderly, Lang::En) => "Golden years",
+            (Achievement::SurvivedWeek, Lang::Zh) => "一周相伴",
+            (Achievement::SurvivedWeek, Lang::En) => "One week together",
+            (Achievement::ThrivedForADay, Lang::Zh) => "完美的一天",
+            (Achievement::ThrivedForADay, Lang::En) => "A perfect day",
+            (Achievement::RecoveredFromSickness, Lang::Zh) => "妙手回春",
+            (Achievement::RecoveredFromSickness, Lang::En) => "Back on its feet",
+            (Achievement::WellTrained, Lang::Zh) => "文武双全",
+            (Achievement::WellTrained, Lang::En) => "Well trained",
+        }
+    }
+
+    pub fn description(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Achievement::ReachedElderly, Lang::Zh) => "陪宠物步入老年",
+            (Achievement::ReachedElderly, Lang::En) => "Raise your pet into old age",
+            (Achievement::SurvivedWeek, Lang::Zh) => "宠物活过 7 天",
+            (Achievement::SurvivedWeek, Lang::En) => "Keep your pet alive for 7 days",
+            (Achievement::ThrivedForADay, Lang::Zh) => "所有状态保持在 80 以上一整天",
+            (Achievement::ThrivedForADay, Lang::En) => "Keep every stat above 80 for a day",
+            (Achievement::RecoveredFromSickness, Lang::Zh) => "让生病的宠物康复",
+            (Achievement::RecoveredFromSickness, Lang::En) => "Nurse a sick pet back to health",
+            (Achievement::WellTrained, Lang::Zh) => "每项技能都练到 5 级",
+            (Achievement::WellTrained, Lang::En) => "Train every skill to level 5",
+        }
+    }
+}
+
+/// Every achievement whose condition holds after a tick, unlocked or not
+pub fn earned(pet: &Pet, report: &TickReport, now: DateTime<Utc>) -> Vec<Achievement> {
+    Achievement::ALL
+        .into_iter()
+        .filter(|achievement| match achievement {
+            Achievement::ReachedElderly => pet.stage() == LifeStage::Elderly,
+            Achievement::SurvivedWeek => {
+                pet.health > 0 && now.signed_duration_since(pet.birth) >= TimeDelta::days(7)
+            }
+            Achievement::ThrivedForADay => pet.high_stats_seconds >= THRIVING_DAY_SECONDS,
+            Achievement::RecoveredFromSickness => report.has(TickEvent::Healed),
+            Achievement::WellTrained => Skill::ALL
+                .into_iter()
+                .all(|skill| pet.skills.level(skill) >= WELL_TRAINED_LEVEL),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::xp_for_level;
+
+    fn report(events: Vec<TickEvent>) -> TickReport {
+        TickReport {
+            events,
+            ..TickReport::default()
+        }
+    }
+
+    #[test]
+    fn test_each_achievement_unlocks_under_its_condition() {
+        let now = Utc::now();
+        let pet = Pet::new("TestPet".to_string());
+        assert!(earned(&pet, &report(vec![]), now).is_empty());
+
+        let mut elder = Pet::new("TestPet".to_string());
+        elder.age = 60;
+        assert_eq!(
+            earned(&elder, &report(vec![]), now),
+            [Achievement::ReachedElderly]
+        );
+
+        let mut veteran = Pet::new("TestPet".to_string());
+        veteran.birth = now - TimeDelta::days(7);
+        assert_eq!(
+            earned(&veteran, &report(vec![]), now),
+            [Achievement::SurvivedWeek]
+        );
+
+        let mut thriving = Pet::new("TestPet".to_string());
+        thriving.high_stats_seconds = THRIVING_DAY_SECONDS - 1;
+        assert!(earned(&thriving, &report(vec![]), now).is_empty());
+        thriving.high_stats_seconds = THRIVING_DAY_SECONDS;
+        assert_eq!(
+            earned(&thriving, &report(vec![]), now),
+            [Achievement::ThrivedForADay]
+        );
+
+        assert_eq!(
+            earned(&pet, &report(vec![TickEvent::Healed]), now),
+            [Achievement::RecoveredFromSickness]
+        );
+
+        let mut trained = Pet::new("TestPet".to_string());
+        trained.skills.agility = xp_for_level(WELL_TRAINED_LEVEL);
+        assert!(earned(&trained, &report(vec![]), now).is_empty());
+        trained.skills.intelligence = xp_for_level(WELL_TRAINED_LEVEL);
+        assert_eq!(
+            earned(&trained, &report(vec![]), now),
+            [Achievement::WellTrained]
+        );
+    }
+
+    #[test]
+    fn test_unlocked_achievements_are_not_announced_again() {
+        let mut pet = Pet::new("TestPet".to_string());
+        let healed = report(vec![TickEvent::Healed]);
+
+        assert_eq!(
+            pet.unlock_achievements(&healed, Utc::now()),
+            [Achievement::RecoveredFromSickness]
+        );
+        assert!(pet.unlock_achievements(&healed, Utc::now()).is_empty());
+        assert!(
+            pet.achievements
+                .contains(&Achievement::RecoveredFromSickness)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::pet::Action;
+use crate::term::AsciiMode;
+
+/// A short-lived art frame shown in response to a care action
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reaction {
+    Eating,
+    Happy,
+    ShakingOff,
+    Training,
+}
+
+impl Reaction {
+    pub fn for_action(action: Action) -> Option<Self> {
+        match action {
+            Action::Feed | Action::Snack | Action::Medicine => Some(Reaction::Eating),
+            Action::Wash | Action::Spa => Some(Reaction::ShakingOff),
+            Action::Play => Some(Reaction::Happy),
+            Action::Train => Some(Reaction::Training),
+            Action::Sleep | Action::Wake => None,
+        }
+    }
+
+    pub fn art(self, ascii: AsciiMode) -> Vec<&'static str> {
+        let (face, caption, ascii_caption) = match self {
+            Reaction::Eating => (r"     ( ^o^ )", "嚼嚼...", "nom nom..."),
+            Reaction::Happy => (r"     ( ^v^ )", "好开心!", "yay!"),
+            Reaction::ShakingOff => (r"    ~( >_< )~", "甩甩水~", "shake shake~"),
+            Reaction::Training => (r"     ( >o< )", "嘿哈!", "hup!"),
+        };
+        let ears = if self == Reaction::ShakingOff {
+            r"    ~/\_/\~"
+        } else {
+            r"     /\_/\"
+        };
+        let caption = if ascii.is_on() {
+            ascii_caption
+        } else {
+            caption
+        };
+        vec!["", ears, face, r"     > ^ <", caption]
+    }
+}
+
+/// How many ticks a reaction stays on screen
+pub const REACTION_TTL: u64 = 2;
+/// Reactions kept at once. Only the newest shows, so a burst of key
+/// presses within one tick drops the oldest.
+pub const MAX_QUEUED: usize = 8;
+
+#[derive(Debug, PartialEq)]
+struct QueuedReaction {
+    reaction: Reaction,
+    expires_at: u64,
+}
+
+/// Reactions waiting to be shown, oldest first
+#[derive(Debug, Default)]
+pub struct ReactionQueue {
+    entries: Vec<QueuedReaction>,
+}
+
+impl ReactionQueue {
+    pub fn push(&mut self, reaction: Reaction, tick: u64) {
+        self.prune(tick);
+        self.entries.push(QueuedReaction {
+            reaction,
+            expires_at: tick + REACTION_TTL,
+        });
+        if self.entries.len() > MAX_QUEUED {
+            let excess = self.entries.len() - MAX_QUEUED;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Drop every reaction that has expired by `tick`
+    pub fn prune(&mut self, tick: u64) {
+        self.entries.retain(|entry| entry.expires_at > tick);
+    }
+
+    pub fn current(&self, tick: u64) -> Option<Reaction> {
+        select_frame(&self.entries, tick)
+    }
+}
+
+/// The most recently queued reaction that is still alive at `tick`
+fn select_frame(entries: &[QueuedReaction], tick: u64) -> Option<Reaction> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| entry.expires_at > tick)
+        .map(|entry| entry.reaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaction_expires_after_ttl() {
+        let mut queue = ReactionQueue::default();
+        queue.push(Reaction::Eating, 10);
+
+        assert_eq!(queue.current(10), Some(Reaction::Eating));
+        assert_eq!(queue.current(10 + REACTION_TTL - 1), Some(Reaction::Eating));
+        assert_eq!(queue.current(10 + REACTION_TTL), None);
+    }
+
+    #[test]
+    fn test_burst_of_reactions_is_capped() {
+        let mut queue = ReactionQueue::default();
+        for _ in 0..100_000 {
+            queue.push(Reaction::Eating, 10);
+        }
+        queue.push(Reaction::Happy, 10);
+        assert_eq!(queue.entries.len(), MAX_QUEUED);
+        assert_eq!(queue.current(10), Some(Reaction::Happy));
+    }
+
+    #[test]
+    fn test_most_recent_reaction_wins() {
+        let mut queue = ReactionQueue::default();
+        queue.push(Reaction::Eating, 10);
+        queue.push(Reaction::Happy, 11);
+
+        assert_eq!(queue.current(11), Some(Reaction::Happy));
+    }
+
+    #[test]
+    fn test_older_reaction_shows_once_newer_expires() {
+        let entries = vec![
+            QueuedReaction {
+                reaction: Reaction::Training,
+                expires_at: 20,
+            },
+            QueuedReaction {
+                reaction: Reaction::ShakingOff,
+                expires_at: 15,
+            },
+        ];
+
+        assert_eq!(select_frame(&entries, 14), Some(Reaction::ShakingOff));
+        assert_eq!(select_frame(&entries, 15), Some(Reaction::Training));
+        assert_eq!(select_frame(&entries, 20), None);
+    }
+
+    #[test]
+    fn test_prune_removes_expired() {
+        let mut queue = ReactionQueue::default();
+        queue.push(Reaction::Eating, 0);
+        queue.push(Reaction::Happy, 5);
+
+        assert_eq!(queue.entries.len(), 1);
+        queue.prune(5 + REACTION_TTL);
+        assert!(queue.entries.is_empty());
+    }
+}
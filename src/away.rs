@@ -0,0 +1,140 @@
+use crate::i18n::Lang;
+use crate::pet::{Pet, PetStatus};
+
+/// How the pet changed between the last save and the end of offline
+/// catch-up. Stat deltas are `after - before`.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct AwayDiff {
+    pub health: i16,
+    pub hunger: i16,
+    pub cleanliness: i16,
+    pub mood: i16,
+    pub got_sick: bool,
+    pub ran_away: bool,
+    pub died: bool,
+    /// A pet that had run away was taken back
+    pub came_home: bool,
+}
+
+impl AwayDiff {
+    pub fn between(before: &Pet, after: &Pet) -> Self {
+        let delta = |before: u8, after: u8| after as i16 - before as i16;
+        Self {
+            health: delta(before.health, after.health),
+            hunger: delta(before.hunger, after.hunger),
+            cleanliness: delta(before.cleanliness, after.cleanliness),
+            mood: delta(before.mood, after.mood),
+            got_sick: before.status != PetStatus::Sick && after.status == PetStatus::Sick,
+            ran_away: before.status != PetStatus::Abandoned && after.status == PetStatus::Abandoned,
+            died: before.health > 0 && after.health == 0,
+            came_home: before.status == PetStatus::Abandoned
+                && after.status != PetStatus::Abandoned,
+        }
+    }
+
+    /// A line like "While away: hunger +40, mood -60, got sick", or `None`
+    /// if nothing changed
+    pub fn summary(&self, lang: Lang) -> Option<String> {
+        let parts = self.changes(lang);
+        if parts.is_empty() {
+            return None;
+        }
+        Some(match lang {
+            Lang::Zh => format!("离开期间: {}", parts.join(", ")),
+            Lang::En => format!("While away: {}", parts.join(", ")),
+        })
+    }
+
+    /// Each change on its own, like `["hunger +40", "got sick"]`
+    pub fn changes(&self, lang: Lang) -> Vec<String> {
+        let stats = match lang {
+            Lang::Zh => [
+                ("健康", self.health),
+                ("饥饿", self.hunger),
+                ("清洁", self.cleanliness),
+                ("心情", self.mood),
+            ],
+            Lang::En => [
+                ("health", self.health),
+                ("hunger", self.hunger),
+                ("cleanliness", self.cleanliness),
+                ("mood", self.mood),
+            ],
+        };
+        let mut parts: Vec<String> = stats
+            .into_iter()
+            .filter(|(_, delta)| *delta != 0)
+            .map(|(name, delta)| format!("{name} {delta:+}"))
+            .collect();
+        let (sick, ran_away, died, came_home) = match lang {
+            Lang::Zh => ("生病了", "离家出走了", "离开了", "回家了"),
+            Lang::En => ("got sick", "ran away", "passed away", "came home"),
+        };
+        for (happened, text) in [
+            (self.came_home, came_home),
+            (self.got_sick, sick),
+            (self.ran_away, ran_away),
+            (self.died, died),
+        ] {
+            if happened {
+                parts.push(text.to_string());
+            }
+        }
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reflects_before_and_after() {
+        let before = Pet::new("TestPet".to_string());
+        let mut after = before.clone();
+        after.hunger = before.hunger + 40;
+        after.mood = before.mood - 60;
+        after.status = PetStatus::Sick;
+
+        let diff = AwayDiff::between(&before, &after);
+        assert_eq!(
+            diff,
+            AwayDiff {
+                hunger: 40,
+                mood: -60,
+                got_sick: true,
+                ..AwayDiff::default()
+            }
+        );
+        assert_eq!(
+            diff.summary(Lang::En).unwrap(),
+            "While away: hunger +40, mood -60, got sick"
+        );
+        assert_eq!(
+            diff.summary(Lang::Zh).unwrap(),
+            "离开期间: 饥饿 +40, 心情 -60, 生病了"
+        );
+    }
+
+    #[test]
+    fn test_no_change_has_no_summary() {
+        let pet = Pet::new("TestPet".to_string());
+        let diff = AwayDiff::between(&pet, &pet);
+        assert!(diff.summary(Lang::En).is_none());
+    }
+
+    #[test]
+    fn test_homecoming_is_summarized() {
+        let mut before = Pet::new("TestPet".to_string());
+        before.status = PetStatus::Abandoned;
+        let mut after = before.clone();
+        after.come_home(chrono::Utc::now());
+
+        let diff = AwayDiff::between(&before, &after);
+        assert!(diff.came_home && !diff.ran_away);
+        assert_eq!(
+            diff.summary(Lang::En).unwrap(),
+            "While away: mood -80, came home"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+/// Rows every letter of the big font takes up
+pub const HEIGHT: usize = 3;
+
+/// The big font: each glyph is `HEIGHT` rows of the same width
+fn glyph(c: char) -> Option<[&'static str; HEIGHT]> {
+    let rows = match c.to_ascii_uppercase() {
+        'A' => [" _ ", "|_|", "| |"],
+        'B' => [" _ ", "|_)", "|_)"],
+        'C' => [" _ ", "|  ", "|_ "],
+        'D' => [" _ ", "| \\", "|_/"],
+        'E' => [" _ ", "|_ ", "|_ "],
+        'F' => [" _ ", "|_ ", "|  "],
+        'G' => [" __", "| _", "|_|"],
+        'H' => ["   ", "|_|", "| |"],
+        'I' => [" ", "|", "|"],
+        'J' => ["   ", "  |", "|_|"],
+        'K' => ["   ", "|/ ", "|\\ "],
+        'L' => ["   ", "|  ", "|_ "],
+        'M' => ["    ", "|\\/|", "|  |"],
+        'N' => ["    ", "|\\ |", "| \\|"],
+        'O' => [" _ ", "| |", "|_|"],
+        'P' => [" _ ", "|_)", "|  "],
+        'Q' => [" _ ", "| |", "|_\\"],
+        'R' => [" _ ", "|_)", "| \\"],
+        'S' => [" _ ", "(_ ", " _)"],
+        'T' => ["___", " | ", " | "],
+        'U' => ["   ", "| |", "|_|"],
+        'V' => ["   ", "\\ /", " V "],
+        'W' => ["    ", "|  |", "|/\\|"],
+        'X' => ["   ", "\\_/", "/ \\"],
+        'Y' => ["   ", "\\_/", " | "],
+        'Z' => ["__ ", " / ", "/__"],
+        '0' => [" _ ", "|/|", "|_|"],
+        '1' => ["   ", "/| ", " | "],
+        '2' => [" _ ", " _)", "/_ "],
+        '3' => ["__ ", " _)", "__)"],
+        '4' => ["   ", "|_|", "  |"],
+        '5' => [" __", "|_ ", " _)"],
+        '6' => [" _ ", "|_ ", "|_)"],
+        '7' => ["__ ", "  /", " / "],
+        '8' => [" _ ", "(_)", "(_)"],
+        '9' => [" _ ", "(_|", "  |"],
+        ' ' => ["  ", "  ", "  "],
+        '-' => ["  ", "__", "  "],
+        '_' => ["   ", "   ", "___"],
+        '.' => [" ", " ", "."],
+        '!' => [" ", "|", "."],
+        '\'' => ["|", " ", " "],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+/// `name` in big letters, `HEIGHT` rows of the same width, if every
+/// character is in the font and it fits in `width` columns
+pub fn big_text(name: &str, width: usize) -> Option<Vec<String>> {
+    let glyphs = name.chars().map(glyph).collect::<Option<Vec<_>>>()?;
+    if glyphs.is_empty() {
+        return None;
+    }
+    let rows: Vec<String> = (0..HEIGHT)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    (rows[0].len() <= width).then_some(rows)
+}
+
+/// The lines that head the pet's panel: `name` in big letters, or just
+/// the name when it won't go big in `width` columns
+pub fn name_lines(name: &str, width: usize) -> Vec<String> {
+    big_text(name, width).unwrap_or_else(|| vec![name.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters_line_up() {
+        assert_eq!(
+            big_text("Hi!", 80).unwrap(),
+            ["       ", "|_| | |", "| | | ."]
+        );
+        let lines = big_text("Mochi 2", 80).unwrap();
+        assert_eq!(lines.len(), HEIGHT);
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()));
+        // Case doesn't matter
+        assert_eq!(big_text("petty", 80), big_text("PETTY", 80));
+    }
+
+    #[test]
+    fn test_too_wide_or_unknown_falls_back_to_the_name() {
+        let width = big_text("Petty", 80).unwrap()[0].len();
+        assert!(big_text("Petty", width).is_some());
+        assert_eq!(big_text("Petty", width - 1), None);
+        assert_eq!(name_lines("Petty", width - 1), ["Petty"]);
+        assert_eq!(name_lines("小白", 80), ["小白"]);
+        assert_eq!(name_lines("", 80), [""]);
+        assert_eq!(name_lines("Petty", width).len(), HEIGHT);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::pet::Action;
+
+/// Bond one heart stands for
+pub const BOND_PER_HEART: u32 = 100;
+/// Hearts in a full row
+pub const MAX_HEARTS: u8 = 5;
+/// Bond stops growing once every heart is filled
+pub const MAX_BOND: u32 = BOND_PER_HEART * MAX_HEARTS as u32;
+/// Bond from one care action
+pub const BOND_PER_CARE: u32 = 1;
+/// Seconds in a row of high stats that earn an extra point of bond
+pub const BOND_STREAK_SECONDS: u32 = 600;
+/// Bond from a work interval finished in `petty focus`
+pub const BOND_PER_POMODORO: u32 = 5;
+
+/// Bond `action` earns; sleeping and waking up don't count as care
+pub fn earned_by(action: Action) -> u32 {
+    if matches!(action, Action::Sleep | Action::Wake) {
+        0
+    } else {
+        BOND_PER_CARE
+    }
+}
+
+/// Filled hearts for `bond`, at most `MAX_HEARTS`
+pub fn hearts(bond: u32) -> u8 {
+    (bond.min(MAX_BOND) / BOND_PER_HEART) as u8
+}
+
+/// A row like `♥♥♡♡♡`, or `[**---]` in ASCII mode
+pub fn heart_row(bond: u32, ascii: bool) -> String {
+    let filled = hearts(bond).into();
+    let empty = (MAX_HEARTS - hearts(bond)).into();
+    if ascii {
+        format!("[{}{}]", "*".repeat(filled), "-".repeat(empty))
+    } else {
+        format!("{}{}", "♥".repeat(filled), "♡".repeat(empty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heart_row_maps_bond_to_hearts() {
+        assert_eq!(hearts(0), 0);
+        assert_eq!(hearts(BOND_PER_HEART - 1), 0);
+        assert_eq!(hearts(BOND_PER_HEART), 1);
+        assert_eq!(hearts(u32::MAX), MAX_HEARTS);
+        assert_eq!(heart_row(2 * BOND_PER_HEART + 50, false), "♥♥♡♡♡");
+        assert_eq!(heart_row(2 * BOND_PER_HEART + 50, true), "[**---]");
+        assert_eq!(heart_row(MAX_BOND, true), "[*****]");
+    }
+}
@@ -0,0 +1,172 @@
+use crate::config::{Calendar, Holiday};
+use crate::i18n::Lang;
+use crate::pet::{Pet, PetStatus};
+use chrono::{Datelike, NaiveDate, TimeDelta};
+
+/// Something the calendar has on for a day
+#[derive(Clone, Debug, PartialEq)]
+pub enum Occasion<'a> {
+    /// The pet was adopted `years` years ago today
+    Birthday {
+        years: i32,
+    },
+    Holiday(&'a Holiday),
+}
+
+/// Everything on for `date` for a pet adopted on `birth`, birthday first.
+/// Nothing's on with the calendar turned off.
+pub fn occasions(date: NaiveDate, birth: NaiveDate, calendar: &Calendar) -> Vec<Occasion<'_>> {
+    let mut occasions = Vec::new();
+    if !calendar.enabled {
+        return occasions;
+    }
+    let years = date.year() - birth.year();
+    if years > 0 && anniversary(birth, date.year()) == date {
+        occasions.push(Occasion::Birthday { years });
+    }
+    occasions.extend(
+        calendar
+            .holidays
+            .iter()
+            .filter(|holiday| is_during(holiday, date))
+            .map(Occasion::Holiday),
+    );
+    occasions
+}
+
+/// The pet's birthday in `year`. Pets adopted on February 29th have theirs
+/// on March 1st outside leap years.
+pub fn anniversary(birth: NaiveDate, year: i32) -> NaiveDate {
+    birth
+        .with_year(year)
+        .or_else(|| NaiveDate::from_ymd_opt(year, 3, 1))
+        .unwrap_or(birth)
+}
+
+/// Whether `date` falls in `holiday`, counting one that started late last
+/// year and runs into this one
+fn is_during(holiday: &Holiday, date: NaiveDate) -> bool {
+    [date.year(), date.year() - 1].into_iter().any(|year| {
+        NaiveDate::from_ymd_opt(year, holiday.month, holiday.day).is_some_and(|start| {
+            date >= start && date < start + TimeDelta::days(holiday.days.into())
+        })
+    })
+}
+
+/// What to draw above the pet for `occasions`, if anything
+pub fn decoration(occasions: &[Occasion], ascii: bool) -> Option<String> {
+    occasions.first().map(|occasion| match occasion {
+        Occasion::Birthday { .. } if ascii => "~ Happy birthday! ~".to_string(),
+        Occasion::Birthday { .. } => "🎂 生日快乐! 🎂".to_string(),
+        Occasion::Holiday(holiday) if ascii => holiday.ascii.clone(),
+        Occasion::Holiday(holiday) => holiday.decoration.clone(),
+    })
+}
+
+/// Give the pet its birthday bonus if `today` is its birthday and it
+/// hasn't had this year's yet. Returns how old it turned.
+pub fn celebrate(pet: &mut Pet, today: NaiveDate, calendar: &Calendar) -> Option<i32> {
+    if matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired) || pet.health == 0 {
+        return None;
+    }
+    let years = occasions(today, pet.birth.date_naive(), calendar)
+        .iter()
+        .find_map(|occasion| match occasion {
+            Occasion::Birthday { years } => Some(*years),
+            Occasion::Holiday(_) => None,
+        })?;
+    if pet.birthday_celebrated == Some(today.year()) {
+        return None;
+    }
+    pet.birthday_celebrated = Some(today.year());
+    pet.mood = pet.mood.saturating_add(calendar.birthday_mood).min(100);
+    pet.coins = pet.coins.saturating_add(calendar.birthday_coins);
+    Some(years)
+}
+
+/// The overlay for a birthday just celebrated
+pub fn birthday_text(name: &str, years: i32, lang: Lang) -> String {
+    match lang {
+        Lang::Zh => format!("🎂 {name} 来到你身边 {years} 周年啦! 心情大好, 还收到了金币。"),
+        Lang::En => format!("{name} has been with you {years} year(s) today! Happy birthday!"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+    use chrono::{TimeZone, Utc};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_birthday_fires_on_the_anniversary() {
+        let calendar = Calendar::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.birth = Utc.with_ymd_and_hms(2024, 6, 15, 9, 0, 0).unwrap();
+        pet.mood = 50;
+        let birth = pet.birth.date_naive();
+
+        assert!(occasions(date(2024, 6, 15), birth, &calendar).is_empty());
+        assert!(occasions(date(2025, 6, 14), birth, &calendar).is_empty());
+        assert_eq!(
+            occasions(date(2026, 6, 15), birth, &calendar),
+            vec![Occasion::Birthday { years: 2 }]
+        );
+
+        assert_eq!(celebrate(&mut pet, date(2025, 6, 14), &calendar), None);
+        assert_eq!(celebrate(&mut pet, date(2025, 6, 15), &calendar), Some(1));
+        assert_eq!((pet.mood, pet.coins), (70, 10));
+        // Only once a year, however many times petty is opened that day
+        assert_eq!(celebrate(&mut pet, date(2025, 6, 15), &calendar), None);
+        assert_eq!(pet.mood, 70);
+        assert_eq!(celebrate(&mut pet, date(2026, 6, 15), &calendar), Some(2));
+
+        // Leap day pets make do with March 1st
+        let leap = date(2024, 2, 29);
+        assert_eq!(anniversary(leap, 2025), date(2025, 3, 1));
+        assert_eq!(anniversary(leap, 2028), leap.with_year(2028).unwrap());
+
+        let off = Calendar {
+            enabled: false,
+            ..Calendar::default()
+        };
+        assert!(occasions(date(2026, 6, 15), birth, &off).is_empty());
+    }
+
+    #[test]
+    fn test_festive_art_on_configured_dates() {
+        let birth = date(2024, 6, 15);
+        let calendar = Calendar::default();
+        let christmas = occasions(date(2025, 12, 25), birth, &calendar);
+        assert!(decoration(&christmas, false).unwrap().contains("圣诞"));
+        assert!(decoration(&christmas, true).unwrap().contains("Christmas"));
+        assert!(occasions(date(2025, 12, 26), birth, &calendar).is_empty());
+        assert_eq!(decoration(&[], false), None);
+
+        let config = parse_config(
+            "[[calendar.holidays]]\n\
+             name = \"Tea Day\"\n\
+             month = 12\n\
+             day = 31\n\
+             days = 2\n\
+             decoration = \"🍵\"\n\
+             ascii = \"c[_]\"\n",
+        )
+        .unwrap();
+        let tea = &config.calendar.holidays[0];
+        // Runs into the new year, and replaces the built-in holidays
+        assert_eq!(
+            occasions(date(2026, 1, 1), birth, &config.calendar),
+            vec![Occasion::Holiday(tea)]
+        );
+        assert!(occasions(date(2025, 12, 25), birth, &config.calendar).is_empty());
+        // A birthday takes the art over a holiday
+        let both = occasions(date(2025, 12, 25), date(2024, 12, 25), &calendar);
+        assert_eq!(both.len(), 2);
+        assert_eq!(decoration(&both, true).unwrap(), "~ Happy birthday! ~");
+    }
+}
@@ -0,0 +1,134 @@
+use crate::bond;
+use crate::config::Config;
+use crate::display::{Stat, format_stat};
+use crate::i18n::Lang;
+use crate::pet::Pet;
+use crate::term::AsciiMode;
+use crate::ui::pet_art;
+use ratatui::text::Line;
+
+/// The pet as plain text for pasting into chats: its art above its stats,
+/// all in one box
+pub fn card_lines(pet: &Pet, config: &Config, ascii: AsciiMode) -> Vec<String> {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let stat = |stat, value| format_stat(stat, value, config.display_mode, lang);
+
+    let art = pet_art(pet, None, 0, ascii, false);
+    // Centered line by line like in the pet panel, leading spaces and all
+    let mut art: Vec<&str> = art.iter().map(|line| line.trim_end()).collect();
+    while art.last().is_some_and(|line| line.is_empty()) {
+        art.pop();
+    }
+    let skip = art.iter().take_while(|line| line.is_empty()).count();
+
+    let mut stats = vec![
+        format!("{}, age {} ({})", pet.name, pet.age, pet.life_stage()),
+        format!(
+            "Health: {}  Hunger: {}",
+            stat(Stat::Health, pet.health),
+            stat(Stat::Hunger, pet.hunger)
+        ),
+        format!(
+            "Clean: {}  Mood: {}",
+            stat(Stat::Cleanliness, pet.cleanliness),
+            stat(Stat::Mood, pet.mood)
+        ),
+    ];
+    if config.show_bond {
+        stats.push(format!(
+            "Bond: {}",
+            bond::heart_row(pet.bond, ascii.is_on())
+        ));
+    }
+
+    let body: Vec<String> = art[skip..]
+        .iter()
+        .map(|line| centered(line, &stats))
+        .chain([String::new()])
+        .chain(stats.iter().cloned())
+        .collect();
+    framed(&body, ascii)
+}
+
+/// `line` padded on the left to sit in the middle of the widest stat line
+fn centered(line: &str, stats: &[String]) -> String {
+    let width = stats.iter().map(|stat| width_of(stat)).max().unwrap_or(0);
+    let padding = width.saturating_sub(width_of(line)) / 2;
+    format!("{}{line}", " ".repeat(padding))
+}
+
+/// `lines` in a box, with ASCII corners and edges in ASCII mode
+fn framed(lines: &[String], ascii: AsciiMode) -> Vec<String> {
+    let [top_left, top_right, bottom_left, bottom_right, flat, side] = if ascii.is_on() {
+        ["+", "+", "+", "+", "-", "|"]
+    } else {
+        ["╭", "╮", "╰", "╯", "─", "│"]
+    };
+    let inner = lines.iter().map(|line| width_of(line)).max().unwrap_or(0) + 2;
+    let mut framed = vec![format!("{top_left}{}{top_right}", flat.repeat(inner))];
+    framed.extend(lines.iter().map(|line| {
+        let padding = " ".repeat(inner - 1 - width_of(line));
+        format!("{side} {line}{padding}{side}")
+    }));
+    framed.push(format!("{bottom_left}{}{bottom_right}", flat.repeat(inner)));
+    framed
+}
+
+/// Columns `line` takes up; CJK text is two columns a character
+fn width_of(line: &str) -> usize {
+    Line::raw(line).width()
+}
+
+/// Put `text` on the system clipboard. Needs the `clipboard` build feature.
+#[cfg(feature = "clipboard")]
+pub fn copy(text: String) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: String) -> Result<(), String> {
+    Err("this build has no clipboard support (rebuild with `--features clipboard`)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pet::PetStatus;
+
+    #[test]
+    fn test_card_shows_the_art_and_stats_in_a_box() {
+        let mut pet = Pet::new("Mochi".to_string());
+        (pet.age, pet.hunger, pet.bond) = (12, 30, 250);
+        let card = card_lines(&pet, &Config::default(), AsciiMode(true));
+
+        assert!(card.first().unwrap().starts_with("+--"));
+        assert!(card.last().unwrap().starts_with("+--"));
+        let width = card[0].len();
+        assert!(card.iter().all(|line| line.len() == width));
+        assert!(card.iter().all(|line| line.is_ascii()));
+        for expected in [
+            "| Mochi, age 12 (young)",
+            "| Health: 100  Hunger: 30",
+            "| Bond: [**---]",
+        ] {
+            assert!(
+                card.iter().any(|line| line.starts_with(expected)),
+                "{expected} not in {card:#?}"
+            );
+        }
+        // The art sits above the stats
+        let name_row = card.iter().position(|line| line.contains("Mochi")).unwrap();
+        assert!(card[1..name_row].iter().any(|line| line.contains("( ")));
+    }
+
+    #[test]
+    fn test_card_lines_up_wide_characters() {
+        let mut pet = Pet::new("Mochi".to_string());
+        pet.status = PetStatus::Sick;
+        let card = card_lines(&pet, &Config::default(), AsciiMode(false));
+        let width = width_of(&card[0]);
+        assert!(card.iter().all(|line| width_of(line) == width));
+        assert!(card.iter().any(|line| line.contains("生病了")));
+    }
+}
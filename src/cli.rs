@@ -0,0 +1,578 @@
+use crate::daemon::Request;
+use crate::term::ColorChoice;
+use anyhow::{Result, anyhow, bail};
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Run the pet UI, optionally naming a newly created pet
+    Run { name: Option<String> },
+    /// Render a theme's colors and exit
+    ThemePreview { theme: Option<String> },
+    /// Run the simulation headless, streaming ticks as JSON lines
+    Watch,
+    /// Keep the pet alive in the background, taking care requests over a
+    /// socket; see `daemon`
+    Daemon,
+    /// Hand `request` to the running daemon, print how the pet is doing
+    /// and exit
+    Send { request: Request },
+    /// Print the pet's diary and exit
+    Diary,
+    /// List achievements and exit
+    Achievements,
+    /// Show the life-stage snapshots and exit
+    Gallery,
+    /// Catch the pet up, reset the abandonment clock and exit
+    Checkin,
+    /// Adopt a companion for the saved pet and exit
+    Companion { name: String },
+    /// Write the saved state to stdout as JSON and exit
+    ExportState,
+    /// Replace the saved state with JSON read from stdin and exit
+    ImportState,
+    /// Show every saved pet and exit
+    List,
+    /// Make a pet the one that launches by default and exit
+    Use { name: String },
+    /// Put the pet card on the clipboard and exit
+    Copy,
+    /// Run the pet UI with a pomodoro timer; lengths are in seconds and
+    /// default to the `[focus]` config
+    Focus {
+        work: Option<u64>,
+        rest: Option<u64>,
+    },
+    /// Play the pet's life back from its log; `speed` is seconds of its
+    /// life per second and defaults to `replay::DEFAULT_SPEED`
+    Replay { speed: Option<u64> },
+    /// Print a one-line status for tmux or a shell prompt and exit;
+    /// `format` overrides the config's `status_line`
+    Line { format: Option<String> },
+    /// Teach each care action in turn on a throwaway pet, leaving the
+    /// real save alone
+    Tutorial,
+    /// Put the save back as it was before the last session and exit;
+    /// `yes` skips asking first
+    Restore { yes: bool },
+    /// Check that the config and the save load, report what's wrong with
+    /// them and exit
+    Validate,
+    /// Print every tuning constant in effect as JSON and exit. Not in the
+    /// README; it's for balancing and bug reports.
+    Constants,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub command: Command,
+    /// Watch the pet without saving or accepting care actions
+    pub readonly: bool,
+    /// Draw with plain ASCII even if the terminal looks UTF-8 capable
+    pub ascii: bool,
+    /// Turn off animations and transient alerts
+    pub reduced_motion: bool,
+    /// Stack the pet above its stats even on a wide terminal
+    pub compact: bool,
+    /// Draw under the prompt and leave the last frame in the scrollback
+    pub inline: bool,
+    /// Whether the plain-text subcommands color their output
+    pub color: ColorChoice,
+    /// `[profiles.<name>]` in the config to use, from `--config-profile`
+    pub config_profile: Option<String>,
+}
+
+/// Parse command line arguments, excluding the program name
+pub fn parse_args(args: &[String]) -> Result<Cli> {
+    let mut readonly = false;
+    let mut ascii = false;
+    let mut reduced_motion = false;
+    let mut compact = false;
+    let mut inline = false;
+    let mut color = ColorChoice::default();
+    let mut jsonl = false;
+    let mut json = false;
+    let mut work = None;
+    let mut rest = None;
+    let mut speed = None;
+    let mut format = None;
+    let mut config_profile = None;
+    let mut yes = false;
+    let mut transfer = Vec::new();
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            flag @ ("--work" | "--break") => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{flag} needs a length like 25m"))?;
+                let seconds = parse_duration(value)
+                    .ok_or_else(|| anyhow!("bad {flag} length: {value} (try 25m, 90s or 1h)"))?;
+                if flag == "--work" {
+                    work = Some(seconds);
+                } else {
+                    rest = Some(seconds);
+                }
+            }
+            "--speed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--speed needs a number like 3600"))?;
+                match value.parse::<u64>() {
+                    Ok(value) if value > 0 => speed = Some(value),
+                    _ => bail!("bad --speed: {value} (seconds of the pet's life per second)"),
+                }
+            }
+            "--format" => {
+                let value = args.next().ok_or_else(|| {
+                    anyhow!("--format needs a format like \"{{name}} H{{health}}\"")
+                })?;
+                format = Some(value.clone());
+            }
+            "--config-profile" => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--config-profile needs the name of a profile"))?;
+                config_profile = Some(name.clone());
+            }
+            "--readonly" => readonly = true,
+            "--ascii" => ascii = true,
+            "--reduced-motion" => reduced_motion = true,
+            "--compact" => compact = true,
+            "--inline" => inline = true,
+            "--jsonl" => jsonl = true,
+            "--json" => json = true,
+            "--yes" => yes = true,
+            "--export-state" => transfer.push(Command::ExportState),
+            "--import-state" => transfer.push(Command::ImportState),
+            flag if flag.starts_with("--color=") => {
+                let value = &flag["--color=".len()..];
+                color = ColorChoice::parse(value).ok_or_else(|| {
+                    anyhow!("unknown --color value: {value} (use auto, always or never)")
+                })?;
+            }
+            flag if flag.starts_with("--") => bail!("unknown option: {flag}"),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    if !transfer.is_empty() {
+        if transfer.len() > 1 || !positional.is_empty() || jsonl || json {
+            bail!("--export-state and --import-state can't be combined with other commands");
+        }
+        return Ok(Cli {
+            command: transfer.remove(0),
+            readonly,
+            ascii,
+            reduced_motion,
+            compact,
+            inline,
+            color,
+            config_profile,
+        });
+    }
+
+    let command = match positional.first().map(String::as_str) {
+        None => Command::Run { name: None },
+        Some("theme-preview") => match positional.as_slice() {
+            [_] => Command::ThemePreview { theme: None },
+            [_, theme] => Command::ThemePreview {
+                theme: Some(theme.clone()),
+            },
+            _ => bail!("usage: petty theme-preview [name]"),
+        },
+        Some("diary") => match positional.as_slice() {
+            [_] => Command::Diary,
+            _ => bail!("usage: petty diary"),
+        },
+        Some("achievements") => match positional.as_slice() {
+            [_] => Command::Achievements,
+            _ => bail!("usage: petty achievements"),
+        },
+        Some("gallery") => match positional.as_slice() {
+            [_] => Command::Gallery,
+            _ => bail!("usage: petty gallery"),
+        },
+        Some("checkin") => match positional.as_slice() {
+            [_] => Command::Checkin,
+            _ => bail!("usage: petty checkin"),
+        },
+        Some("companion") => match positional.as_slice() {
+            [_, name] => Command::Companion { name: name.clone() },
+            _ => bail!("usage: petty companion <name>"),
+        },
+        Some("list") => match positional.as_slice() {
+            [_] => Command::List,
+            _ => bail!("usage: petty list"),
+        },
+        Some("use") => match positional.as_slice() {
+            [_, name] => Command::Use { name: name.clone() },
+            _ => bail!("usage: petty use <name>"),
+        },
+        Some("copy") => match positional.as_slice() {
+            [_] => Command::Copy,
+            _ => bail!("usage: petty copy"),
+        },
+        Some("focus") => match positional.as_slice() {
+            [_] => Command::Focus { work, rest },
+            _ => bail!("usage: petty focus [--work 25m] [--break 5m]"),
+        },
+        Some("replay") => match positional.as_slice() {
+            [_] => Command::Replay { speed },
+            _ => bail!("usage: petty replay [--speed 3600]"),
+        },
+        Some("line") => match positional.as_slice() {
+            [_] => Command::Line {
+                format: format.clone(),
+            },
+            _ => bail!("usage: petty line [--format \"{{face}} {{name}} H{{health}}\"]"),
+        },
+        Some("daemon") => match positional.as_slice() {
+            [_] if !readonly => Command::Daemon,
+            [_] => bail!("--readonly can't be used with `petty daemon`"),
+            [_, word] if word == "status" || word == "stop" => Command::Send {
+                request: Request::parse(word).map_err(|e| anyhow!(e))?,
+            },
+            _ => bail!("usage: petty daemon [status|stop]"),
+        },
+        Some("validate") => match positional.as_slice() {
+            [_] => Command::Validate,
+            _ => bail!("usage: petty validate"),
+        },
+        Some("tutorial") => match positional.as_slice() {
+            [_] => Command::Tutorial,
+            _ => bail!("usage: petty tutorial"),
+        },
+        Some("restore") => match positional.as_slice() {
+            [_] => Command::Restore { yes },
+            _ => bail!("usage: petty restore [--yes]"),
+        },
+        Some("watch") => match positional.as_slice() {
+            // JSON lines is the only output format for now
+            [_] if jsonl => Command::Watch,
+            _ => bail!("usage: petty watch --jsonl"),
+        },
+        Some("constants") => match positional.as_slice() {
+            [_] if json => Command::Constants,
+            _ => bail!("usage: petty constants --json"),
+        },
+        // Care for the daemon's pet, like `petty feed`
+        Some(word) => match (Request::parse(word), positional.as_slice()) {
+            (Ok(request @ Request::Act(_)), [_]) if request.encode() == word => {
+                Command::Send { request }
+            }
+            _ => Command::Run {
+                name: Some(word.to_string()),
+            },
+        },
+    };
+
+    if jsonl && command != Command::Watch {
+        bail!("--jsonl is only valid with `petty watch`");
+    }
+    if json && command != Command::Constants {
+        bail!("--json is only valid with `petty constants`");
+    }
+    if (work.is_some() || rest.is_some()) && !matches!(command, Command::Focus { .. }) {
+        bail!("--work and --break are only valid with `petty focus`");
+    }
+    if format.is_some() && !matches!(command, Command::Line { .. }) {
+        bail!("--format is only valid with `petty line`");
+    }
+    if yes && !matches!(command, Command::Restore { .. }) {
+        bail!("--yes is only valid with `petty restore`");
+    }
+    if speed.is_some() && !matches!(command, Command::Replay { .. }) {
+        bail!("--speed is only valid with `petty replay`");
+    }
+    if work == Some(0) {
+        bail!("--work must be longer than 0");
+    }
+
+    Ok(Cli {
+        command,
+        readonly,
+        ascii,
+        reduced_motion,
+        compact,
+        inline,
+        color,
+        config_profile,
+    })
+}
+
+/// Seconds in a length like `25m`, `90s` or `1h`; a bare number is minutes
+fn parse_duration(value: &str) -> Option<u64> {
+    let (number, unit) = match value.char_indices().last()? {
+        (at, 's') => (&value[..at], 1),
+        (at, 'm') => (&value[..at], 60),
+        (at, 'h') => (&value[..at], 3600),
+        _ => (value, 60),
+    };
+    number.parse::<u64>().ok()?.checked_mul(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(list: &[&str]) -> Result<Cli> {
+        let args: Vec<String> = list.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(parse(&[]).unwrap().command, Command::Run { name: None });
+        assert_eq!(
+            parse(&["Fluffy"]).unwrap().command,
+            Command::Run {
+                name: Some("Fluffy".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_theme_preview() {
+        assert_eq!(
+            parse(&["theme-preview"]).unwrap().command,
+            Command::ThemePreview { theme: None }
+        );
+        assert_eq!(
+            parse(&["theme-preview", "ocean"]).unwrap().command,
+            Command::ThemePreview {
+                theme: Some("ocean".to_string())
+            }
+        );
+        assert!(parse(&["theme-preview", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_diary() {
+        assert_eq!(parse(&["diary"]).unwrap().command, Command::Diary);
+        assert!(parse(&["diary", "extra"]).is_err());
+        assert_eq!(
+            parse(&["achievements"]).unwrap().command,
+            Command::Achievements
+        );
+        assert_eq!(parse(&["gallery"]).unwrap().command, Command::Gallery);
+        assert!(parse(&["gallery", "extra"]).is_err());
+        assert_eq!(parse(&["checkin"]).unwrap().command, Command::Checkin);
+        assert_eq!(parse(&["copy"]).unwrap().command, Command::Copy);
+        assert!(parse(&["copy", "extra"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_companion() {
+        assert_eq!(
+            parse(&["companion", "Buddy"]).unwrap().command,
+            Command::Companion {
+                name: "Buddy".to_string()
+            }
+        );
+        assert!(parse(&["companion"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_list_and_use() {
+        assert_eq!(parse(&["list"]).unwrap().command, Command::List);
+        assert!(parse(&["list", "extra"]).is_err());
+        assert_eq!(
+            parse(&["use", "Buddy"]).unwrap().command,
+            Command::Use {
+                name: "Buddy".to_string()
+            }
+        );
+        assert!(parse(&["use"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_focus() {
+        assert_eq!(
+            parse(&["focus"]).unwrap().command,
+            Command::Focus {
+                work: None,
+                rest: None
+            }
+        );
+        assert_eq!(
+            parse(&["focus", "--work", "50m", "--break", "90s"])
+                .unwrap()
+                .command,
+            Command::Focus {
+                work: Some(3000),
+                rest: Some(90)
+            }
+        );
+        assert_eq!(
+            parse(&["--work", "1h", "focus"]).unwrap().command,
+            Command::Focus {
+                work: Some(3600),
+                rest: None
+            }
+        );
+        assert!(parse(&["focus", "--work"]).is_err());
+        assert!(parse(&["focus", "--work", "soon"]).is_err());
+        assert!(parse(&["focus", "--work", "0m"]).is_err());
+        assert!(parse(&["--break", "5m"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(
+            parse(&["watch", "--jsonl"]).unwrap().command,
+            Command::Watch
+        );
+        assert!(parse(&["watch"]).is_err());
+        assert!(parse(&["--jsonl"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_replay() {
+        assert_eq!(
+            parse(&["replay"]).unwrap().command,
+            Command::Replay { speed: None }
+        );
+        assert_eq!(
+            parse(&["replay", "--speed", "60"]).unwrap().command,
+            Command::Replay { speed: Some(60) }
+        );
+        assert!(parse(&["replay", "--speed", "0"]).is_err());
+        assert!(parse(&["replay", "--speed"]).is_err());
+        assert!(parse(&["--speed", "60"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_profile() {
+        let cli = parse(&["--config-profile", "strict", "diary"]).unwrap();
+        assert_eq!(cli.config_profile.as_deref(), Some("strict"));
+        assert_eq!(cli.command, Command::Diary);
+        assert_eq!(parse(&[]).unwrap().config_profile, None);
+        assert!(parse(&["--config-profile"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse(&["line"]).unwrap().command,
+            Command::Line { format: None }
+        );
+        assert_eq!(
+            parse(&["line", "--format", "{name} M{mood}"])
+                .unwrap()
+                .command,
+            Command::Line {
+                format: Some("{name} M{mood}".to_string())
+            }
+        );
+        assert!(parse(&["line", "--format"]).is_err());
+        assert!(parse(&["--format", "{name}"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_tutorial() {
+        assert_eq!(parse(&["tutorial"]).unwrap().command, Command::Tutorial);
+        assert!(parse(&["tutorial", "extra"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_daemon() {
+        assert_eq!(parse(&["daemon"]).unwrap().command, Command::Daemon);
+        assert!(parse(&["daemon", "extra"]).is_err());
+        assert!(parse(&["daemon", "--readonly"]).is_err());
+        assert_eq!(
+            parse(&["daemon", "stop"]).unwrap().command,
+            Command::Send {
+                request: Request::Stop
+            }
+        );
+        assert!(parse(&["daemon", "feed"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_care_for_the_daemon() {
+        for word in [
+            "feed", "wash", "play", "train", "sleep", "wake", "spa", "medicine", "snack",
+        ] {
+            let request = Request::parse(word).unwrap();
+            assert!(matches!(request, Request::Act(_)));
+            assert_eq!(parse(&[word]).unwrap().command, Command::Send { request });
+        }
+        // Anything else is still a name for a new pet
+        assert_eq!(
+            parse(&["Feed"]).unwrap().command,
+            Command::Run {
+                name: Some("Feed".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_validate() {
+        assert_eq!(parse(&["validate"]).unwrap().command, Command::Validate);
+        assert!(parse(&["validate", "config.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_restore() {
+        assert_eq!(
+            parse(&["restore"]).unwrap().command,
+            Command::Restore { yes: false }
+        );
+        assert_eq!(
+            parse(&["restore", "--yes"]).unwrap().command,
+            Command::Restore { yes: true }
+        );
+        assert!(parse(&["restore", "extra"]).is_err());
+        assert!(parse(&["--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_constants() {
+        assert_eq!(
+            parse(&["constants", "--json"]).unwrap().command,
+            Command::Constants
+        );
+        assert!(parse(&["constants"]).is_err());
+        assert!(parse(&["watch", "--json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_state_transfer() {
+        assert_eq!(
+            parse(&["--export-state"]).unwrap().command,
+            Command::ExportState
+        );
+        assert_eq!(
+            parse(&["--import-state"]).unwrap().command,
+            Command::ImportState
+        );
+        assert!(parse(&["--export-state", "--import-state"]).is_err());
+        assert!(parse(&["--import-state", "diary"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        let cli = parse(&["--readonly", "Fluffy"]).unwrap();
+        assert!(cli.readonly);
+        assert_eq!(
+            cli.command,
+            Command::Run {
+                name: Some("Fluffy".to_string())
+            }
+        );
+        assert!(!parse(&[]).unwrap().readonly);
+        assert!(parse(&["theme-preview", "--ascii"]).unwrap().ascii);
+        assert!(parse(&["--reduced-motion"]).unwrap().reduced_motion);
+        assert!(parse(&["--compact"]).unwrap().compact);
+        assert!(parse(&["focus", "--inline"]).unwrap().inline);
+        assert_eq!(parse(&[]).unwrap().color, ColorChoice::Auto);
+        assert_eq!(
+            parse(&["diary", "--color=never"]).unwrap().color,
+            ColorChoice::Never
+        );
+        assert!(parse(&["--color=rainbow"]).is_err());
+        assert!(parse(&["--nope"]).is_err());
+    }
+}
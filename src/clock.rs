@@ -0,0 +1,56 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use std::cell::Cell;
+
+/// Where the current time comes from, so the rules that depend on it can
+/// be run against a time of the caller's choosing
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The wall clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that stays at one instant until it's moved
+#[derive(Clone, Debug)]
+pub struct FixedClock(Cell<DateTime<Utc>>);
+
+impl FixedClock {
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self(Cell::new(at))
+    }
+
+    /// Move the clock by `delta`, backwards if it's negative
+    pub fn advance(&self, delta: TimeDelta) {
+        self.0.set(self.0.get() + delta);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock_only_moves_when_told() {
+        let start = Utc::now() - TimeDelta::days(30);
+        let clock = FixedClock::new(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now(), start);
+        clock.advance(TimeDelta::minutes(5));
+        assert_eq!(clock.now(), start + TimeDelta::minutes(5));
+        clock.advance(TimeDelta::minutes(-10));
+        assert_eq!(clock.now(), start - TimeDelta::minutes(5));
+    }
+}
@@ -0,0 +1,1575 @@
+use crate::display::DisplayMode;
+use crate::error::{self, Error};
+use crate::i18n::Lang;
+use crate::pet::{Action, RECOVERED_HEALTH};
+use crate::sound;
+use crate::state::{DEFAULT_MAX_STATE_MB, StateFormat, get_data_dir};
+use crate::statusline;
+use crate::theme::Theme;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// User configuration read from `~/.petty/config.toml`. Every field is
+/// optional so an empty or missing file gives the default behavior.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Name of the active theme, either a builtin or one from `themes`
+    pub theme: String,
+    pub themes: HashMap<String, Theme>,
+    pub weights: NeedWeights,
+    pub rules: Rules,
+    pub cooldowns: ActionCooldowns,
+    pub sounds: Sounds,
+    pub caretaker: Caretaker,
+    pub thresholds: Thresholds,
+    pub focus: FocusRules,
+    pub retirement: Retirement,
+    pub aging: AgingRules,
+    pub overstimulation: Overstimulation,
+    pub sulking: Sulking,
+    pub host_load: HostLoad,
+    pub calendar: Calendar,
+    pub medicine: Medicine,
+    pub loneliness: Loneliness,
+    pub variety: Variety,
+    pub personality: Personality,
+    pub growth_spurts: GrowthSpurts,
+    pub meals: Meals,
+    /// Language for generated text such as the diary
+    pub lang: Lang,
+    /// Show stats as numbers, percentages or words
+    pub display_mode: DisplayMode,
+    /// Start with the radar chart under the stats; `r` toggles it
+    pub radar_chart: bool,
+    /// Show a line of what the pet is thinking under its art
+    pub pet_talks: bool,
+    /// Let a passing look like "getting hungry..." show which way the
+    /// pet's stats are heading
+    pub pet_emotes: bool,
+    /// Same as `--reduced-motion`: no animations, and alerts stay put
+    pub reduced_motion: bool,
+    /// Send a desktop notification when health becomes critical. Needs
+    /// the `desktop-notify` build feature.
+    pub desktop_notify: bool,
+    /// Encoding of the save file
+    pub format: StateFormat,
+    /// Save somewhere other than `~/.petty`; the extension picks the format
+    pub state_file: Option<PathBuf>,
+    /// Hold `state.json.lock` while running so a second instance can't
+    /// overwrite the same save
+    pub lock: bool,
+    /// Save right after every care action instead of only on exit
+    pub save_on_action: bool,
+    /// Save every this many seconds while playing so a crash loses little
+    /// and offline catch-up doesn't replay time already played; 0 disables
+    pub autosave_seconds: u64,
+    /// Upper bound on redraws per second; bursts of updates are coalesced
+    pub max_fps: u32,
+    /// Refuse to load a save or journal bigger than this many megabytes
+    pub max_state_mb: u64,
+    /// Record deaths in `graveyard.json` and never reuse a dead pet's name
+    pub hardcore: bool,
+    /// What happens to the save when the pet runs away
+    pub on_abandon: OnAbandon,
+    /// Whether the pet lives on while petty isn't running
+    pub mode: TimeMode,
+    /// Print a summary of the session once petty has closed
+    pub exit_digest: bool,
+    /// Show the bond with the pet as a row of hearts
+    pub show_bond: bool,
+    /// What `petty line` prints, see `statusline::render`
+    pub status_line: String,
+    /// Pet under `~/.petty/pets` that launches when no other save is
+    /// named; set by `petty use`
+    pub active_pet: Option<String>,
+    /// Ignore feeding a full pet or washing a clean one instead of only
+    /// pointing it out
+    pub skip_wasteful: bool,
+    /// Feed a pet once on launch if time away has left it starving
+    pub grace_feed: bool,
+    /// Capture the mouse so the pet's eyes can follow the cursor
+    pub mouse: bool,
+    /// Write the pet's name in big letters above it, for petty left up as
+    /// a display piece
+    pub big_name: bool,
+    /// Show the pet's name and health in the terminal's window title
+    pub terminal_title: bool,
+    /// Call the pet by its name when it's gone instead of "your pet"
+    pub name_in_messages: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: "default".to_string(),
+            themes: HashMap::new(),
+            weights: NeedWeights::default(),
+            rules: Rules::default(),
+            cooldowns: ActionCooldowns::default(),
+            sounds: Sounds::default(),
+            caretaker: Caretaker::default(),
+            thresholds: Thresholds::default(),
+            focus: FocusRules::default(),
+            retirement: Retirement::default(),
+            aging: AgingRules::default(),
+            overstimulation: Overstimulation::default(),
+            sulking: Sulking::default(),
+            host_load: HostLoad::default(),
+            calendar: Calendar::default(),
+            medicine: Medicine::default(),
+            loneliness: Loneliness::default(),
+            variety: Variety::default(),
+            personality: Personality::default(),
+            growth_spurts: GrowthSpurts::default(),
+            meals: Meals::default(),
+            lang: Lang::default(),
+            display_mode: DisplayMode::default(),
+            radar_chart: false,
+            pet_talks: true,
+            pet_emotes: true,
+            reduced_motion: false,
+            desktop_notify: false,
+            format: StateFormat::default(),
+            state_file: None,
+            lock: true,
+            save_on_action: false,
+            autosave_seconds: 60,
+            max_fps: 30,
+            max_state_mb: DEFAULT_MAX_STATE_MB,
+            hardcore: false,
+            on_abandon: OnAbandon::default(),
+            mode: TimeMode::default(),
+            exit_digest: true,
+            show_bond: true,
+            status_line: statusline::DEFAULT_FORMAT.to_string(),
+            active_pet: None,
+            skip_wasteful: false,
+            grace_feed: false,
+            mouse: false,
+            big_name: false,
+            terminal_title: true,
+            name_in_messages: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OnAbandon {
+    /// Delete the save so the next launch adopts a new pet
+    #[default]
+    Delete,
+    /// Keep the save; the pet is back, sad, at the next launch
+    Suspend,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeMode {
+    /// Needs keep decaying while petty is closed, and a pet left too long runs away
+    #[default]
+    Realtime,
+    /// Time only passes while petty is running; the pet waits where it was left
+    Session,
+}
+
+/// How much each need counts towards the care score and health decline.
+/// Only the ratios matter; the weights are normalized before use.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct NeedWeights {
+    pub hunger: f32,
+    pub cleanliness: f32,
+    pub mood: f32,
+}
+
+impl Default for NeedWeights {
+    fn default() -> Self {
+        Self {
+            hunger: 1.0,
+            cleanliness: 1.0,
+            mood: 1.0,
+        }
+    }
+}
+
+impl NeedWeights {
+    pub fn validate(&self) -> Result<(), String> {
+        let weights = [self.hunger, self.cleanliness, self.mood];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err("weights must be non-negative numbers".to_string());
+        }
+        if weights.iter().sum::<f32>() == 0.0 {
+            return Err("at least one weight must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+
+    /// Weights for hunger, cleanliness and mood scaled to sum to 1
+    pub fn normalized(&self) -> [f32; 3] {
+        let sum = self.hunger + self.cleanliness + self.mood;
+        if sum <= 0.0 {
+            return [1.0 / 3.0; 3];
+        }
+        [self.hunger / sum, self.cleanliness / sum, self.mood / sum]
+    }
+}
+
+/// Tuning for the simulation rules, shared by the live ticker and offline catch-up
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Rules {
+    /// Multiplier on health loss while two or more needs are in danger at once
+    pub compound_neglect: f32,
+    /// Decay steps (3 seconds each) hunger may stay high before it hurts health
+    pub starving_grace: u32,
+    pub thriving: Thriving,
+    pub fatigue: Fatigue,
+    pub sleep: SleepRules,
+    pub recovery: Recovery,
+    pub couplings: Couplings,
+    /// Minutes after adoption during which needs don't decay
+    pub honeymoon_minutes: u32,
+    pub habits: HabitRules,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            compound_neglect: 1.5,
+            starving_grace: 10,
+            thriving: Thriving::default(),
+            fatigue: Fatigue::default(),
+            sleep: SleepRules::default(),
+            recovery: Recovery::default(),
+            couplings: Couplings::default(),
+            honeymoon_minutes: 10,
+            habits: HabitRules::default(),
+        }
+    }
+}
+
+/// While every need is in this range the pet slowly recovers on its own
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Thriving {
+    pub max_hunger: u8,
+    pub min_cleanliness: u8,
+    pub min_mood: u8,
+    /// Health gained per decay step (every 3 seconds)
+    pub health: u8,
+    /// Mood gained per decay step
+    pub mood: u8,
+}
+
+impl Default for Thriving {
+    fn default() -> Self {
+        Self {
+            max_hunger: 30,
+            min_cleanliness: 70,
+            min_mood: 70,
+            health: 1,
+            mood: 1,
+        }
+    }
+}
+
+/// Fatigue builds up while awake and only sleep takes it away
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Fatigue {
+    /// Fatigue gained per decay step (every 3 seconds) awake
+    pub rise: u8,
+    /// Fatigue lost per second asleep
+    pub relief: u8,
+    /// From this fatigue on the pet no longer recovers health while awake
+    pub tired: u8,
+    /// At this fatigue the pet falls asleep on its own
+    pub collapse: u8,
+}
+
+impl Default for Fatigue {
+    fn default() -> Self {
+        Self {
+            rise: 1,
+            relief: 2,
+            tired: 80,
+            collapse: 100,
+        }
+    }
+}
+
+/// What happens to each stat while the pet sleeps, the same in the live
+/// ticker and offline catch-up. Fatigue always drains by `fatigue.relief`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SleepRules {
+    /// Health regained per second asleep, or every `ELDERLY_HEAL_SECONDS`
+    /// for an elderly pet
+    pub heal: u8,
+    /// Hunger gained per decay step (every 3 seconds) asleep
+    pub hunger: u8,
+    /// Cleanliness lost per decay step asleep
+    pub cleanliness: u8,
+    /// Mood regained per decay step asleep
+    pub mood: u8,
+    /// Most health, and most mood, sleep gives back per hour while petty
+    /// is closed, so a pet left asleep can't heal for free; 0 is no limit
+    pub offline_cap: u8,
+}
+
+impl Default for SleepRules {
+    fn default() -> Self {
+        // Needs hold still and only health recovers
+        Self {
+            heal: 1,
+            hunger: 0,
+            cleanliness: 0,
+            mood: 0,
+            offline_cap: 0,
+        }
+    }
+}
+
+/// How much more a meal or a night's sleep does for a pet that badly needs
+/// it. See `Pet::recover`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Recovery {
+    /// Health a meal gives a pet at half health
+    pub feed_health: u8,
+    /// How far health swings that, from `1 + coupling` times as much at
+    /// no health to `1 - coupling` times at full health. 0 keeps it flat.
+    pub coupling: f32,
+}
+
+impl Default for Recovery {
+    fn default() -> Self {
+        Self {
+            feed_health: 5,
+            coupling: 0.5,
+        }
+    }
+}
+
+/// Ways one stat drags on another. See `Pet::appetite` and
+/// `Pet::grumpiness`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Couplings {
+    pub enabled: bool,
+    /// A pet this dirty or dirtier is put off its food
+    pub filthy: u8,
+    /// As is one this sad or sadder
+    pub miserable: u8,
+    /// Share of a meal a put-off pet still eats
+    pub appetite: f32,
+    /// From this hunger on the pet gets grumpy
+    pub hungry: u8,
+    /// Extra mood a grumpy pet loses each second
+    pub grumpiness: u8,
+}
+
+impl Default for Couplings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            filthy: 20,
+            miserable: 20,
+            appetite: 0.5,
+            hungry: 70,
+            grumpiness: 1,
+        }
+    }
+}
+
+/// How far the pet adapts to the owner's routine
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct HabitRules {
+    pub enabled: bool,
+    /// Mood boredom can't take a pet below when all of its care has been play
+    pub max_mood_baseline: u8,
+}
+
+impl Default for HabitRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_mood_baseline: 30,
+        }
+    }
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.compound_neglect.is_finite() || self.compound_neglect < 1.0 {
+            return Err("compound_neglect must be at least 1.0".to_string());
+        }
+        if self.fatigue.tired > self.fatigue.collapse {
+            return Err("fatigue.tired must not be above fatigue.collapse".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.recovery.coupling) {
+            return Err("recovery.coupling must be between 0 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.couplings.appetite) {
+            return Err("couplings.appetite must be between 0 and 1".to_string());
+        }
+        if self.habits.max_mood_baseline > 100 {
+            return Err("habits.max_mood_baseline must be at most 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Feeds and washes the pet for coins once a need crosses its threshold,
+/// while playing and during offline catch-up alike
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Caretaker {
+    pub enabled: bool,
+    pub feed: bool,
+    pub wash: bool,
+    /// Feed once hunger reaches this
+    pub hunger: u8,
+    /// Wash once cleanliness drops to this
+    pub cleanliness: u8,
+    /// Coins each feed or wash costs
+    pub cost: u32,
+}
+
+impl Default for Caretaker {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feed: true,
+            wash: true,
+            hunger: 80,
+            cleanliness: 20,
+            cost: 5,
+        }
+    }
+}
+
+impl Caretaker {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hunger > 100 || self.cleanliness > 100 {
+            return Err("thresholds must be between 0 and 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Points either side of a `danger` level between one tier of health loss
+/// and the next
+pub const DANGER_TIER: i16 = 10;
+
+/// Where a stat turns yellow (`warn`) and red (`danger`) in the stats panel
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Threshold {
+    pub warn: u8,
+    pub danger: u8,
+}
+
+impl Threshold {
+    /// Health lost per decay step to a stat that's bad when high: one
+    /// point within `DANGER_TIER` of `danger`, two past it and three once
+    /// it's another tier further
+    pub fn penalty_above(self, value: u8) -> u8 {
+        let (value, danger) = (i16::from(value), i16::from(self.danger));
+        if value > danger + DANGER_TIER {
+            3
+        } else if value > danger {
+            2
+        } else if value > danger - DANGER_TIER {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Like `penalty_above`, for a stat that's bad when low
+    pub fn penalty_below(self, value: u8) -> u8 {
+        let (value, danger) = (i16::from(value), i16::from(self.danger));
+        if value < danger - DANGER_TIER {
+            3
+        } else if value < danger {
+            2
+        } else if value < danger + DANGER_TIER {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// The levels the simulation and the UI agree a stat is in trouble at.
+/// Needs start costing health a tier before `danger`, and a pet falls sick
+/// once its health drops below `health.danger`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Thresholds {
+    pub health: Threshold,
+    pub hunger: Threshold,
+    pub cleanliness: Threshold,
+    pub mood: Threshold,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            health: Threshold {
+                warn: 50,
+                danger: 20,
+            },
+            hunger: Threshold {
+                warn: 60,
+                danger: 80,
+            },
+            cleanliness: Threshold {
+                warn: 40,
+                danger: 20,
+            },
+            mood: Threshold {
+                warn: 40,
+                danger: 20,
+            },
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn validate(&self) -> Result<(), String> {
+        // Hunger is the one need that gets worse as it goes up
+        if self.hunger.warn > self.hunger.danger || self.hunger.danger > 100 {
+            return Err("hunger must have warn <= danger <= 100".to_string());
+        }
+        for (name, threshold) in [
+            ("health", self.health),
+            ("cleanliness", self.cleanliness),
+            ("mood", self.mood),
+        ] {
+            if threshold.danger > threshold.warn || threshold.warn > 100 {
+                return Err(format!("{name} must have danger <= warn <= 100"));
+            }
+        }
+        if self.health.danger == 0 || self.health.danger >= RECOVERED_HEALTH {
+            return Err(format!(
+                "health.danger must be between 1 and {}",
+                RECOVERED_HEALTH - 1
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Care in quick succession does less and less good, across all the
+/// actions together, so calm and spaced care beats frantic care
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Overstimulation {
+    pub enabled: bool,
+    /// Seconds of quiet that halve how worked up the pet is
+    pub half_life_seconds: f64,
+    /// Interactions in quick succession that still do their full good
+    pub calm: f64,
+    /// The smallest share of an action's benefit that's left, however
+    /// worked up the pet is
+    pub min_benefit: f64,
+}
+
+impl Default for Overstimulation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            half_life_seconds: 30.0,
+            calm: 3.0,
+            min_benefit: 0.25,
+        }
+    }
+}
+
+impl Overstimulation {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.half_life_seconds.is_finite() || self.half_life_seconds <= 0.0 {
+            return Err("half_life_seconds must be a positive number".to_string());
+        }
+        if !self.calm.is_finite() || self.calm < 1.0 {
+            return Err("calm must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_benefit) {
+            return Err("min_benefit must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A pet kept miserable in front of its owner hides until coaxed back out.
+/// Unlike running away it's never for good and the save is untouched.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Sulking {
+    pub enabled: bool,
+    /// Mood at or below which the pet sulks
+    pub mood: u8,
+    /// Seconds of sulking in a row before it hides
+    pub hide_after_seconds: u32,
+    /// Mood the pet needs to be coaxed out again
+    pub coax_mood: u8,
+    /// Seconds in a row at `coax_mood` or better before it comes out
+    pub coax_seconds: u32,
+}
+
+impl Default for Sulking {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mood: 15,
+            hide_after_seconds: 120,
+            coax_mood: 50,
+            coax_seconds: 60,
+        }
+    }
+}
+
+impl Sulking {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.coax_mood > 100 {
+            return Err("coax_mood must be at most 100".to_string());
+        }
+        if self.mood >= self.coax_mood {
+            return Err("mood must be below coax_mood".to_string());
+        }
+        if self.hide_after_seconds == 0 || self.coax_seconds == 0 {
+            return Err("hide_after_seconds and coax_seconds must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// How much medicine can do, and how often. The doses and the cooldown
+/// are kept in the save, so restarting petty doesn't reset them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Medicine {
+    /// Health a dose gives back
+    pub heal: u8,
+    /// Doses before the allowance runs out
+    pub daily_doses: u32,
+    /// Hours from the first dose of an allowance until it's full again
+    pub refresh_hours: u32,
+    /// Minutes to wait between doses
+    pub cooldown_minutes: u32,
+}
+
+impl Default for Medicine {
+    fn default() -> Self {
+        Self {
+            heal: 20,
+            daily_doses: 3,
+            refresh_hours: 24,
+            cooldown_minutes: 10,
+        }
+    }
+}
+
+impl Medicine {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.refresh_hours == 0 {
+            return Err("refresh_hours must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A pet left alone with nobody pressing a key gets lonely, and only more
+/// so the longer it lasts. See `Pet::loneliness`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Loneliness {
+    pub enabled: bool,
+    /// Minutes awake without the owner before loneliness starts
+    pub after_minutes: u32,
+    /// Minutes more until the pet is as lonely as it gets
+    pub full_minutes: u32,
+}
+
+impl Default for Loneliness {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_minutes: 10,
+            full_minutes: 30,
+        }
+    }
+}
+
+/// Cheering the pet up the same way over and over bores it, each go in a
+/// row doing less for its mood than the last; a different pastime in
+/// between brings the fun back. See `Pet::variety`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Variety {
+    pub enabled: bool,
+    /// Minutes until the pet forgets what it last did and is game again
+    pub memory_minutes: u32,
+    /// Share of the mood lost for each go in a row at the same pastime
+    pub repeat_penalty: f64,
+    /// The smallest share of the mood that's left, however bored the pet is
+    pub min_benefit: f64,
+}
+
+impl Default for Variety {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            memory_minutes: 30,
+            repeat_penalty: 0.25,
+            min_benefit: 0.25,
+        }
+    }
+}
+
+impl Variety {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.repeat_penalty) {
+            return Err("repeat_penalty must be between 0 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_benefit) {
+            return Err("min_benefit must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Long-term care slowly shapes who the pet becomes: a pet played with a
+/// lot grows energetic, one kept happy cheerful, one kept clean tidy.
+/// See `personality::Traits`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Personality {
+    pub enabled: bool,
+    /// Minutes of care for each step a trait leans
+    pub step_minutes: u32,
+    /// How far a trait can lean either way, out of 100
+    pub max_drift: u8,
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            step_minutes: 10,
+            max_drift: 60,
+        }
+    }
+}
+
+impl Personality {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.step_minutes == 0 {
+            return Err("step_minutes must be at least 1".to_string());
+        }
+        if self.max_drift > 100 {
+            return Err("max_drift must be at most 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Now and then while young the pet shoots up for a year of its age,
+/// hungrier and quicker to tire than usual. See `pet::growth_spurt`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct GrowthSpurts {
+    pub enabled: bool,
+    /// A spurt comes every this many years of age
+    pub every: u32,
+    /// Extra hunger per decay step during a spurt
+    pub hunger: u8,
+    /// Extra fatigue per decay step during a spurt
+    pub fatigue: u8,
+}
+
+impl Default for GrowthSpurts {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every: 4,
+            hunger: 2,
+            fatigue: 1,
+        }
+    }
+}
+
+impl GrowthSpurts {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.every == 0 {
+            return Err("every must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Feeding comes in two sizes: a snack takes the edge off, a full meal
+/// fills the pet up but leaves it digesting, and it won't eat again until
+/// that's done
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Meals {
+    /// Hunger a snack takes away
+    pub snack: u8,
+    /// Hunger a full meal takes away, shared out with a companion
+    pub meal: u8,
+    /// Minutes a full meal takes to go down, during which the pet won't
+    /// eat; 0 for none
+    pub digest_minutes: u32,
+    /// Grams a full meal puts on the pet
+    pub weight_gain: u32,
+}
+
+impl Default for Meals {
+    fn default() -> Self {
+        Self {
+            snack: 8,
+            meal: 20,
+            digest_minutes: 30,
+            weight_gain: 5,
+        }
+    }
+}
+
+impl Meals {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.meal > 100 || self.snack > 100 {
+            return Err("snack and meal must be at most 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A novelty: the busier the machine petty runs on, the more tired the
+/// pet gets. Needs the `host-load` build feature to read the load.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct HostLoad {
+    pub enabled: bool,
+    /// Seconds between readings of the machine's load
+    pub sample_seconds: u64,
+    /// Fatigue a machine running flat out keeps the pet at, at least
+    pub max_fatigue: u8,
+}
+
+impl Default for HostLoad {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_seconds: 5,
+            max_fatigue: 70,
+        }
+    }
+}
+
+impl HostLoad {
+    pub fn validate(&self, fatigue: &Fatigue) -> Result<(), String> {
+        if self.sample_seconds == 0 {
+            return Err("sample_seconds must be at least 1".to_string());
+        }
+        // Otherwise a busy machine would put the pet to sleep on the spot
+        if self.max_fatigue >= fatigue.collapse {
+            return Err("max_fatigue must be below rules.fatigue.collapse".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Days of the year that change how the pet looks, and the bonus on each
+/// anniversary of its adoption. See `calendar::occasions`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Calendar {
+    pub enabled: bool,
+    /// Mood the pet gets on its birthday
+    pub birthday_mood: u8,
+    /// And coins
+    pub birthday_coins: u32,
+    /// Replaces the built-in holidays when set
+    pub holidays: Vec<Holiday>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        let holiday = |name: &str, (month, day), days, decoration: &str, ascii: &str| Holiday {
+            name: name.to_string(),
+            month,
+            day,
+            days,
+            decoration: decoration.to_string(),
+            ascii: ascii.to_string(),
+        };
+        Self {
+            enabled: true,
+            birthday_mood: 20,
+            birthday_coins: 10,
+            holidays: vec![
+                holiday(
+                    "New Year",
+                    (1, 1),
+                    1,
+                    "🎆 新年快乐! 🎆",
+                    "* Happy New Year! *",
+                ),
+                holiday(
+                    "Halloween",
+                    (10, 31),
+                    1,
+                    "🎃 不给糖就捣蛋! 🎃",
+                    "(o) Trick or treat! (o)",
+                ),
+                holiday(
+                    "Christmas",
+                    (12, 24),
+                    2,
+                    "🎄 圣诞快乐! 🎄",
+                    "*<:o) Merry Christmas!",
+                ),
+            ],
+        }
+    }
+}
+
+impl Calendar {
+    pub fn validate(&self) -> Result<(), String> {
+        for holiday in &self.holidays {
+            // 2024 is a leap year, so February 29th is allowed
+            if chrono::NaiveDate::from_ymd_opt(2024, holiday.month, holiday.day).is_none() {
+                return Err(format!("{} isn't on a real date", holiday.name));
+            }
+            if holiday.days == 0 {
+                return Err(format!("{} must last at least a day", holiday.name));
+            }
+            if !holiday.ascii.is_ascii() {
+                return Err(format!("{}'s ascii decoration isn't ASCII", holiday.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A festive stretch of the year, from `month`/`day` for `days` days
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Holiday {
+    pub name: String,
+    pub month: u32,
+    pub day: u32,
+    #[serde(default = "one_day")]
+    pub days: u32,
+    /// Shown above the pet
+    pub decoration: String,
+    /// Shown instead in ASCII mode
+    pub ascii: String,
+}
+
+fn one_day() -> u32 {
+    1
+}
+
+/// What it takes for the pet to grow a year older
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AgingMode {
+    /// A year every 5 minutes, however the pet is doing
+    #[default]
+    Time,
+    /// Only 5 minutes of good enough care count as a year; a neglected
+    /// pet stays the age it is
+    Care,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AgingRules {
+    pub mode: AgingMode,
+    /// Care score the pet has to average over the 5 minutes to age in
+    /// `care` mode
+    pub min_care: u8,
+}
+
+impl Default for AgingRules {
+    fn default() -> Self {
+        Self {
+            mode: AgingMode::Time,
+            min_care: 60,
+        }
+    }
+}
+
+/// Sends a pet that lives long enough into the hall of fame instead of
+/// keeping it until its health gives out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Retirement {
+    pub enabled: bool,
+    /// The pet retires on reaching this age
+    pub age: u32,
+}
+
+impl Default for Retirement {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            age: 100,
+        }
+    }
+}
+
+impl Retirement {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.age == 0 {
+            return Err("age must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Intervals and rewards of `petty focus`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct FocusRules {
+    /// Minutes of a work interval when `--work` isn't given
+    pub work_minutes: u64,
+    /// Minutes of a break when `--break` isn't given
+    pub break_minutes: u64,
+    /// Mood the pet gains for each finished work interval
+    pub mood: u8,
+    /// Mood the pet loses when focus ends in the middle of work
+    pub quit_mood: u8,
+}
+
+impl Default for FocusRules {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            break_minutes: 5,
+            mood: 15,
+            quit_mood: 10,
+        }
+    }
+}
+
+impl FocusRules {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.work_minutes == 0 {
+            return Err("work_minutes must be at least 1".to_string());
+        }
+        if self.mood > 100 || self.quit_mood > 100 {
+            return Err("mood changes must be between 0 and 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Minimum seconds between two uses of each care action; 0 disables the cooldown
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ActionCooldowns {
+    pub feed: f64,
+    pub wash: f64,
+    pub play: f64,
+    pub train: f64,
+    /// Shared by going to sleep and waking up
+    pub sleep: f64,
+    pub spa: f64,
+}
+
+impl Default for ActionCooldowns {
+    fn default() -> Self {
+        Self {
+            feed: 2.0,
+            wash: 2.0,
+            play: 2.0,
+            train: 5.0,
+            sleep: 0.0,
+            spa: 30.0,
+        }
+    }
+}
+
+impl ActionCooldowns {
+    pub fn validate(&self) -> Result<(), String> {
+        let cooldowns = [
+            self.feed, self.wash, self.play, self.train, self.sleep, self.spa,
+        ];
+        if cooldowns.iter().any(|c| !c.is_finite() || *c < 0.0) {
+            return Err("cooldowns must be non-negative numbers of seconds".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, action: Action) -> Duration {
+        let seconds = match action {
+            Action::Feed | Action::Snack => self.feed,
+            Action::Wash => self.wash,
+            Action::Play => self.play,
+            Action::Train => self.train,
+            Action::Sleep | Action::Wake => self.sleep,
+            Action::Spa => self.spa,
+            // Its cooldown is kept with the pet, see `Medicine`
+            Action::Medicine => 0.0,
+        };
+        Duration::from_secs_f64(seconds)
+    }
+}
+
+/// Terminal bell patterns for care actions and events, see
+/// `sound::parse_pattern`. An empty pattern stays silent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Sounds {
+    pub enabled: bool,
+    pub feed: String,
+    pub wash: String,
+    pub play: String,
+    pub train: String,
+    /// Rung for going to sleep and waking up
+    pub sleep: String,
+    pub sick: String,
+    pub critical: String,
+    pub died: String,
+}
+
+impl Default for Sounds {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feed: "..".to_string(),
+            wash: String::new(),
+            play: String::new(),
+            train: String::new(),
+            sleep: String::new(),
+            sick: "-".to_string(),
+            critical: "- - -".to_string(),
+            died: String::new(),
+        }
+    }
+}
+
+impl Sounds {
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in [
+            &self.feed,
+            &self.wash,
+            &self.play,
+            &self.train,
+            &self.sleep,
+            &self.sick,
+            &self.critical,
+            &self.died,
+        ] {
+            sound::parse_pattern(pattern)?;
+        }
+        Ok(())
+    }
+
+    /// A spa day rings the wash pattern, and a snack or medicine the feed one
+    pub fn for_action(&self, action: Action) -> &str {
+        match action {
+            Action::Feed | Action::Snack | Action::Medicine => &self.feed,
+            Action::Wash | Action::Spa => &self.wash,
+            Action::Play => &self.play,
+            Action::Train => &self.train,
+            Action::Sleep | Action::Wake => &self.sleep,
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), String> {
+        self.weights
+            .validate()
+            .map_err(|e| format!("[weights]: {e}"))?;
+        self.rules.validate().map_err(|e| format!("[rules]: {e}"))?;
+        if self.max_fps == 0 {
+            return Err("max_fps must be at least 1".to_string());
+        }
+        if self.max_state_mb == 0 {
+            return Err("max_state_mb must be at least 1".to_string());
+        }
+        self.cooldowns
+            .validate()
+            .map_err(|e| format!("[cooldowns]: {e}"))?;
+        self.sounds
+            .validate()
+            .map_err(|e| format!("[sounds]: {e}"))?;
+        self.caretaker
+            .validate()
+            .map_err(|e| format!("[caretaker]: {e}"))?;
+        self.thresholds
+            .validate()
+            .map_err(|e| format!("[thresholds]: {e}"))?;
+        self.focus.validate().map_err(|e| format!("[focus]: {e}"))?;
+        self.retirement
+            .validate()
+            .map_err(|e| format!("[retirement]: {e}"))?;
+        self.overstimulation
+            .validate()
+            .map_err(|e| format!("[overstimulation]: {e}"))?;
+        self.sulking
+            .validate()
+            .map_err(|e| format!("[sulking]: {e}"))?;
+        self.host_load
+            .validate(&self.rules.fatigue)
+            .map_err(|e| format!("[host_load]: {e}"))?;
+        self.calendar
+            .validate()
+            .map_err(|e| format!("[calendar]: {e}"))?;
+        self.medicine
+            .validate()
+            .map_err(|e| format!("[medicine]: {e}"))?;
+        if self.loneliness.full_minutes == 0 {
+            return Err("[loneliness]: full_minutes must be at least 1".to_string());
+        }
+        self.variety
+            .validate()
+            .map_err(|e| format!("[variety]: {e}"))?;
+        self.personality
+            .validate()
+            .map_err(|e| format!("[personality]: {e}"))?;
+        self.growth_spurts
+            .validate()
+            .map_err(|e| format!("[growth_spurts]: {e}"))?;
+        self.meals.validate().map_err(|e| format!("[meals]: {e}"))?;
+        if self.aging.min_care > 100 {
+            return Err("[aging]: min_care must be at most 100".to_string());
+        }
+        Ok(())
+    }
+
+    /// Look up a theme by name, preferring user-defined themes over builtins
+    pub fn find_theme(&self, name: &str) -> Option<Theme> {
+        self.themes
+            .get(name)
+            .cloned()
+            .or_else(|| Theme::builtin(name))
+    }
+
+    /// The configured theme, falling back to the default theme if the name is unknown
+    pub fn active_theme(&self) -> Theme {
+        self.find_theme(&self.theme).unwrap_or_default()
+    }
+}
+
+pub fn get_config_path() -> error::Result<PathBuf> {
+    let mut path = get_data_dir()?;
+    path.push("config.toml");
+    Ok(path)
+}
+
+/// Picks a profile from `config.toml` when `--config-profile` isn't given
+pub const PROFILE_VAR: &str = "PETTY_PROFILE";
+
+pub fn parse_config(data: &str) -> error::Result<Config> {
+    parse_config_profile(data, None)
+}
+
+/// Parse `data` with one of its `[profiles.<name>]` tables laid over the
+/// top-level settings: `profile` if given, otherwise the file's own
+/// top-level `profile = "<name>"`, if any. Naming a profile the file
+/// doesn't have is an error.
+pub fn parse_config_profile(data: &str, profile: Option<&str>) -> error::Result<Config> {
+    let mut table: toml::Table = toml::from_str(data).map_err(|e| Error::Config(e.to_string()))?;
+    let profiles = match table.remove("profiles") {
+        Some(toml::Value::Table(profiles)) => profiles,
+        Some(_) => return Err(Error::Config("profiles must be a table".to_string())),
+        None => toml::Table::new(),
+    };
+    let fallback = match table.remove("profile") {
+        Some(toml::Value::String(name)) => Some(name),
+        Some(_) => return Err(Error::Config("profile must be a name".to_string())),
+        None => None,
+    };
+    if let Some(name) = profile.or(fallback.as_deref()) {
+        let Some(toml::Value::Table(overrides)) = profiles.get(name) else {
+            let mut names: Vec<&str> = profiles.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            let known = if names.is_empty() {
+                "there are none".to_string()
+            } else {
+                format!("there's {}", names.join(", "))
+            };
+            return Err(Error::Config(format!("no profile called {name} ({known})")));
+        };
+        merge(&mut table, overrides.clone());
+    }
+    let config: Config = table.try_into().map_err(|e| Error::Config(e.to_string()))?;
+    config.validate().map_err(Error::Config)?;
+    Ok(config)
+}
+
+/// Lay `overrides` over `base`: tables are merged key by key and anything
+/// else is replaced
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The config, with `profile` or `PETTY_PROFILE` picking the profile
+pub fn load_config(profile: Option<&str>) -> error::Result<Config> {
+    load_config_at(&get_config_path()?, profile)
+}
+
+/// The config in `path`, or the default one if there's no such file
+pub fn load_config_at(path: &Path, profile: Option<&str>) -> error::Result<Config> {
+    let from_env = std::env::var(PROFILE_VAR)
+        .ok()
+        .filter(|name| !name.is_empty());
+    let profile = profile.or(from_env.as_deref());
+    if !path.exists() {
+        return match profile {
+            Some(name) => Err(Error::Config(format!(
+                "no profile called {name} (there's no config file)"
+            ))),
+            None => Ok(Config::default()),
+        };
+    }
+    let data = fs::read_to_string(path)?;
+    parse_config_profile(&data, profile)
+}
+
+/// `data` with its top-level `active_pet` set to `slot`, or removed for
+/// `None`. Everything else in the file, comments included, is kept as is.
+pub fn with_active_pet(data: &str, slot: Option<&str>) -> String {
+    let tables = data
+        .lines()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(usize::MAX);
+    let mut lines: Vec<String> = data
+        .lines()
+        .enumerate()
+        .filter(|(i, line)| {
+            let key = line.split('=').next().unwrap_or_default().trim();
+            *i >= tables || !line.contains('=') || key != "active_pet"
+        })
+        .map(|(_, line)| line.to_string())
+        .collect();
+    if let Some(slot) = slot {
+        let value = toml::Value::String(slot.to_string());
+        lines.insert(0, format!("active_pet = {value}"));
+    }
+    let mut data = lines.join("\n");
+    data.push('\n');
+    data
+}
+
+/// Write `slot` as the active pet to the config file
+pub fn save_active_pet(slot: Option<&str>) -> error::Result<()> {
+    let path = get_config_path()?;
+    let data = match fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let data = with_active_pet(&data, slot);
+    // Don't leave behind a file the next launch would refuse
+    parse_config(&data)?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_empty_config_is_default() {
+        assert_eq!(parse_config("").unwrap(), Config::default());
+    }
+
+    const PROFILES: &str = "lang = \"en\"\n\
+        autosave_seconds = 30\n\
+        [rules]\n\
+        honeymoon_minutes = 5\n\
+        starving_grace = 4\n\
+        [profiles.strict]\n\
+        hardcore = true\n\
+        [profiles.strict.rules]\n\
+        honeymoon_minutes = 0\n\
+        [profiles.chill]\n\
+        autosave_seconds = 120\n\
+        [profiles.chill.caretaker]\n\
+        enabled = true\n";
+
+    #[test]
+    fn test_profile_is_laid_over_the_base() {
+        let base = parse_config(PROFILES).unwrap();
+        assert_eq!((base.lang, base.hardcore), (Lang::En, false));
+        assert_eq!(base.rules.honeymoon_minutes, 5);
+
+        let strict = parse_config_profile(PROFILES, Some("strict")).unwrap();
+        assert!(strict.hardcore);
+        // Only the keys the profile sets change, however deep
+        assert_eq!(strict.rules.honeymoon_minutes, 0);
+        assert_eq!(strict.rules.starving_grace, 4);
+        assert_eq!((strict.lang, strict.autosave_seconds), (Lang::En, 30));
+        // Anything neither sets keeps its default
+        assert_eq!(strict.rules.thriving, Thriving::default());
+
+        let chill = parse_config_profile(PROFILES, Some("chill")).unwrap();
+        assert_eq!(chill.autosave_seconds, 120);
+        assert!(chill.caretaker.enabled && !chill.hardcore);
+    }
+
+    #[test]
+    fn test_profile_selection_and_fallback() {
+        let with_default = format!("profile = \"chill\"\n{PROFILES}");
+        let config = parse_config(&with_default).unwrap();
+        assert_eq!(config.autosave_seconds, 120);
+        // Asking for one by name beats the file's own choice
+        let config = parse_config_profile(&with_default, Some("strict")).unwrap();
+        assert!(config.hardcore && config.autosave_seconds == 30);
+    }
+
+    #[test]
+    fn test_missing_profile_is_an_error() {
+        let error = parse_config_profile(PROFILES, Some("lenient")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("no profile called lenient (there's chill, strict)"),
+            "{error}"
+        );
+        let error = parse_config("profile = \"lenient\"\n").unwrap_err();
+        assert!(error.to_string().contains("(there are none)"), "{error}");
+        // A profile's settings are checked like any others
+        let invalid = "[profiles.broken.rules]\ncompound_neglect = 0.5\n";
+        assert!(parse_config(invalid).is_ok());
+        assert!(parse_config_profile(invalid, Some("broken")).is_err());
+    }
+
+    #[test]
+    fn test_active_pet_is_set_and_cleared() {
+        let data =
+            "# my pets\nactive_pet = \"Old\"\nlang = \"en\"\n\n[caretaker]\nenabled = true\n";
+        let data = with_active_pet(data, Some("Buddy"));
+        assert!(data.starts_with("active_pet = \"Buddy\"\n# my pets\n"));
+        let config = parse_config(&data).unwrap();
+        assert_eq!(config.active_pet.as_deref(), Some("Buddy"));
+        assert_eq!(config.lang, Lang::En);
+        assert!(config.caretaker.enabled);
+
+        let data = with_active_pet(&data, None);
+        assert_eq!(parse_config(&data).unwrap().active_pet, None);
+        assert_eq!(
+            parse_config(&with_active_pet("", Some("Mochi")))
+                .unwrap()
+                .active_pet
+                .as_deref(),
+            Some("Mochi")
+        );
+    }
+
+    #[test]
+    fn test_custom_theme_overrides_builtin() {
+        let config = parse_config(
+            r##"
+            theme = "mine"
+
+            [themes.mine]
+            border = "magenta"
+            danger = "#ff0000"
+            "##,
+        )
+        .unwrap();
+
+        let theme = config.active_theme();
+        assert_eq!(theme.border, Color::Magenta);
+        assert_eq!(theme.danger, Color::Rgb(255, 0, 0));
+        // Unset elements come from the default theme
+        assert_eq!(theme.good, Theme::default().good);
+    }
+
+    #[test]
+    fn test_weights_are_validated() {
+        assert!(parse_config("[weights]\nmood = -1.0").is_err());
+        assert!(parse_config("[weights]\nhunger = 0.0\ncleanliness = 0.0\nmood = 0.0").is_err());
+        assert!(parse_config("[weights]\nmood = 3.0").is_ok());
+    }
+
+    #[test]
+    fn test_rules_are_validated() {
+        assert!(parse_config("[rules]\ncompound_neglect = 0.5").is_err());
+        assert!(parse_config("[rules]\ncompound_neglect = 2.0").is_ok());
+        assert!(parse_config("[rules.fatigue]\ntired = 90\ncollapse = 50").is_err());
+        assert!(parse_config("[rules.habits]\nmax_mood_baseline = 101").is_err());
+    }
+
+    #[test]
+    fn test_thresholds_are_validated() {
+        let config = parse_config("[thresholds]\nhunger = { warn = 40, danger = 60 }").unwrap();
+        assert_eq!(config.thresholds.hunger.danger, 60);
+        assert_eq!(config.thresholds.mood, Thresholds::default().mood);
+        assert!(parse_config("[thresholds]\nhunger = { warn = 90, danger = 60 }").is_err());
+        assert!(parse_config("[thresholds]\nmood = { warn = 10, danger = 30 }").is_err());
+        assert!(parse_config("[thresholds]\ncleanliness = { warn = 140, danger = 30 }").is_err());
+        assert!(parse_config("[thresholds]\nhealth = { warn = 60, danger = 55 }").is_err());
+        assert!(parse_config("[thresholds]\nhealth = { warn = 60, danger = 0 }").is_err());
+    }
+
+    #[test]
+    fn test_sounds_are_validated() {
+        assert!(parse_config("[sounds]\nfeed = \"..?\"").is_err());
+        let config = parse_config("[sounds]\nenabled = true\nplay = \". .\"").unwrap();
+        assert_eq!(config.sounds.for_action(Action::Play), ". .");
+        assert!(!Config::default().sounds.enabled);
+    }
+
+    #[test]
+    fn test_max_fps_is_validated() {
+        assert!(parse_config("max_fps = 0").is_err());
+        assert_eq!(parse_config("max_fps = 60").unwrap().max_fps, 60);
+    }
+
+    #[test]
+    fn test_max_state_mb_is_validated() {
+        assert!(parse_config("max_state_mb = 0").is_err());
+        assert_eq!(parse_config("max_state_mb = 2").unwrap().max_state_mb, 2);
+    }
+
+    #[test]
+    fn test_cooldowns_are_validated() {
+        assert!(parse_config("[cooldowns]\nfeed = -1.0").is_err());
+        let config = parse_config("[cooldowns]\ntrain = 0.5").unwrap();
+        assert_eq!(
+            config.cooldowns.get(Action::Train),
+            Duration::from_millis(500)
+        );
+        assert_eq!(config.cooldowns.get(Action::Feed), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_weights_are_normalized() {
+        let weights = NeedWeights {
+            hunger: 2.0,
+            cleanliness: 1.0,
+            mood: 1.0,
+        };
+        assert_eq!(weights.normalized(), [0.5, 0.25, 0.25]);
+    }
+
+    #[test]
+    fn test_unknown_theme_falls_back_to_default() {
+        let config = parse_config(r#"theme = "missing""#).unwrap();
+        assert_eq!(config.active_theme(), Theme::default());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::bond::{
+    BOND_PER_CARE, BOND_PER_HEART, BOND_PER_POMODORO, BOND_STREAK_SECONDS, MAX_BOND,
+};
+use crate::config::{Config, DANGER_TIER};
+use crate::favorites::{FAVORITE_MEAL, FAVORITE_MOOD};
+use crate::habits::HUNGER_STEP;
+use crate::pet::{
+    ABANDON_AFTER, AGE_MILESTONE, AGING_SECONDS, COINS_PER_ACTION, COMPANION_MOOD,
+    COMPANION_NEGLECTED, CRITICAL_HEALTH, ELDERLY_HEAL_SECONDS, HOMECOMING_MOOD, MAX_MESSES,
+    MESS_INTERVAL, RECOVERED_HEALTH, SPA_COST, SPA_HEALTH, SPA_MOOD, URGENT_MARGIN,
+};
+use crate::skills::{LEVEL_STEP, MAX_LEVEL, TRAIN_XP};
+use serde::Serialize;
+
+/// The balance numbers built into petty, which no config can change
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Fixed {
+    pub hunger_step: u8,
+    pub mess_interval_seconds: u64,
+    pub max_messes: u8,
+    pub aging_seconds: u64,
+    pub age_milestone: u32,
+    pub elderly_heal_seconds: u64,
+    pub critical_health: u8,
+    pub recovered_health: u8,
+    pub danger_tier: i16,
+    pub urgent_margin: i16,
+    pub abandon_after_seconds: i64,
+    pub homecoming_mood: u8,
+    pub coins_per_action: u32,
+    pub spa_cost: u32,
+    pub spa_mood: u8,
+    pub spa_health: u8,
+    pub favorite_meal: u8,
+    pub favorite_mood: u8,
+    pub companion_neglected: u8,
+    pub companion_mood: u8,
+    pub train_xp: u32,
+    pub level_step: u32,
+    pub max_level: u8,
+    pub bond_per_care: u32,
+    pub bond_per_heart: u32,
+    pub max_bond: u32,
+    pub bond_streak_seconds: u32,
+    pub bond_per_pomodoro: u32,
+}
+
+impl Fixed {
+    pub const CURRENT: Fixed = Fixed {
+        hunger_step: HUNGER_STEP,
+        mess_interval_seconds: MESS_INTERVAL,
+        max_messes: MAX_MESSES,
+        aging_seconds: AGING_SECONDS,
+        age_milestone: AGE_MILESTONE,
+        elderly_heal_seconds: ELDERLY_HEAL_SECONDS,
+        critical_health: CRITICAL_HEALTH,
+        recovered_health: RECOVERED_HEALTH,
+        danger_tier: DANGER_TIER,
+        urgent_margin: URGENT_MARGIN,
+        abandon_after_seconds: ABANDON_AFTER.num_seconds(),
+        homecoming_mood: HOMECOMING_MOOD,
+        coins_per_action: COINS_PER_ACTION,
+        spa_cost: SPA_COST,
+        spa_mood: SPA_MOOD,
+        spa_health: SPA_HEALTH,
+        favorite_meal: FAVORITE_MEAL,
+        favorite_mood: FAVORITE_MOOD,
+        companion_neglected: COMPANION_NEGLECTED,
+        companion_mood: COMPANION_MOOD,
+        train_xp: TRAIN_XP,
+        level_step: LEVEL_STEP,
+        max_level: MAX_LEVEL,
+        bond_per_care: BOND_PER_CARE,
+        bond_per_heart: BOND_PER_HEART,
+        max_bond: MAX_BOND,
+        bond_streak_seconds: BOND_STREAK_SECONDS,
+        bond_per_pomodoro: BOND_PER_POMODORO,
+    };
+}
+
+/// Every number in effect: the built-in ones next to the config with the
+/// user's overrides applied
+#[derive(Serialize, Debug)]
+pub struct Constants<'a> {
+    pub fixed: Fixed,
+    pub config: &'a Config,
+}
+
+/// `Constants` for `config` as pretty-printed JSON, for `petty constants --json`
+pub fn constants_json(config: &Config) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&Constants {
+        fixed: Fixed::CURRENT,
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+    use serde_json::Value;
+
+    #[test]
+    fn test_dump_has_every_constant_and_the_overrides() {
+        let config = parse_config("[rules]\nhoneymoon_minutes = 3\n").unwrap();
+        let dump: Value = serde_json::from_str(&constants_json(&config).unwrap()).unwrap();
+
+        let fixed = serde_json::to_value(Fixed::CURRENT).unwrap();
+        assert_eq!(dump["fixed"], fixed);
+        for key in [
+            "hunger_step",
+            "aging_seconds",
+            "critical_health",
+            "spa_cost",
+        ] {
+            assert!(fixed.get(key).is_some(), "missing {key}");
+        }
+        for key in ["rules", "thresholds", "cooldowns", "weights", "caretaker"] {
+            assert!(dump["config"].get(key).is_some(), "missing {key}");
+        }
+        assert_eq!(dump["config"]["rules"]["honeymoon_minutes"], 3);
+        assert_eq!(dump["config"]["rules"]["sleep"]["heal"], 1);
+        assert_eq!(dump["fixed"]["aging_seconds"], 300);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::pet::Action;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// When each care action was last allowed, so every action can have its
+/// own minimum interval between uses
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    last_used: HashMap<Action, Instant>,
+}
+
+impl Cooldowns {
+    /// Whether `action` may be used at `now`. An allowed use starts the
+    /// action's cooldown again; a blocked one doesn't extend it.
+    pub fn try_use(&mut self, action: Action, now: Instant, cooldown: Duration) -> bool {
+        let ready = self
+            .last_used
+            .get(&action)
+            .is_none_or(|used| now.duration_since(*used) >= cooldown);
+        if ready {
+            self.last_used.insert(action, now);
+        }
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_action_is_blocked_within_cooldown() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+
+        assert!(cooldowns.try_use(Action::Feed, now, COOLDOWN));
+        assert!(!cooldowns.try_use(Action::Feed, now + Duration::from_secs(1), COOLDOWN));
+        assert!(cooldowns.try_use(Action::Feed, now + COOLDOWN, COOLDOWN));
+    }
+
+    #[test]
+    fn test_actions_cool_down_independently() {
+        let mut cooldowns = Cooldowns::default();
+        let now = Instant::now();
+
+        assert!(cooldowns.try_use(Action::Feed, now, COOLDOWN));
+        assert!(cooldowns.try_use(Action::Wash, now, COOLDOWN));
+        assert!(!cooldowns.try_use(Action::Feed, now, COOLDOWN));
+        // No cooldown configured means never blocked
+        assert!(cooldowns.try_use(Action::Sleep, now, Duration::ZERO));
+        assert!(cooldowns.try_use(Action::Sleep, now, Duration::ZERO));
+    }
+}
@@ -0,0 +1,451 @@
+//! `petty daemon` keeps the pet alive with no terminal open, and takes
+//! care requests over a Unix socket next to the save, e.g. `state.sock`.
+//!
+//! The protocol is one request per connection: the client writes a line
+//! with one word, and the daemon answers with one JSON line (a `Reply`)
+//! and hangs up. The words are the care actions (`feed`, `wash`, `play`,
+//! `train`, `sleep`, `wake`, `spa`, `medicine` and `snack`), `status` to
+//! only look, and `stop` to save and shut the daemon down. `send` is the
+//! client side, for `petty feed` and friends.
+
+use crate::config::Config;
+use crate::cooldown::Cooldowns;
+use crate::pet::{Action, Pet, PetStatus};
+use crate::state::{StateFile, save_state_to};
+use anyhow::{Result, bail};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::time::{interval, timeout};
+
+/// Seconds between saves when `autosave_seconds` is 0, since nothing else
+/// would ever save a daemon's pet before it stops
+pub const SAVE_SECONDS: u64 = 60;
+/// How long a client gets to send its request before it's hung up on
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The word for each care action
+const ACTION_WORDS: [(&str, Action); 9] = [
+    ("feed", Action::Feed),
+    ("wash", Action::Wash),
+    ("play", Action::Play),
+    ("train", Action::Train),
+    ("sleep", Action::Sleep),
+    ("wake", Action::Wake),
+    ("spa", Action::Spa),
+    ("medicine", Action::Medicine),
+    ("snack", Action::Snack),
+];
+
+/// What a client can ask of the daemon
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Request {
+    Act(Action),
+    Status,
+    Stop,
+}
+
+impl Request {
+    /// The request in one line as a client sends it, surrounding
+    /// whitespace and case aside
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let word = line.trim().to_ascii_lowercase();
+        match word.as_str() {
+            "status" => Ok(Request::Status),
+            "stop" => Ok(Request::Stop),
+            _ => ACTION_WORDS
+                .iter()
+                .find(|(name, _)| *name == word)
+                .map(|(_, action)| Request::Act(*action))
+                .ok_or_else(|| format!("unknown request: {}", line.trim())),
+        }
+    }
+
+    /// The line a client sends for this request, newline not included
+    pub fn encode(self) -> &'static str {
+        match self {
+            Request::Status => "status",
+            Request::Stop => "stop",
+            Request::Act(action) => ACTION_WORDS
+                .iter()
+                .find(|(_, known)| *known == action)
+                .map_or("status", |(name, _)| name),
+        }
+    }
+}
+
+/// The daemon's answer: how the pet is doing after the request, and why
+/// the request was refused if it was
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Reply {
+    pub error: Option<String>,
+    pub name: String,
+    pub age: u32,
+    pub health: u8,
+    pub hunger: u8,
+    pub cleanliness: u8,
+    pub mood: u8,
+    pub is_sleeping: bool,
+    pub status: PetStatus,
+}
+
+impl Reply {
+    pub fn of(pet: &Pet, error: Option<String>) -> Self {
+        Self {
+            error,
+            name: pet.name.clone(),
+            age: pet.age,
+            health: pet.health,
+            hunger: pet.hunger,
+            cleanliness: pet.cleanliness,
+            mood: pet.mood,
+            is_sleeping: pet.is_sleeping,
+            status: pet.status.clone(),
+        }
+    }
+
+    /// How the pet is doing, like "Mochi: health 90, hunger 20, clean 75,
+    /// mood 60"
+    pub fn line(&self) -> String {
+        let mut line = format!(
+            "{}: health {}, hunger {}, clean {}, mood {}",
+            self.name, self.health, self.hunger, self.cleanliness, self.mood
+        );
+        if self.is_sleeping {
+            line.push_str(" (asleep)");
+        }
+        if self.status != PetStatus::Alive {
+            line.push_str(&format!(" [{:?}]", self.status));
+        }
+        line
+    }
+
+    /// The reply as the one JSON line sent back, newline not included
+    pub fn encode(&self) -> String {
+        // Plain fields always serialize
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn decode(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line.trim()).map_err(|e| format!("not a petty daemon reply: {e}"))
+    }
+}
+
+/// Where the daemon for the save at `path` listens
+pub fn socket_path(path: &Path) -> PathBuf {
+    path.with_extension("sock")
+}
+
+/// Whether the daemon's tick at `second` is due for a scheduled save
+pub fn save_due(config: &Config, second: u64) -> bool {
+    let every = match config.autosave_seconds {
+        0 => SAVE_SECONDS,
+        seconds => seconds,
+    };
+    second > 0 && second.is_multiple_of(every)
+}
+
+/// Everything the daemon keeps between ticks and requests, apart from
+/// the pet and its socket
+#[derive(Debug, Default)]
+pub struct Daemon {
+    /// Seconds the daemon has been running for
+    pub second: u64,
+    cooldowns: Cooldowns,
+    /// Care was given since the last save
+    cared: bool,
+}
+
+impl Daemon {
+    /// Run one second of the pet's life. Returns whether to save now:
+    /// on the save schedule, or because care was given since the last.
+    pub fn tick(&mut self, pet: &mut Pet, config: &Config) -> bool {
+        self.second += 1;
+        pet.live(config, self.second, Utc::now());
+        let cared = std::mem::take(&mut self.cared);
+        save_due(config, self.second) || cared
+    }
+
+    /// Answer `request`, taking care the way the UI takes a key press
+    pub fn handle(
+        &mut self,
+        pet: &mut Pet,
+        config: &Config,
+        request: Request,
+        now: Instant,
+    ) -> Reply {
+        let Request::Act(action) = request else {
+            return Reply::of(pet, None);
+        };
+        pet.interact();
+        let refused = if !pet.can_act(action) {
+            Some(format!("{} can't do that right now", pet.name))
+        } else if config.skip_wasteful && pet.would_waste(action) {
+            Some(format!("{} doesn't need that right now", pet.name))
+        } else if action == Action::Medicine
+            && let Some(ready) = pet.doses.ready_at(&config.medicine, Utc::now())
+        {
+            Some(format!("No medicine until {}", ready.format("%H:%M UTC")))
+        } else if matches!(action, Action::Feed | Action::Snack)
+            && let Some(done) = pet.digesting(Utc::now())
+        {
+            Some(format!(
+                "{} is still digesting until {}",
+                pet.name,
+                done.format("%H:%M UTC")
+            ))
+        } else if !self
+            .cooldowns
+            .try_use(action, now, config.cooldowns.get(action))
+        {
+            Some("Not yet; that was only just done".to_string())
+        } else {
+            None
+        };
+        if refused.is_none() {
+            pet.act(action, config, Utc::now());
+            self.cared = true;
+        }
+        Reply::of(pet, refused)
+    }
+}
+
+/// Removes the socket once the daemon stops
+struct Socket(PathBuf);
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        fs::remove_file(&self.0).ok();
+    }
+}
+
+/// Listen at `path`, clearing out a socket left by a daemon that died.
+/// One that still answers is running, so it's left alone.
+async fn listen(path: &Path) -> Result<UnixListener> {
+    if UnixStream::connect(path).await.is_ok() {
+        bail!("a petty daemon is already running at {}", path.display());
+    }
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    Ok(UnixListener::bind(path)?)
+}
+
+/// Read one request from `stream` and answer it. Returns the request, if
+/// one was read.
+async fn serve(
+    stream: UnixStream,
+    daemon: &mut Daemon,
+    pet: &mut Pet,
+    config: &Config,
+) -> Result<Option<Request>> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    timeout(REQUEST_TIMEOUT, BufReader::new(reader).read_line(&mut line)).await??;
+    let (request, reply) = match Request::parse(&line) {
+        Ok(request) => (
+            Some(request),
+            daemon.handle(pet, config, request, Instant::now()),
+        ),
+        Err(e) => (None, Reply::of(pet, Some(e))),
+    };
+    writer
+        .write_all(format!("{}\n", reply.encode()).as_bytes())
+        .await?;
+    Ok(request)
+}
+
+/// Send `request` to the daemon listening at `path` and wait for its reply
+pub async fn send(path: &Path, request: Request) -> Result<Reply> {
+    let stream = match UnixStream::connect(path).await {
+        Ok(stream) => stream,
+        // No socket, or one left behind by a daemon that's gone
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) => {
+            bail!(
+                "No petty daemon is running at {}; start one with `petty daemon`",
+                path.display()
+            )
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(format!("{}\n", request.encode()).as_bytes())
+        .await?;
+    let mut line = String::new();
+    timeout(REQUEST_TIMEOUT, BufReader::new(reader).read_line(&mut line)).await??;
+    Reply::decode(&line).map_err(anyhow::Error::msg)
+}
+
+fn save(pet: &mut Pet, file: &StateFile) -> Result<()> {
+    // Keep catch-up from replaying time the daemon already lived through
+    pet.last_seen = Utc::now();
+    Ok(save_state_to(file, pet)?)
+}
+
+/// Keep the pet alive in the background until it's stopped, by a signal
+/// or a `stop` request, or its life ends. It's saved on the way out by
+/// whoever started it, like after a session.
+pub async fn run_daemon(pet: &mut Pet, config: &Config, file: &StateFile) -> Result<()> {
+    let path = socket_path(&file.path);
+    let listener = listen(&path).await?;
+    let _socket = Socket(path.clone());
+    let mut ticker = interval(Duration::from_secs(1));
+    let mut terminate = signal(SignalKind::terminate())?;
+    // Closing the terminal it was started from ends it like a stop, saved
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut daemon = Daemon::default();
+    eprintln!(
+        "Looking after {}; listening on {}",
+        pet.name,
+        path.display()
+    );
+
+    // The first tick of an interval completes immediately
+    ticker.tick().await;
+
+    while pet.health > 0 && !matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired) {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+            _ = hangup.recv() => break,
+            _ = ticker.tick() => {
+                if daemon.tick(pet, config) {
+                    save(pet, file)?;
+                }
+            }
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                // A client that goes wrong mustn't take the pet down with it
+                match serve(stream, &mut daemon, pet, config).await {
+                    Ok(Some(Request::Stop)) => break,
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Dropped a request: {e}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::favorites::Food;
+
+    #[test]
+    fn test_requests_round_trip() {
+        let requests = ACTION_WORDS
+            .iter()
+            .map(|(_, action)| Request::Act(*action))
+            .chain([Request::Status, Request::Stop]);
+        for request in requests {
+            assert_eq!(Request::parse(request.encode()), Ok(request));
+        }
+        assert_eq!(Request::parse("  Feed\n"), Ok(Request::Act(Action::Feed)));
+        assert_eq!(
+            Request::parse("pat\n"),
+            Err("unknown request: pat".to_string())
+        );
+        assert!(Request::parse("").is_err());
+
+        let pet = Pet::new("TestPet".to_string());
+        let reply = Reply::of(&pet, Some("nope".to_string()));
+        assert!(!reply.encode().contains('\n'));
+        assert_eq!(Reply::decode(&reply.encode()), Ok(reply));
+        assert!(Reply::decode("feed").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_no_daemon_is_a_clear_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = socket_path(&dir.path().join("state.json"));
+        let error = send(&path, Request::Status).await.unwrap_err();
+        assert!(error.to_string().starts_with("No petty daemon"), "{error}");
+
+        // Left behind by a daemon that was killed
+        drop(UnixListener::bind(&path).unwrap());
+        let error = send(&path, Request::Act(Action::Feed)).await.unwrap_err();
+        assert!(error.to_string().starts_with("No petty daemon"), "{error}");
+    }
+
+    #[test]
+    fn test_reply_line() {
+        let mut pet = Pet::new("Mochi".to_string());
+        (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (90, 20, 75, 60);
+        assert_eq!(
+            Reply::of(&pet, None).line(),
+            "Mochi: health 90, hunger 20, clean 75, mood 60"
+        );
+        (pet.is_sleeping, pet.status) = (true, PetStatus::Sick);
+        assert_eq!(
+            Reply::of(&pet, None).line(),
+            "Mochi: health 90, hunger 20, clean 75, mood 60 (asleep) [Sick]"
+        );
+    }
+
+    #[test]
+    fn test_saves_on_schedule_and_after_care() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let mut daemon = Daemon::default();
+        let mut saves = Vec::new();
+        for _ in 0..3 * config.autosave_seconds {
+            if daemon.tick(&mut pet, &config) {
+                saves.push(daemon.second);
+            }
+        }
+        let every = config.autosave_seconds;
+        assert_eq!(saves, [every, 2 * every, 3 * every]);
+        assert!(!save_due(&config, 0));
+
+        // Care is saved on the next tick, however far off the schedule is
+        pet.hunger = 50;
+        // Not today's menu, so the meal is a plain one
+        pet.favorites.food = Food::Cookies;
+        let reply = daemon.handle(
+            &mut pet,
+            &config,
+            Request::Act(Action::Feed),
+            Instant::now(),
+        );
+        assert_eq!((reply.error, reply.hunger), (None, 30));
+        assert!(daemon.tick(&mut pet, &config));
+        assert!(!daemon.tick(&mut pet, &config));
+
+        let never = Config {
+            autosave_seconds: 0,
+            ..Config::default()
+        };
+        assert!(save_due(&never, SAVE_SECONDS));
+        assert!(!save_due(&never, SAVE_SECONDS - 1));
+    }
+
+    #[test]
+    fn test_care_is_refused_like_in_the_ui() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let mut daemon = Daemon::default();
+        let now = Instant::now();
+        pet.mood = 50;
+        let played = daemon.handle(&mut pet, &config, Request::Act(Action::Play), now);
+        assert_eq!(played.error, None);
+        let again = daemon.handle(&mut pet, &config, Request::Act(Action::Play), now);
+        assert!(again.error.is_some());
+        assert_eq!(again.mood, played.mood);
+
+        // Asleep, only waking does anything
+        pet.is_sleeping = true;
+        let fed = daemon.handle(&mut pet, &config, Request::Act(Action::Feed), now);
+        assert!(fed.error.unwrap().contains("can't"));
+        let status = daemon.handle(&mut pet, &config, Request::Status, now);
+        assert_eq!(status, Reply::of(&pet, None));
+    }
+}
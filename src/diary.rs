@@ -0,0 +1,178 @@
+use crate::history::{Event, LogEntry};
+use crate::i18n::{Lang, format_duration};
+use crate::pet::{LifeStage, TickEvent};
+use chrono::{DateTime, Utc};
+
+/// The sentence a diary entry uses for `event`, or `None` if the event
+/// isn't worth writing about
+pub fn sentence(event: Event, lang: Lang) -> Option<String> {
+    let text = match (event, lang) {
+        (Event::Tick(TickEvent::GrewUp(stage)), Lang::Zh) => match stage {
+            LifeStage::Young => return None,
+            LifeStage::Adult => "我长大成年了!",
+            LifeStage::Elderly => "我步入老年了。",
+        },
+        (Event::Tick(TickEvent::GrewUp(stage)), Lang::En) => match stage {
+            LifeStage::Young => return None,
+            LifeStage::Adult => "I became an adult!",
+            LifeStage::Elderly => "I'm getting old now.",
+        },
+        (Event::Tick(TickEvent::Milestone(age)), Lang::Zh) => {
+            return Some(format!("我 {age} 岁啦!"));
+        }
+        (Event::Tick(TickEvent::Milestone(age)), Lang::En) => {
+            return Some(format!("I turned {age}!"));
+        }
+        (Event::Adopted, Lang::Zh) => "我来到了新家，新的旅程开始了!",
+        (Event::Adopted, Lang::En) => "I arrived at my new home!",
+        (Event::Fed { hunger }, Lang::Zh) if hunger > 80 => "我饿坏了，还好你及时喂了我!",
+        (Event::Fed { hunger }, Lang::En) if hunger > 80 => {
+            "I was very hungry but you fed me just in time!"
+        }
+        (Event::Washed { cleanliness }, Lang::Zh) if cleanliness < 20 => {
+            "我脏得不行，你帮我洗了个舒服的澡。"
+        }
+        (Event::Washed { cleanliness }, Lang::En) if cleanliness < 20 => {
+            "I was filthy, but you gave me a nice bath."
+        }
+        (Event::Played { mood }, Lang::Zh) if mood < 20 => "我本来很难过，和你玩了之后好多了。",
+        (Event::Played { mood }, Lang::En) if mood < 20 => {
+            "I was feeling down until we played together."
+        }
+        (Event::Pampered, Lang::Zh) => "你带我享受了一次 SPA, 好舒服!",
+        (Event::Pampered, Lang::En) => "You treated me to a spa day!",
+        (Event::GraceFed { .. }, Lang::Zh) => "我快饿晕了，你一回来就先给我吃了点东西。",
+        (Event::GraceFed { .. }, Lang::En) => {
+            "I was starving, but you fed me the moment you came back."
+        }
+        (Event::Medicated, Lang::Zh) => "我吃了药, 感觉好些了。",
+        (Event::Medicated, Lang::En) => "You gave me some medicine and I felt better.",
+        (Event::Trained, Lang::Zh) => "我们一起训练了。",
+        (Event::Trained, Lang::En) => "We trained together.",
+        (Event::Tick(TickEvent::Aged), Lang::Zh) => "我又长大了一点。",
+        (Event::Tick(TickEvent::Aged), Lang::En) => "I grew a little older.",
+        (Event::Tick(TickEvent::GotSick), Lang::Zh) => "我生病了，好难受...",
+        (Event::Tick(TickEvent::GotSick), Lang::En) => "I got sick and felt awful...",
+        (Event::Tick(TickEvent::Healed), Lang::Zh) => "我的病终于好了!",
+        (Event::Tick(TickEvent::Healed), Lang::En) => "I finally got better!",
+        (Event::Tick(TickEvent::ReachedCritical), Lang::Zh) => "我的状况非常糟糕...",
+        (Event::Tick(TickEvent::ReachedCritical), Lang::En) => "I was in really bad shape...",
+        (Event::Tick(TickEvent::Died), Lang::Zh) => "再见了...",
+        (Event::Tick(TickEvent::Died), Lang::En) => "Goodbye...",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+pub fn day_label(day: i64, lang: Lang) -> String {
+    match lang {
+        Lang::Zh => format!("第 {day} 天"),
+        Lang::En => format!("Day {day}"),
+    }
+}
+
+/// Turn the event log into diary lines like "Day 3: I got sick...", in
+/// order. Days count from `birth`, and a sentence is written at most once
+/// per day.
+pub fn diary_entries(log: &[LogEntry], birth: DateTime<Utc>, lang: Lang) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut written: Vec<(i64, String)> = Vec::new();
+
+    for entry in log {
+        let Some(mut text) = sentence(entry.event, lang) else {
+            continue;
+        };
+        let lived = entry.at.signed_duration_since(birth);
+        if entry.event == Event::Tick(TickEvent::Died) {
+            let lived = format_duration(lived.to_std().unwrap_or_default(), lang);
+            text.push_str(&match lang {
+                Lang::Zh => format!(" 我们一起度过了 {lived}。"),
+                Lang::En => format!(" We had {lived} together."),
+            });
+        }
+        let day = lived.num_days().max(0) + 1;
+        let separator = if lang == Lang::Zh { "：" } else { ": " };
+        let line = format!("{}{separator}{text}", day_label(day, lang));
+        if written.contains(&(day, text.clone())) {
+            continue;
+        }
+        written.push((day, text));
+        entries.push(line);
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(at: DateTime<Utc>, event: Event) -> LogEntry {
+        LogEntry { at, event }
+    }
+
+    #[test]
+    fn test_diary_entries_in_order() {
+        let birth = Utc::now();
+        let log = vec![
+            entry(birth, Event::Adopted),
+            entry(birth + Duration::hours(1), Event::Fed { hunger: 10 }),
+            entry(birth + Duration::days(2), Event::Fed { hunger: 90 }),
+            entry(birth + Duration::days(2), Event::Tick(TickEvent::GotSick)),
+            entry(birth + Duration::days(3), Event::Tick(TickEvent::Healed)),
+        ];
+
+        assert_eq!(
+            diary_entries(&log, birth, Lang::En),
+            vec![
+                "Day 1: I arrived at my new home!",
+                "Day 3: I was very hungry but you fed me just in time!",
+                "Day 3: I got sick and felt awful...",
+                "Day 4: I finally got better!",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diary_farewell_counts_time_together() {
+        let birth = Utc::now();
+        let died = birth + Duration::days(2) + Duration::hours(5);
+        let log = vec![entry(died, Event::Tick(TickEvent::Died))];
+
+        assert_eq!(
+            diary_entries(&log, birth, Lang::En),
+            vec!["Day 3: Goodbye... We had 2 days 5 hours together."]
+        );
+        assert_eq!(
+            diary_entries(&log, birth, Lang::Zh),
+            vec!["第 3 天：再见了... 我们一起度过了 2 天 5 小时。"]
+        );
+    }
+
+    #[test]
+    fn test_diary_skips_repeats_within_a_day() {
+        let birth = Utc::now();
+        let log = vec![
+            entry(birth, Event::Trained),
+            entry(birth + Duration::minutes(5), Event::Trained),
+            entry(birth + Duration::days(1), Event::Trained),
+        ];
+
+        assert_eq!(
+            diary_entries(&log, birth, Lang::Zh),
+            vec!["第 1 天：我们一起训练了。", "第 2 天：我们一起训练了。"]
+        );
+    }
+
+    #[test]
+    fn test_diary_ignores_routine_events() {
+        let birth = Utc::now();
+        let log = vec![
+            entry(birth, Event::Washed { cleanliness: 80 }),
+            entry(birth, Event::FellAsleep),
+            entry(birth, Event::WokeUp),
+        ];
+        assert!(diary_entries(&log, birth, Lang::En).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::achievements::Achievement;
+use crate::away::AwayDiff;
+use crate::history::{Event, LogEntry};
+use crate::i18n::{Lang, format_duration};
+use crate::personality::Traits;
+use crate::pet::{LifeStage, Pet, TickEvent};
+use chrono::TimeDelta;
+
+/// What happened in one session of play, printed once petty has left the
+/// alternate screen
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Digest {
+    pub played: TimeDelta,
+    pub fed: u32,
+    pub washed: u32,
+    pub played_with: u32,
+    pub trained: u32,
+    pub pampered: u32,
+    pub stats: AwayDiff,
+    pub grew_up: Vec<LifeStage>,
+    pub birthdays: Vec<u32>,
+    pub unlocked: Vec<Achievement>,
+    /// Who the pet is by the end of the session
+    pub traits: Traits,
+}
+
+impl Digest {
+    /// Compare the pet at the start of the session with the pet at its
+    /// end. Actions and milestones come from the entries the session added
+    /// to the log.
+    pub fn between(start: &Pet, end: &Pet, played: TimeDelta) -> Self {
+        let added = end.recorded.saturating_sub(start.recorded) as usize;
+        let new_entries = &end.log[end.log.len().saturating_sub(added)..];
+        let mut digest = Self {
+            played,
+            stats: AwayDiff::between(start, end),
+            traits: end.traits,
+            unlocked: Achievement::ALL
+                .into_iter()
+                .filter(|a| end.achievements.contains(a) && !start.achievements.contains(a))
+                .collect(),
+            ..Self::default()
+        };
+        for LogEntry { event, .. } in new_entries {
+            match event {
+                Event::Fed { .. } => digest.fed += 1,
+                Event::Washed { .. } => digest.washed += 1,
+                Event::Played { .. } => digest.played_with += 1,
+                Event::Trained => digest.trained += 1,
+                Event::Pampered => digest.pampered += 1,
+                Event::Tick(TickEvent::GrewUp(stage)) => digest.grew_up.push(*stage),
+                Event::Tick(TickEvent::Milestone(age)) => digest.birthdays.push(*age),
+                _ => {}
+            }
+        }
+        digest
+    }
+
+    /// A few lines like "Played for 12 minutes: fed 3, washed 1" and
+    /// what changed
+    pub fn lines(&self, lang: Lang) -> Vec<String> {
+        let played = format_duration(self.played.to_std().unwrap_or_default(), lang);
+        let counts = match lang {
+            Lang::Zh => [
+                ("喂食", self.fed),
+                ("洗澡", self.washed),
+                ("玩耍", self.played_with),
+                ("训练", self.trained),
+                ("SPA", self.pampered),
+            ],
+            Lang::En => [
+                ("fed", self.fed),
+                ("washed", self.washed),
+                ("played", self.played_with),
+                ("trained", self.trained),
+                ("spa days", self.pampered),
+            ],
+        };
+        let actions: Vec<String> = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(name, count)| format!("{name} {count}"))
+            .collect();
+        let mut lines = vec![match (lang, actions.is_empty()) {
+            (Lang::Zh, true) => format!("本次陪伴了 {played}"),
+            (Lang::Zh, false) => format!("本次陪伴了 {played}: {}", actions.join(", ")),
+            (Lang::En, true) => format!("Played for {played}"),
+            (Lang::En, false) => format!("Played for {played}: {}", actions.join(", ")),
+        }];
+
+        let changes = self.stats.changes(lang);
+        if !changes.is_empty() {
+            lines.push(match lang {
+                Lang::Zh => format!("变化: {}", changes.join(", ")),
+                Lang::En => format!("Changes: {}", changes.join(", ")),
+            });
+        }
+
+        let mut milestones: Vec<String> = Vec::new();
+        for stage in &self.grew_up {
+            milestones.push(match (lang, stage) {
+                (Lang::Zh, LifeStage::Young) => "长成了幼年".to_string(),
+                (Lang::Zh, LifeStage::Adult) => "长大成年".to_string(),
+                (Lang::Zh, LifeStage::Elderly) => "步入老年".to_string(),
+                (Lang::En, LifeStage::Young) => "grew up a little".to_string(),
+                (Lang::En, LifeStage::Adult) => "became an adult".to_string(),
+                (Lang::En, LifeStage::Elderly) => "reached old age".to_string(),
+            });
+        }
+        for age in &self.birthdays {
+            milestones.push(match lang {
+                Lang::Zh => format!("{age} 岁了"),
+                Lang::En => format!("turned {age}"),
+            });
+        }
+        for achievement in &self.unlocked {
+            milestones.push(match lang {
+                Lang::Zh => format!("解锁成就「{}」", achievement.title(lang)),
+                Lang::En => format!("unlocked \"{}\"", achievement.title(lang)),
+            });
+        }
+        if !milestones.is_empty() {
+            lines.push(match lang {
+                Lang::Zh => format!("里程碑: {}", milestones.join(", ")),
+                Lang::En => format!("Milestones: {}", milestones.join(", ")),
+            });
+        }
+
+        let traits = self.traits.describe(lang);
+        if !traits.is_empty() {
+            lines.push(match lang {
+                Lang::Zh => format!("性格: {}", traits.join(", ")),
+                Lang::En => format!("Personality: {}", traits.join(", ")),
+            });
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Meals};
+    use crate::favorites::{Favorites, Food};
+    use crate::personality::NOTICEABLE;
+    use crate::pet::Action;
+    use chrono::Utc;
+
+    #[test]
+    fn test_digest_counts_the_session() {
+        // Meals back to back, with no waiting for the last to go down
+        let config = Config {
+            meals: Meals {
+                digest_minutes: 0,
+                ..Meals::default()
+            },
+            ..Config::default()
+        };
+        let mut start = Pet::new("TestPet".to_string());
+        (start.health, start.hunger, start.mood) = (50, 60, 50);
+        start.favorites = Favorites {
+            food: Food::Cookies,
+            activity: Action::Train,
+        };
+        // Actions from before the session don't count
+        start.act(Action::Feed, &config, Utc::now());
+        let mut end = start.clone();
+        end.act(Action::Feed, &config, Utc::now());
+        end.act(Action::Feed, &config, Utc::now());
+        end.act(Action::Play, &config, Utc::now());
+        end.record(Event::Tick(TickEvent::Milestone(10)), Utc::now());
+        end.achievements.insert(Achievement::SurvivedWeek);
+        end.traits.cheer = NOTICEABLE;
+
+        let digest = Digest::between(&start, &end, TimeDelta::minutes(12));
+        assert_eq!((digest.fed, digest.played_with, digest.washed), (2, 1, 0));
+        assert_eq!(digest.stats.hunger, end.hunger as i16 - start.hunger as i16);
+        assert_eq!(digest.birthdays, vec![10]);
+        assert_eq!(
+            digest.lines(Lang::En),
+            vec![
+                "Played for 12 minutes: fed 2, played 1".to_string(),
+                "Changes: health +10, hunger -35, mood +10".to_string(),
+                "Milestones: turned 10, unlocked \"One week together\"".to_string(),
+                "Personality: cheerful".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quiet_session_is_one_line() {
+        let pet = Pet::new("TestPet".to_string());
+        let digest = Digest::between(&pet, &pet, TimeDelta::seconds(30));
+        assert_eq!(
+            digest.lines(Lang::En),
+            vec!["Played for less than a minute".to_string()]
+        );
+    }
+}
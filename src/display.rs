@@ -0,0 +1,181 @@
+use crate::i18n::Lang;
+use serde::{Deserialize, Serialize};
+
+/// How stat values are written in the stats panel
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    /// The raw value, e.g. `Hunger: 65`
+    #[default]
+    Numbers,
+    /// `Hunger: 65%`
+    Percent,
+    /// `Hunger: peckish`
+    Words,
+}
+
+/// A stat shown in the stats panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stat {
+    Health,
+    Hunger,
+    Cleanliness,
+    Mood,
+    Fatigue,
+}
+
+/// The lowest value (highest for hunger and fatigue) that still earns a word, with
+/// the word in English and Chinese
+type Word = (u8, &'static str, &'static str);
+
+/// Words for each stat from best to worst
+fn words(stat: Stat) -> [Word; 4] {
+    match stat {
+        Stat::Health => [
+            (80, "strong", "强壮"),
+            (50, "fine", "还行"),
+            (20, "weak", "虚弱"),
+            (0, "frail", "奄奄一息"),
+        ],
+        Stat::Hunger => [
+            (20, "full", "饱饱的"),
+            (60, "peckish", "有点饿"),
+            (80, "hungry", "饿了"),
+            (u8::MAX, "starving", "饿坏了"),
+        ],
+        Stat::Cleanliness => [
+            (80, "spotless", "干干净净"),
+            (40, "tidy", "还算干净"),
+            (20, "grubby", "脏兮兮"),
+            (0, "filthy", "臭烘烘"),
+        ],
+        Stat::Mood => [
+            (80, "joyful", "开心"),
+            (40, "content", "平静"),
+            (20, "glum", "闷闷不乐"),
+            (0, "miserable", "很伤心"),
+        ],
+        Stat::Fatigue => [
+            (30, "rested", "精神饱满"),
+            (60, "drowsy", "有点困"),
+            (80, "tired", "累了"),
+            (u8::MAX, "exhausted", "筋疲力尽"),
+        ],
+    }
+}
+
+/// The descriptive word for a stat value
+pub fn stat_word(stat: Stat, value: u8, lang: Lang) -> &'static str {
+    let words = words(stat);
+    let (_, en, zh) = words
+        .into_iter()
+        .find(|(bound, _, _)| match stat {
+            // Hunger and fatigue are the stats where lower is better
+            Stat::Hunger | Stat::Fatigue => value <= *bound,
+            _ => value >= *bound,
+        })
+        .unwrap_or(words[3]);
+    match lang {
+        Lang::Zh => zh,
+        Lang::En => en,
+    }
+}
+
+/// A stat value written the way `mode` asks for
+pub fn format_stat(stat: Stat, value: u8, mode: DisplayMode, lang: Lang) -> String {
+    match mode {
+        DisplayMode::Numbers => value.to_string(),
+        DisplayMode::Percent => format!("{}%", value.min(100)),
+        DisplayMode::Words => stat_word(stat, value, lang).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hunger_words_at_boundaries() {
+        let word = |value| stat_word(Stat::Hunger, value, Lang::En);
+        assert_eq!(word(0), "full");
+        assert_eq!(word(20), "full");
+        assert_eq!(word(21), "peckish");
+        assert_eq!(word(60), "peckish");
+        assert_eq!(word(61), "hungry");
+        assert_eq!(word(80), "hungry");
+        assert_eq!(word(81), "starving");
+        assert_eq!(word(u8::MAX), "starving");
+
+        let word = |value| stat_word(Stat::Fatigue, value, Lang::En);
+        assert_eq!(word(30), "rested");
+        assert_eq!(word(31), "drowsy");
+        assert_eq!(word(80), "tired");
+        assert_eq!(word(81), "exhausted");
+    }
+
+    #[test]
+    fn test_other_stat_words_at_boundaries() {
+        let cases = [
+            (
+                Stat::Health,
+                [(100, "strong"), (80, "strong"), (79, "fine"), (50, "fine")],
+            ),
+            (
+                Stat::Health,
+                [(49, "weak"), (20, "weak"), (19, "frail"), (0, "frail")],
+            ),
+            (
+                Stat::Cleanliness,
+                [(80, "spotless"), (79, "tidy"), (40, "tidy"), (39, "grubby")],
+            ),
+            (
+                Stat::Cleanliness,
+                [(20, "grubby"), (19, "filthy"), (0, "filthy"), (0, "filthy")],
+            ),
+            (
+                Stat::Mood,
+                [
+                    (80, "joyful"),
+                    (79, "content"),
+                    (40, "content"),
+                    (39, "glum"),
+                ],
+            ),
+            (
+                Stat::Mood,
+                [
+                    (20, "glum"),
+                    (19, "miserable"),
+                    (0, "miserable"),
+                    (0, "miserable"),
+                ],
+            ),
+        ];
+        for (stat, values) in cases {
+            for (value, expected) in values {
+                assert_eq!(
+                    stat_word(stat, value, Lang::En),
+                    expected,
+                    "{stat:?} {value}"
+                );
+            }
+        }
+        assert_eq!(stat_word(Stat::Mood, 80, Lang::Zh), "开心");
+    }
+
+    #[test]
+    fn test_format_stat_modes() {
+        assert_eq!(
+            format_stat(Stat::Hunger, 65, DisplayMode::Numbers, Lang::En),
+            "65"
+        );
+        assert_eq!(
+            format_stat(Stat::Health, 105, DisplayMode::Percent, Lang::En),
+            "100%"
+        );
+        assert_eq!(
+            format_stat(Stat::Mood, 30, DisplayMode::Words, Lang::En),
+            "glum"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::history::{Event, LogEntry};
+
+/// Log entries looked back over to pick what the pet dreams about
+pub const DREAM_WINDOW: usize = 20;
+/// Seconds each frame of a dream stays up
+pub const DREAM_FRAME_SECONDS: u64 = 2;
+
+/// What a sleeping pet dreams about
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dream {
+    Food,
+    Ball,
+    Bath,
+    Training,
+    /// Nothing much happened lately
+    Stars,
+}
+
+impl Dream {
+    /// The care the pet got most in the last `DREAM_WINDOW` log entries.
+    /// Ties go to whatever came first in the window.
+    pub fn for_log(log: &[LogEntry]) -> Self {
+        let recent = &log[log.len().saturating_sub(DREAM_WINDOW)..];
+        let mut counts: Vec<(Dream, usize)> = Vec::new();
+        for entry in recent {
+            let dream = match entry.event {
+                Event::Fed { .. } | Event::GraceFed { .. } => Dream::Food,
+                Event::Played { .. } => Dream::Ball,
+                Event::Washed { .. } | Event::Pampered => Dream::Bath,
+                Event::Trained => Dream::Training,
+                _ => continue,
+            };
+            match counts.iter_mut().find(|(seen, _)| *seen == dream) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((dream, 1)),
+            }
+        }
+        counts
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map_or(Dream::Stars, |(dream, _)| dream)
+    }
+
+    /// The thought bubble at `tick`, moving on every `DREAM_FRAME_SECONDS`
+    pub fn bubble(self, tick: u64) -> &'static str {
+        let frames = match self {
+            Dream::Food => ["( ><>     )", "(   ><>   )", "(     ><> )"],
+            Dream::Ball => ["(  o      )", "(    O    )", "(      o  )"],
+            Dream::Bath => ["( o  ~  . )", "( .  o  ~ )", "( ~  .  o )"],
+            Dream::Training => ["(   \\o/   )", "(    |    )", "(   /o\\   )"],
+            Dream::Stars => ["( *  .  * )", "( .  *  . )", "( *  .  * )"],
+        };
+        frames[(tick / DREAM_FRAME_SECONDS) as usize % frames.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn log_of(events: &[Event]) -> Vec<LogEntry> {
+        events
+            .iter()
+            .map(|event| LogEntry {
+                at: Utc::now(),
+                event: *event,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dream_follows_the_most_common_care() {
+        assert_eq!(Dream::for_log(&[]), Dream::Stars);
+        let played = Event::Played { mood: 50 };
+        let fed = Event::Fed { hunger: 50 };
+        let log = log_of(&[played, fed, Event::Adopted, fed, Event::FellAsleep]);
+        assert_eq!(Dream::for_log(&log), Dream::Food);
+        assert_eq!(Dream::for_log(&log_of(&[played, fed])), Dream::Ball);
+
+        // Only the recent past counts
+        let mut log = log_of(&[Event::Trained; 30]);
+        log.extend(log_of(&[Event::Pampered; DREAM_WINDOW / 2 + 1]));
+        assert_eq!(Dream::for_log(&log), Dream::Bath);
+    }
+
+    #[test]
+    fn test_dream_cycles_with_the_tick() {
+        let frames: Vec<&str> = (0..4)
+            .map(|n| Dream::Food.bubble(n * DREAM_FRAME_SECONDS))
+            .collect();
+        assert_eq!(frames[0], Dream::Food.bubble(1));
+        assert_ne!(frames[0], frames[1]);
+        assert_ne!(frames[1], frames[2]);
+        assert_eq!(frames[3], frames[0]);
+    }
+}
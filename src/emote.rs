@@ -0,0 +1,113 @@
+use crate::config::Thresholds;
+use crate::i18n::Lang;
+use crate::pet::{Pet, TickReport};
+
+/// Points from a warning line at which a need heading for it shows
+pub const NEAR: u8 = 10;
+
+/// A passing look on the pet's face for which way it's going, as opposed
+/// to the notices for things that have happened
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Emote {
+    Hurting,
+    GettingHungry,
+    GettingGrubby,
+    GettingBored,
+    Refreshed,
+    CheeringUp,
+}
+
+impl Emote {
+    #[cfg(test)]
+    pub const ALL: [Emote; 6] = [
+        Emote::Hurting,
+        Emote::GettingHungry,
+        Emote::GettingGrubby,
+        Emote::GettingBored,
+        Emote::Refreshed,
+        Emote::CheeringUp,
+    ];
+
+    pub fn text(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Emote::Hurting, Lang::Zh) => "好难受...",
+            (Emote::Hurting, Lang::En) => "ouch...",
+            (Emote::GettingHungry, Lang::Zh) => "有点饿了...",
+            (Emote::GettingHungry, Lang::En) => "getting hungry...",
+            (Emote::GettingGrubby, Lang::Zh) => "身上有点脏了...",
+            (Emote::GettingGrubby, Lang::En) => "getting grubby...",
+            (Emote::GettingBored, Lang::Zh) => "有点无聊了...",
+            (Emote::GettingBored, Lang::En) => "getting bored...",
+            (Emote::Refreshed, Lang::Zh) => "精神好多了!",
+            (Emote::Refreshed, Lang::En) => "feeling refreshed!",
+            (Emote::CheeringUp, Lang::Zh) => "心情变好了~",
+            (Emote::CheeringUp, Lang::En) => "cheering up~",
+        }
+    }
+}
+
+/// How `pet` feels about the way `report` moved its stats, if it shows.
+/// Losing health comes first, then a need sliding to within `NEAR` of its
+/// warning line, then health or mood on the rise.
+pub fn emote(report: &TickReport, pet: &Pet, thresholds: &Thresholds) -> Option<Emote> {
+    if report.health < 0 {
+        return Some(Emote::Hurting);
+    }
+    if report.hunger > 0 && pet.hunger.saturating_add(NEAR) >= thresholds.hunger.warn {
+        return Some(Emote::GettingHungry);
+    }
+    if report.cleanliness < 0 && pet.cleanliness <= thresholds.cleanliness.warn.saturating_add(NEAR)
+    {
+        return Some(Emote::GettingGrubby);
+    }
+    if report.mood < 0 && pet.mood <= thresholds.mood.warn.saturating_add(NEAR) {
+        return Some(Emote::GettingBored);
+    }
+    if report.health > 0 {
+        return Some(Emote::Refreshed);
+    }
+    if report.mood > 0 {
+        return Some(Emote::CheeringUp);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(health: i16, hunger: i16, cleanliness: i16, mood: i16) -> TickReport {
+        TickReport {
+            health,
+            hunger,
+            cleanliness,
+            mood,
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_reports_map_to_emotes() {
+        let thresholds = Thresholds::default();
+        let mut pet = Pet::new("Mochi".to_string());
+        (pet.hunger, pet.cleanliness, pet.mood) = (thresholds.hunger.warn - 5, 90, 90);
+        let feels = |report: TickReport, pet: &Pet| emote(&report, pet, &thresholds);
+
+        assert_eq!(feels(report(0, 1, 0, 0), &pet), Some(Emote::GettingHungry));
+        assert_eq!(feels(report(-1, 1, 0, 0), &pet), Some(Emote::Hurting));
+        assert_eq!(feels(report(1, 0, 0, 2), &pet), Some(Emote::Refreshed));
+        assert_eq!(feels(report(0, 0, 0, 2), &pet), Some(Emote::CheeringUp));
+        assert_eq!(feels(report(0, 0, 0, 0), &pet), None);
+
+        // Far from the line, a slide isn't worth a look yet
+        pet.hunger = 0;
+        assert_eq!(feels(report(0, 1, -1, -2), &pet), None);
+        pet.cleanliness = thresholds.cleanliness.warn;
+        assert_eq!(
+            feels(report(0, 1, -1, -2), &pet),
+            Some(Emote::GettingGrubby)
+        );
+        pet.mood = thresholds.mood.warn + NEAR;
+        assert_eq!(feels(report(0, 0, 0, -2), &pet), Some(Emote::GettingBored));
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can go wrong loading, saving or locking the pet
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// There's no save yet, so there's a pet to adopt
+    #[error("No saved pet at {}", .0.display())]
+    NotFound(PathBuf),
+    /// The save is there but can't be trusted or read
+    #[error("{} is corrupt: {reason}", path.display())]
+    Corrupt { path: PathBuf, reason: String },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The pet couldn't be encoded for saving
+    #[error("Could not encode the pet: {0}")]
+    Serialization(String),
+    #[error("Could not find home directory")]
+    NoHomeDir,
+    /// Another instance holds the lock on the save
+    #[error("petty is already running (pid {pid}); lock file: {}", path.display())]
+    Locked { pid: String, path: PathBuf },
+    #[error("Invalid config: {0}")]
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,126 @@
+use crate::i18n::Lang;
+use crate::pet::Action;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Extra mood from the pet's favorite activity
+pub const FAVORITE_MOOD: u8 = 5;
+/// Extra hunger a meal of the pet's favorite food takes away
+pub const FAVORITE_MEAL: u8 = 10;
+
+/// What's in the bowl; the menu changes every day
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Food {
+    #[default]
+    Fish,
+    Carrots,
+    Berries,
+    Cookies,
+}
+
+impl Food {
+    pub const ALL: [Food; 4] = [Food::Fish, Food::Carrots, Food::Berries, Food::Cookies];
+
+    /// What feeding serves on the pet's day of life `at`, starting the
+    /// menu from the top on the day it was born
+    pub fn on_menu(birth: DateTime<Utc>, at: DateTime<Utc>) -> Food {
+        let day = at.signed_duration_since(birth).num_days().max(0) as usize;
+        Food::ALL[day % Food::ALL.len()]
+    }
+
+    pub fn name(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Food::Fish, Lang::Zh) => "小鱼干",
+            (Food::Fish, Lang::En) => "fish",
+            (Food::Carrots, Lang::Zh) => "胡萝卜",
+            (Food::Carrots, Lang::En) => "carrots",
+            (Food::Berries, Lang::Zh) => "浆果",
+            (Food::Berries, Lang::En) => "berries",
+            (Food::Cookies, Lang::Zh) => "饼干",
+            (Food::Cookies, Lang::En) => "cookies",
+        }
+    }
+}
+
+/// Activities a pet can take a liking to
+pub const ACTIVITIES: [Action; 3] = [Action::Play, Action::Train, Action::Wash];
+
+/// The food and activity a pet likes best, picked once when it's adopted
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct Favorites {
+    pub food: Food,
+    pub activity: Action,
+}
+
+impl Default for Favorites {
+    fn default() -> Self {
+        Self {
+            food: Food::default(),
+            activity: Action::Play,
+        }
+    }
+}
+
+impl Favorites {
+    /// The favorites of a pet with `seed`. They come straight from the
+    /// seed so picking them doesn't use up any of the pet's random draws.
+    pub fn for_seed(seed: u64) -> Self {
+        Self {
+            food: Food::ALL[(seed % Food::ALL.len() as u64) as usize],
+            activity: ACTIVITIES
+                [(seed / Food::ALL.len() as u64 % ACTIVITIES.len() as u64) as usize],
+        }
+    }
+
+    /// A line like "Favorite food: fish, favorite activity: playing"
+    pub fn line(&self, lang: Lang) -> String {
+        let activity = match (self.activity, lang) {
+            (Action::Wash, Lang::Zh) => "洗澡",
+            (Action::Wash, Lang::En) => "bath time",
+            (Action::Train, Lang::Zh) => "训练",
+            (Action::Train, Lang::En) => "training",
+            (_, Lang::Zh) => "玩耍",
+            (_, Lang::En) => "playing",
+        };
+        let food = self.food.name(lang);
+        match lang {
+            Lang::Zh => format!("最爱的食物: {food}, 最爱的活动: {activity}"),
+            Lang::En => format!("Favorite food: {food}, favorite activity: {activity}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn test_menu_changes_daily() {
+        let birth = Utc::now();
+        assert_eq!(Food::on_menu(birth, birth), Food::Fish);
+        assert_eq!(
+            Food::on_menu(birth, birth + TimeDelta::hours(25)),
+            Food::Carrots
+        );
+        assert_eq!(Food::on_menu(birth, birth + TimeDelta::days(4)), Food::Fish);
+        // A clock set before the birth still serves something
+        assert_eq!(Food::on_menu(birth, birth - TimeDelta::days(3)), Food::Fish);
+    }
+
+    #[test]
+    fn test_every_favorite_can_come_up() {
+        let picks: Vec<Favorites> = (0..12).map(Favorites::for_seed).collect();
+        for food in Food::ALL {
+            assert!(picks.iter().any(|favorites| favorites.food == food));
+        }
+        for activity in ACTIVITIES {
+            assert!(picks.iter().any(|favorites| favorites.activity == activity));
+        }
+        assert_eq!(
+            Favorites::default().line(Lang::En),
+            "Favorite food: fish, favorite activity: playing"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::bond::BOND_PER_POMODORO;
+use crate::config::FocusRules;
+use crate::i18n::Lang;
+use crate::pet::Pet;
+
+/// What the focus timer is counting down
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase {
+    Work,
+    Break,
+    /// Stopped; nothing counts down anymore
+    Idle,
+}
+
+/// Work and break intervals taking turns, starting with work
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pomodoro {
+    /// Seconds in a work interval
+    pub work: u64,
+    /// Seconds in a break
+    pub rest: u64,
+    pub phase: Phase,
+    /// Seconds left in the current phase
+    pub remaining: u64,
+    /// Work intervals seen through to the end
+    pub completed: u32,
+}
+
+impl Pomodoro {
+    pub fn new(work: u64, rest: u64) -> Self {
+        Self {
+            work,
+            rest,
+            phase: Phase::Work,
+            remaining: work,
+            completed: 0,
+        }
+    }
+
+    /// Count down one second, returning the phase that just ended, if any
+    pub fn tick(&mut self) -> Option<Phase> {
+        if self.phase == Phase::Idle {
+            return None;
+        }
+        self.remaining = self.remaining.saturating_sub(1);
+        if self.remaining > 0 {
+            return None;
+        }
+        let ended = self.phase;
+        (self.phase, self.remaining) = match ended {
+            Phase::Work => {
+                self.completed += 1;
+                (Phase::Break, self.rest)
+            }
+            _ => (Phase::Work, self.work),
+        };
+        Some(ended)
+    }
+
+    /// Stop the timer. Returns whether that cut a work interval short.
+    pub fn stop(&mut self) -> bool {
+        let early = self.phase == Phase::Work;
+        (self.phase, self.remaining) = (Phase::Idle, 0);
+        early
+    }
+
+    /// A line like "Focus: 12:34 of work left (2 done)"
+    pub fn line(&self, lang: Lang) -> String {
+        let left = format!("{}:{:02}", self.remaining / 60, self.remaining % 60);
+        let done = self.completed;
+        match (self.phase, lang) {
+            (Phase::Work, Lang::Zh) => format!("专注: 工作剩余 {left} (已完成 {done} 个)"),
+            (Phase::Work, Lang::En) => format!("Focus: {left} of work left ({done} done)"),
+            (Phase::Break, Lang::Zh) => format!("专注: 休息剩余 {left} (已完成 {done} 个)"),
+            (Phase::Break, Lang::En) => format!("Focus: {left} of break left ({done} done)"),
+            (Phase::Idle, Lang::Zh) => format!("专注: 已结束 (完成 {done} 个)"),
+            (Phase::Idle, Lang::En) => format!("Focus: stopped ({done} done)"),
+        }
+    }
+}
+
+/// Cheer the pet up for a finished work interval
+pub fn reward(pet: &mut Pet, rules: &FocusRules) {
+    pet.mood = pet.mood.saturating_add(rules.mood).min(100);
+    pet.strengthen_bond(BOND_PER_POMODORO);
+}
+
+/// The pet is let down when the owner gives up on a work interval
+pub fn quit_early(pet: &mut Pet, rules: &FocusRules) {
+    pet.mood = pet.mood.saturating_sub(rules.quit_mood);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_and_breaks_take_turns() {
+        let mut pomodoro = Pomodoro::new(3, 2);
+        assert_eq!((pomodoro.tick(), pomodoro.tick()), (None, None));
+        assert_eq!(pomodoro.tick(), Some(Phase::Work));
+        assert_eq!(
+            (pomodoro.phase, pomodoro.remaining, pomodoro.completed),
+            (Phase::Break, 2, 1)
+        );
+        assert_eq!(
+            pomodoro.line(Lang::En),
+            "Focus: 0:02 of break left (1 done)"
+        );
+
+        // Stopping during a break isn't quitting early
+        let mut resting = pomodoro.clone();
+        assert!(!resting.stop());
+
+        assert_eq!(
+            (pomodoro.tick(), pomodoro.tick()),
+            (None, Some(Phase::Break))
+        );
+        assert_eq!((pomodoro.phase, pomodoro.remaining), (Phase::Work, 3));
+        assert!(pomodoro.stop());
+        assert_eq!(pomodoro.phase, Phase::Idle);
+        assert_eq!(pomodoro.tick(), None);
+        assert_eq!(pomodoro.completed, 1);
+    }
+
+    #[test]
+    fn test_finished_work_pleases_the_pet() {
+        let rules = FocusRules::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.mood = 50;
+        let mut pomodoro = Pomodoro::new(1, 1);
+        if pomodoro.tick() == Some(Phase::Work) {
+            reward(&mut pet, &rules);
+        }
+        assert_eq!(pet.mood, 50 + rules.mood);
+        assert_eq!(pet.bond, BOND_PER_POMODORO);
+
+        quit_early(&mut pet, &rules);
+        assert_eq!(pet.mood, 50 + rules.mood - rules.quit_mood);
+    }
+}
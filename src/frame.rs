@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+/// Coalesces redraw requests so the screen is drawn at most `max_fps` times
+/// a second, however fast ticks, keys and animation frames come in
+#[derive(Debug)]
+pub struct FrameLimiter {
+    min_interval: Duration,
+    last_frame: Option<Instant>,
+    pending: bool,
+}
+
+impl FrameLimiter {
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            min_interval: Duration::from_secs(1) / max_fps.max(1),
+            last_frame: None,
+            // Nothing is on screen yet
+            pending: true,
+        }
+    }
+
+    /// Something changed and the screen should be redrawn soon
+    pub fn request(&mut self) {
+        self.pending = true;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Whether to draw at `now`. Counts as drawing the frame if so.
+    pub fn should_draw(&mut self, now: Instant) -> bool {
+        if !self.pending || self.wait(now) > Duration::ZERO {
+            return false;
+        }
+        self.pending = false;
+        self.last_frame = Some(now);
+        true
+    }
+
+    /// How long from `now` until the next frame may be drawn
+    pub fn wait(&self, now: Instant) -> Duration {
+        self.last_frame.map_or(Duration::ZERO, |last| {
+            (last + self.min_interval).saturating_duration_since(now)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_are_spaced_out() {
+        let mut frames = FrameLimiter::new(10);
+        let start = Instant::now();
+        assert!(frames.should_draw(start));
+
+        frames.request();
+        assert!(!frames.should_draw(start + Duration::from_millis(50)));
+        assert_eq!(
+            frames.wait(start + Duration::from_millis(50)),
+            Duration::from_millis(50)
+        );
+        // The request is kept until the interval is up
+        assert!(frames.should_draw(start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_requests_are_coalesced() {
+        let mut frames = FrameLimiter::new(10);
+        let start = Instant::now();
+        assert!(frames.should_draw(start));
+        for _ in 0..5 {
+            frames.request();
+        }
+        assert!(frames.should_draw(start + Duration::from_secs(1)));
+        assert!(!frames.should_draw(start + Duration::from_secs(2)));
+    }
+}
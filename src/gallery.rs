@@ -0,0 +1,130 @@
+use crate::i18n::Lang;
+use crate::pet::{LifeStage, Pet, TickEvent, TickReport};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The pet as it was the moment it reached a new life stage. Only the stage
+/// and stats are kept; the art is drawn from the stage when shown.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Snapshot {
+    pub stage: LifeStage,
+    pub age: u32,
+    pub taken: DateTime<Utc>,
+    pub health: u8,
+    pub hunger: u8,
+    pub cleanliness: u8,
+    pub mood: u8,
+}
+
+impl Snapshot {
+    pub fn of(pet: &Pet, stage: LifeStage, taken: DateTime<Utc>) -> Self {
+        Self {
+            stage,
+            age: pet.age,
+            taken,
+            health: pet.health,
+            hunger: pet.hunger,
+            cleanliness: pet.cleanliness,
+            mood: pet.mood,
+        }
+    }
+}
+
+/// A snapshot for every life stage the tick crossed into
+pub fn capture(pet: &Pet, report: &TickReport, now: DateTime<Utc>) -> Vec<Snapshot> {
+    report
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            TickEvent::GrewUp(stage) => Some(Snapshot::of(pet, *stage, now)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ears, face and paws of the pet at each stage
+fn stage_art(stage: LifeStage) -> [&'static str; 3] {
+    match stage {
+        LifeStage::Young => [r"     /\_/\", r"     ( o.o )", r"     > ^ <"],
+        LifeStage::Adult => [r"     /\_/\", r"     ( ._. )", r"     > ^ <"],
+        LifeStage::Elderly => [r"     /\_/\", r"     ( -_- )", r"     > v <"],
+    }
+}
+
+pub fn stage_label(stage: LifeStage, lang: Lang) -> &'static str {
+    match (stage, lang) {
+        (LifeStage::Young, Lang::Zh) => "幼年",
+        (LifeStage::Young, Lang::En) => "Young",
+        (LifeStage::Adult, Lang::Zh) => "成年",
+        (LifeStage::Adult, Lang::En) => "Adult",
+        (LifeStage::Elderly, Lang::Zh) => "老年",
+        (LifeStage::Elderly, Lang::En) => "Elderly",
+    }
+}
+
+/// Every snapshot as a caption, the art and a stats line, oldest first
+pub fn gallery_lines(gallery: &[Snapshot], birth: DateTime<Utc>, lang: Lang) -> Vec<String> {
+    if gallery.is_empty() {
+        let empty = match lang {
+            Lang::Zh => "还没有照片, 等宠物长大吧!",
+            Lang::En => "No photos yet, wait for your pet to grow up!",
+        };
+        return vec![empty.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    for snapshot in gallery {
+        let day = snapshot
+            .taken
+            .signed_duration_since(birth)
+            .num_days()
+            .max(0)
+            + 1;
+        let stage = stage_label(snapshot.stage, lang);
+        lines.push(match lang {
+            Lang::Zh => format!("[{stage}] 第 {day} 天, {} 岁", snapshot.age),
+            Lang::En => format!("[{stage}] Day {day}, age {}", snapshot.age),
+        });
+        lines.extend(stage_art(snapshot.stage).map(String::from));
+        lines.push(format!(
+            "Health: {}  Hunger: {}  Cleanliness: {}  Mood: {}",
+            snapshot.health, snapshot.hunger, snapshot.cleanliness, snapshot.mood
+        ));
+        lines.push(String::new());
+    }
+    lines.pop();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_growing_up_takes_one_photo() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.age = 20;
+
+        // Age 21 is the first adult age
+        for second in 1..=300 {
+            let report = pet.tick(&config, second, pet.birth);
+            pet.capture_growth(&report, Utc::now());
+        }
+
+        assert_eq!(pet.gallery.len(), 1);
+        assert_eq!(pet.gallery[0].stage, LifeStage::Adult);
+        assert_eq!(pet.gallery[0].age, 21);
+        let lines = gallery_lines(&pet.gallery, pet.birth, Lang::En);
+        assert!(lines[0].starts_with("[Adult] Day 1, age 21"));
+        assert!(lines.contains(&r"     ( ._. )".to_string()));
+    }
+
+    #[test]
+    fn test_empty_gallery() {
+        let lines = gallery_lines(&[], Utc::now(), Lang::En);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("No photos yet"));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::pet::Pet;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// A pet that died in hardcore mode, kept for good
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Grave {
+    pub name: String,
+    pub age: u32,
+    pub birth: DateTime<Utc>,
+    pub died: DateTime<Utc>,
+}
+
+impl Grave {
+    pub fn of(pet: &Pet, died: DateTime<Utc>) -> Self {
+        Self {
+            name: pet.name.clone(),
+            age: pet.age,
+            birth: pet.birth,
+            died,
+        }
+    }
+}
+
+/// `graveyard.json` next to the save at `state_path`
+pub fn graveyard_path(state_path: &Path) -> PathBuf {
+    state_path.with_file_name("graveyard.json")
+}
+
+/// Every grave, oldest first. No graveyard yet means nobody has died.
+pub fn load_graveyard(path: &Path) -> io::Result<Vec<Grave>> {
+    match fs::read(path) {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn bury(path: &Path, grave: Grave) -> io::Result<()> {
+    let mut graves = load_graveyard(path)?;
+    graves.push(grave);
+    fs::write(path, serde_json::to_vec_pretty(&graves)?)
+}
+
+pub fn is_buried(graves: &[Grave], name: &str) -> bool {
+    graves.iter().any(|grave| grave.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graves_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = graveyard_path(&dir.path().join("state.json"));
+        assert!(load_graveyard(&path).unwrap().is_empty());
+
+        bury(&path, Grave::of(&Pet::new("Mochi".to_string()), Utc::now())).unwrap();
+        bury(&path, Grave::of(&Pet::new("Tofu".to_string()), Utc::now())).unwrap();
+
+        let graves = load_graveyard(&path).unwrap();
+        assert_eq!(graves.len(), 2);
+        assert!(is_buried(&graves, "Mochi"));
+        assert!(!is_buried(&graves, "Petty"));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::pet::Action;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Each new observation moves a rolling average this fraction of the way
+/// towards it, so old habits fade over a few dozen actions
+pub const SMOOTHING: u64 = 8;
+/// Meals that have to be timed before the pet trusts the routine
+pub const SETTLED_MEALS: u32 = 3;
+/// Gaps between meals may stray by up to this fraction of the usual gap
+/// and still count as a routine
+pub const ROUTINE_SLACK: u32 = 4;
+/// Hunger gained per decay step without a routine
+pub const HUNGER_STEP: u8 = 2;
+
+/// `average` moved `1 / SMOOTHING` of the way towards `sample`
+pub fn rolling(average: u32, sample: u32) -> u32 {
+    ((u64::from(average) * (SMOOTHING - 1) + u64::from(sample)) / SMOOTHING) as u32
+}
+
+/// What the pet has picked up from the owner's routine
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct Habits {
+    pub last_meal: Option<DateTime<Utc>>,
+    /// Gaps between meals observed so far
+    pub meals: u32,
+    /// Rolling average of the seconds between meals
+    pub meal_interval: u32,
+    /// Rolling average of how far each gap strayed from `meal_interval`
+    pub meal_jitter: u32,
+    /// Rolling share of care actions that were play, in thousandths
+    pub play_share: u32,
+}
+
+impl Habits {
+    /// Take note of the owner performing `action` at `at`
+    pub fn observe(&mut self, action: Action, at: DateTime<Utc>) {
+        if matches!(action, Action::Sleep | Action::Wake) {
+            return;
+        }
+        let played = if action == Action::Play { 1000 } else { 0 };
+        self.play_share = rolling(self.play_share, played);
+        if action != Action::Feed {
+            return;
+        }
+
+        if let Some(last) = self.last_meal {
+            let gap = at.signed_duration_since(last).num_seconds().max(0);
+            let gap = u32::try_from(gap).unwrap_or(u32::MAX);
+            if self.meals == 0 {
+                self.meal_interval = gap;
+            } else {
+                self.meal_jitter = rolling(self.meal_jitter, gap.abs_diff(self.meal_interval));
+                self.meal_interval = rolling(self.meal_interval, gap);
+            }
+            self.meals = self.meals.saturating_add(1);
+        }
+        self.last_meal = Some(at);
+    }
+
+    /// Whether meals have come regularly enough for the pet to expect them
+    pub fn has_meal_routine(&self) -> bool {
+        self.meals >= SETTLED_MEALS
+            && self.meal_interval > 0
+            && self.meal_jitter <= self.meal_interval / ROUTINE_SLACK
+    }
+
+    /// Hunger gained per decay step at `now`. A pet used to regular meals
+    /// doesn't fret before mealtime, but gets hungry fast once it's late.
+    pub fn hunger_step(&self, now: DateTime<Utc>) -> u8 {
+        let Some(last) = self.last_meal.filter(|_| self.has_meal_routine()) else {
+            return HUNGER_STEP;
+        };
+        if now.signed_duration_since(last).num_seconds() < i64::from(self.meal_interval) {
+            HUNGER_STEP - 1
+        } else {
+            HUNGER_STEP + 1
+        }
+    }
+
+    /// Mood that boredom alone won't take the pet below, rising with how
+    /// much of its care has been play, up to `max`
+    pub fn mood_baseline(&self, max: u8) -> u8 {
+        (u64::from(self.play_share.min(1000)) * u64::from(max) / 1000) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn fed_every(gaps: &[i64]) -> (Habits, DateTime<Utc>) {
+        let mut habits = Habits::default();
+        let mut at = Utc::now();
+        habits.observe(Action::Feed, at);
+        for gap in gaps {
+            at += TimeDelta::minutes(*gap);
+            habits.observe(Action::Feed, at);
+        }
+        (habits, at)
+    }
+
+    #[test]
+    fn test_regular_meals_shift_hunger_towards_mealtime() {
+        let (regular, fed) = fed_every(&[60, 62, 58, 61]);
+        assert!(regular.has_meal_routine());
+        assert!(regular.meal_interval.abs_diff(3600) < 120);
+        assert_eq!(
+            regular.hunger_step(fed + TimeDelta::minutes(30)),
+            HUNGER_STEP - 1
+        );
+        assert_eq!(
+            regular.hunger_step(fed + TimeDelta::minutes(70)),
+            HUNGER_STEP + 1
+        );
+
+        let (erratic, fed) = fed_every(&[10, 120, 5, 90]);
+        assert!(!erratic.has_meal_routine());
+        assert_eq!(
+            erratic.hunger_step(fed + TimeDelta::minutes(30)),
+            HUNGER_STEP
+        );
+
+        // Too few meals to go on yet
+        let (new, fed) = fed_every(&[60, 60]);
+        assert_eq!(new.hunger_step(fed), HUNGER_STEP);
+    }
+
+    #[test]
+    fn test_frequent_play_raises_the_mood_baseline() {
+        let mut habits = Habits::default();
+        let now = Utc::now();
+        assert_eq!(habits.mood_baseline(30), 0);
+        for _ in 0..20 {
+            habits.observe(Action::Play, now);
+        }
+        let playful = habits.mood_baseline(30);
+        assert!(playful > 20, "{playful}");
+
+        // Sleep doesn't count, and other care slowly takes play's place
+        habits.observe(Action::Sleep, now);
+        assert_eq!(habits.mood_baseline(30), playful);
+        for _ in 0..20 {
+            habits.observe(Action::Wash, now);
+        }
+        assert!(habits.mood_baseline(30) < playful / 2);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::pet::Pet;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// A pet that lived to retire, kept for good
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Plaque {
+    pub name: String,
+    pub age: u32,
+    pub birth: DateTime<Utc>,
+    pub retired: DateTime<Utc>,
+    pub bond: u32,
+}
+
+impl Plaque {
+    pub fn of(pet: &Pet, retired: DateTime<Utc>) -> Self {
+        Self {
+            name: pet.name.clone(),
+            age: pet.age,
+            birth: pet.birth,
+            retired,
+            bond: pet.bond,
+        }
+    }
+}
+
+/// `hall_of_fame.json` next to the save at `state_path`
+pub fn hall_of_fame_path(state_path: &Path) -> PathBuf {
+    state_path.with_file_name("hall_of_fame.json")
+}
+
+/// Every plaque, oldest first. No file yet means nobody has retired.
+pub fn load_hall_of_fame(path: &Path) -> io::Result<Vec<Plaque>> {
+    match fs::read(path) {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn induct(path: &Path, plaque: Plaque) -> io::Result<()> {
+    let mut plaques = load_hall_of_fame(path)?;
+    plaques.push(plaque);
+    fs::write(path, serde_json::to_vec_pretty(&plaques)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaques_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = hall_of_fame_path(&dir.path().join("state.json"));
+        assert!(load_hall_of_fame(&path).unwrap().is_empty());
+
+        let mut mochi = Pet::new("Mochi".to_string());
+        mochi.age = 100;
+        induct(&path, Plaque::of(&mochi, Utc::now())).unwrap();
+        induct(&path, Plaque::of(&Pet::new("Tofu".to_string()), Utc::now())).unwrap();
+
+        let plaques = load_hall_of_fame(&path).unwrap();
+        assert_eq!(plaques.len(), 2);
+        assert_eq!((plaques[0].name.as_str(), plaques[0].age), ("Mochi", 100));
+    }
+}
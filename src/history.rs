@@ -0,0 +1,85 @@
+use crate::pet::{Action, TickEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Oldest entries are dropped once the log grows past this
+pub const MAX_LOG_ENTRIES: usize = 1000;
+
+/// Something worth remembering about the pet's life. Care actions carry the
+/// relevant stat as it was just before the action.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Event {
+    Adopted,
+    Fed {
+        hunger: u8,
+    },
+    Washed {
+        cleanliness: u8,
+    },
+    Played {
+        mood: u8,
+    },
+    Trained,
+    FellAsleep,
+    WokeUp,
+    /// A spa day
+    Pampered,
+    /// The one emergency meal a pet that was starving on launch gets
+    GraceFed {
+        hunger: u8,
+    },
+    Tick(TickEvent),
+    /// A dose of medicine
+    Medicated,
+    Snacked {
+        hunger: u8,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LogEntry {
+    pub at: DateTime<Utc>,
+    pub event: Event,
+}
+
+impl Event {
+    pub fn for_action(action: Action, hunger: u8, cleanliness: u8, mood: u8) -> Self {
+        match action {
+            Action::Feed => Event::Fed { hunger },
+            Action::Wash => Event::Washed { cleanliness },
+            Action::Play => Event::Played { mood },
+            Action::Train => Event::Trained,
+            Action::Sleep => Event::FellAsleep,
+            Action::Wake => Event::WokeUp,
+            Action::Spa => Event::Pampered,
+            Action::Medicine => Event::Medicated,
+            Action::Snack => Event::Snacked { hunger },
+        }
+    }
+}
+
+/// Append `event` to `log`, dropping the oldest entries past the cap
+pub fn push_entry(log: &mut Vec<LogEntry>, event: Event, at: DateTime<Utc>) {
+    log.push(LogEntry { at, event });
+    if log.len() > MAX_LOG_ENTRIES {
+        let excess = log.len() - MAX_LOG_ENTRIES;
+        log.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_is_capped() {
+        let mut log = Vec::new();
+        for _ in 0..MAX_LOG_ENTRIES {
+            push_entry(&mut log, Event::Trained, Utc::now());
+        }
+        push_entry(&mut log, Event::Adopted, Utc::now());
+
+        assert_eq!(log.len(), MAX_LOG_ENTRIES);
+        assert_eq!(log.last().unwrap().event, Event::Adopted);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::config::HostLoad;
+
+/// How busy the machine is, each share between 0 and 1
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Load {
+    pub cpu: f32,
+    pub memory: f32,
+}
+
+/// Pressure up to this counts as an idle machine and leaves the pet be
+pub const IDLE_PRESSURE: f32 = 0.3;
+
+/// The pet's energy from 0 to 100 on a machine under `load`: all of it
+/// while the machine idles, then less and less as the pressure (mostly
+/// CPU, some memory) climbs to full
+pub fn energy(load: Load) -> u8 {
+    let pressure = (0.7 * load.cpu + 0.3 * load.memory).clamp(0.0, 1.0);
+    let drain = ((pressure - IDLE_PRESSURE) / (1.0 - IDLE_PRESSURE)).max(0.0);
+    (100.0 * (1.0 - drain)).round() as u8
+}
+
+/// The fatigue a pet can't get below while the machine is under `load`,
+/// scaled so a flat-out machine brings it to `rules.max_fatigue`
+pub fn fatigue_floor(load: Load, rules: &HostLoad) -> u8 {
+    let tiredness = 100 - u32::from(energy(load));
+    (tiredness * u32::from(rules.max_fatigue) / 100) as u8
+}
+
+/// Reads the machine's load. Needs the `host-load` build feature; without
+/// it there's never anything to read.
+#[derive(Default)]
+pub struct Sampler {
+    #[cfg(feature = "host-load")]
+    system: sysinfo::System,
+}
+
+impl Sampler {
+    /// The load right now. CPU use is measured since the last sample, so
+    /// the first one reads as idle.
+    #[cfg(feature = "host-load")]
+    pub fn sample(&mut self) -> Option<Load> {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        let total = self.system.total_memory();
+        if total == 0 {
+            return None;
+        }
+        Some(Load {
+            cpu: self.system.global_cpu_usage() / 100.0,
+            memory: self.system.used_memory() as f32 / total as f32,
+        })
+    }
+
+    #[cfg(not(feature = "host-load"))]
+    pub fn sample(&mut self) -> Option<Load> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_machine_tires_the_pet() {
+        let rules = HostLoad::default();
+        let idle = Load {
+            cpu: 0.05,
+            memory: 0.4,
+        };
+        let busy = Load {
+            cpu: 0.95,
+            memory: 0.9,
+        };
+        assert_eq!(energy(idle), 100);
+        assert_eq!(fatigue_floor(idle, &rules), 0);
+        assert!(energy(busy) < 20, "{}", energy(busy));
+        assert!(fatigue_floor(busy, &rules) > 50);
+
+        let flat_out = Load {
+            cpu: 1.0,
+            memory: 1.0,
+        };
+        assert_eq!(energy(flat_out), 0);
+        assert_eq!(fatigue_floor(flat_out, &rules), rules.max_fatigue);
+    }
+
+    #[test]
+    fn test_energy_falls_as_the_load_rises() {
+        let energies: Vec<u8> = (0..=10)
+            .map(|tenth| {
+                let share = tenth as f32 / 10.0;
+                energy(Load {
+                    cpu: share,
+                    memory: share,
+                })
+            })
+            .collect();
+        assert!(energies.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+}
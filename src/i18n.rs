@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Language for generated text such as diary entries
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    Zh,
+    En,
+}
+
+/// A duration in its two largest units, e.g. "3 days 4 hours" or "3 天 4 小时".
+/// Anything under a minute reads as "less than a minute".
+pub fn format_duration(duration: Duration, lang: Lang) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes == 0 && !duration.is_zero() {
+        return match lang {
+            Lang::Zh => "不到 1 分钟".to_string(),
+            Lang::En => "less than a minute".to_string(),
+        };
+    }
+
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    let parts = if days > 0 {
+        [(days, Unit::Day), (hours, Unit::Hour)]
+    } else if hours > 0 {
+        [(hours, Unit::Hour), (minutes, Unit::Minute)]
+    } else {
+        [(minutes, Unit::Minute), (0, Unit::Minute)]
+    };
+    let parts: Vec<String> = parts
+        .iter()
+        .enumerate()
+        // Keep the first unit even when it's zero, so zero reads "0 minutes"
+        .filter(|(i, (count, _))| *i == 0 || *count > 0)
+        .map(|(_, (count, unit))| unit.format(*count, lang))
+        .collect();
+    parts.join(" ")
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Day,
+    Hour,
+    Minute,
+}
+
+impl Unit {
+    fn format(self, count: u64, lang: Lang) -> String {
+        let (zh, one, many) = match self {
+            Unit::Day => ("天", "day", "days"),
+            Unit::Hour => ("小时", "hour", "hours"),
+            Unit::Minute => ("分钟", "minute", "minutes"),
+        };
+        match lang {
+            Lang::Zh => format!("{count} {zh}"),
+            Lang::En if count == 1 => format!("{count} {one}"),
+            Lang::En => format!("{count} {many}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    fn format(seconds: u64, lang: Lang) -> String {
+        format_duration(Duration::from_secs(seconds), lang)
+    }
+
+    #[test]
+    fn test_zero_and_sub_minute() {
+        assert_eq!(format(0, Lang::En), "0 minutes");
+        assert_eq!(format(0, Lang::Zh), "0 分钟");
+        assert_eq!(format(59, Lang::En), "less than a minute");
+        assert_eq!(format(59, Lang::Zh), "不到 1 分钟");
+        assert_eq!(
+            format_duration(Duration::from_millis(1), Lang::En),
+            "less than a minute"
+        );
+    }
+
+    #[test]
+    fn test_english_plurals() {
+        assert_eq!(format(MINUTE, Lang::En), "1 minute");
+        assert_eq!(format(45 * MINUTE + 30, Lang::En), "45 minutes");
+        assert_eq!(format(HOUR, Lang::En), "1 hour");
+        assert_eq!(format(HOUR + MINUTE, Lang::En), "1 hour 1 minute");
+        assert_eq!(format(5 * HOUR + 2 * MINUTE, Lang::En), "5 hours 2 minutes");
+        assert_eq!(format(DAY, Lang::En), "1 day");
+        assert_eq!(format(DAY + HOUR, Lang::En), "1 day 1 hour");
+        assert_eq!(
+            format(3 * DAY + 4 * HOUR + MINUTE, Lang::En),
+            "3 days 4 hours"
+        );
+    }
+
+    #[test]
+    fn test_chinese_units() {
+        assert_eq!(format(MINUTE, Lang::Zh), "1 分钟");
+        assert_eq!(format(45 * MINUTE, Lang::Zh), "45 分钟");
+        assert_eq!(format(HOUR, Lang::Zh), "1 小时");
+        assert_eq!(format(5 * HOUR + 2 * MINUTE, Lang::Zh), "5 小时 2 分钟");
+        assert_eq!(format(DAY, Lang::Zh), "1 天");
+        assert_eq!(format(3 * DAY + 4 * HOUR, Lang::Zh), "3 天 4 小时");
+    }
+}
@@ -0,0 +1,48 @@
+pub mod achievements;
+pub mod anim;
+pub mod away;
+pub mod banner;
+pub mod bond;
+pub mod calendar;
+pub mod card;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod constants;
+pub mod cooldown;
+pub mod daemon;
+pub mod diary;
+pub mod digest;
+pub mod display;
+pub mod dream;
+pub mod emote;
+pub mod error;
+pub mod favorites;
+pub mod focus;
+pub mod frame;
+pub mod gallery;
+pub mod graveyard;
+pub mod habits;
+pub mod hall;
+pub mod history;
+pub mod host;
+pub mod i18n;
+pub mod notify;
+pub mod personality;
+pub mod pet;
+pub mod radar;
+pub mod replay;
+pub mod rng;
+pub mod roster;
+pub mod skills;
+pub mod sound;
+pub mod state;
+pub mod statusline;
+pub mod stimulation;
+pub mod talk;
+pub mod term;
+pub mod theme;
+pub mod tutorial;
+pub mod ui;
+pub mod validate;
+pub mod watch;
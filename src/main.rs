@@ -1,104 +1,660 @@
-mod pet;
-mod state;
-mod ui;
-
-use anyhow::Result;
-use chrono::Utc;
-use pet::{Pet, PetStatus};
-use state::{delete_state, load_state, save_state};
+use achievements::Achievement;
+use anyhow::{Context, Result, anyhow, bail};
+use away::AwayDiff;
+use card::card_lines;
+use chrono::{DateTime, TimeDelta, Utc};
+use cli::{Command, parse_args};
+use clock::{Clock, SystemClock};
+use config::{AgingMode, Config, OnAbandon, TimeMode, get_config_path, load_config};
+use constants::constants_json;
+use daemon::{run_daemon, socket_path};
+use diary::diary_entries;
+use digest::Digest;
+use error::Error;
+use gallery::gallery_lines;
+use graveyard::{Grave, bury, graveyard_path, is_buried, load_graveyard};
+use hall::{Plaque, hall_of_fame_path, induct};
+use history::Event;
+use i18n::Lang;
+use pet::{ABANDON_AFTER, AGING_SECONDS, Companion, MESS_INTERVAL, Pet, PetStatus};
+use petty::{
+    achievements, away, card, cli, clock, config, constants, daemon, diary, digest, error, gallery,
+    graveyard, hall, history, i18n, pet, replay, roster, state, statusline, term, theme, tutorial,
+    ui, validate, watch,
+};
+use replay::timeline;
+use roster::{list_lines, saved_pets};
+use state::{
+    StateFile, backup_state, delete_state_at, find_backup, get_data_dir, load_backup, load_state,
+    lock_state, restore_backup, save_state_to, state_file, valid_slot,
+};
 use std::env;
-use ui::run_ui;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use term::{AsciiMode, Paint, Style};
+use theme::Theme;
+use ui::{UiOptions, run_replay, run_ui, show_theme_preview};
+use watch::run_watch;
 
-const ABANDON_SECONDS: i64 = 3 * 24 * 60 * 60; // 3 days
+const LOAD_FAILED: &str = "Could not load your pet";
+/// Hunger at which `grace_feed` steps in
+const GRACE_FEED_HUNGER: u8 = 90;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    // Parse command line arguments for pet name
-    let args: Vec<String> = env::args().collect();
-    let pet_name = if args.len() > 1 {
-        args[1].clone()
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            let cause = error_kind(&e);
+            if let Some(hint) = cause.and_then(hint) {
+                eprintln!("{hint}");
+            }
+            ExitCode::from(cause.map_or(1, exit_code))
+        }
+    }
+}
+
+/// The data dir `petty list` and `petty use` look in. A `state_file` set in
+/// the config is a single save of its own, so there's nothing to switch.
+fn pets_dir(config: &Config) -> Result<std::path::PathBuf> {
+    if config.state_file.is_some() {
+        bail!("`state_file` is set in the config, so there's only the one pet");
+    }
+    Ok(get_data_dir()?)
+}
+
+/// The config for `petty <name>`: the pet saved in `dir` under that name
+/// is played for this run, active or not. Any other name is adopted into
+/// the active save if it's empty, as before.
+fn with_named_pet(mut config: Config, name: &str, dir: &std::path::Path) -> Result<Config> {
+    if let Some(save) = roster::find(&saved_pets(dir, config.format)?, name) {
+        config.active_pet = save.slot.clone();
+    }
+    Ok(config)
+}
+
+/// The crate error underneath whatever context was added on the way up
+fn error_kind(error: &anyhow::Error) -> Option<&Error> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<Error>())
+}
+
+/// Exit codes from `sysexits.h`, so scripts can tell a missing pet from a
+/// corrupt one or a busy save
+fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::Corrupt { .. } => 65,
+        Error::NotFound(_) => 66,
+        Error::Serialization(_) => 70,
+        Error::Io(_) => 74,
+        Error::Locked { .. } => 75,
+        Error::NoHomeDir | Error::Config(_) => 78,
+    }
+}
+
+/// What the user can do about `error`, if there's anything to suggest
+fn hint(error: &Error) -> Option<&'static str> {
+    match error {
+        Error::NotFound(_) => Some("Run `petty <name>` to adopt a pet."),
+        Error::Corrupt { .. } => {
+            Some("The save was left as it is; move it aside to start over with a new pet.")
+        }
+        Error::Locked { .. } => {
+            Some("Use `petty --readonly` to watch the pet without taking over its save.")
+        }
+        Error::NoHomeDir => Some("Set `state_file` in the config to choose where the pet lives."),
+        Error::Io(_) | Error::Serialization(_) | Error::Config(_) => None,
+    }
+}
+
+async fn run() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = parse_args(&args)?;
+    // Checked before loading, so a broken config can be reported on
+    if cli.command == Command::Validate {
+        return validate_files(cli.config_profile.as_deref());
+    }
+    let mut config = load_config(cli.config_profile.as_deref())?;
+    if let Command::Run { name: Some(name) } = &cli.command
+        && config.state_file.is_none()
+    {
+        config = with_named_pet(config, name, &get_data_dir()?)?;
+    }
+    let clock = SystemClock;
+    let file = state_file(&config)?;
+
+    let pet_name = match &cli.command {
+        Command::Run { name: Some(name) } => name.clone(),
+        Command::Run { name: None } | Command::Watch | Command::Daemon | Command::Focus { .. } => {
+            config
+                .active_pet
+                .clone()
+                .unwrap_or_else(|| "Petty".to_string())
+        }
+        Command::Constants => {
+            println!("{}", constants_json(&config)?);
+            return Ok(());
+        }
+        Command::Validate => unreachable!("handled before the config is loaded"),
+        Command::Send { request } => {
+            let reply = daemon::send(&socket_path(&file.path), *request).await?;
+            println!("{}", reply.line());
+            if let Some(error) = reply.error {
+                bail!(error);
+            }
+            return Ok(());
+        }
+        Command::Tutorial => {
+            // No state file, so nothing is ever saved
+            let mut pet = tutorial::practice_pet();
+            let options = UiOptions {
+                ascii: AsciiMode::detect(cli.ascii),
+                reduced_motion: cli.reduced_motion || config.reduced_motion,
+                compact: cli.compact,
+                inline: cli.inline,
+                tutorial: true,
+                ..UiOptions::default()
+            };
+            return run_ui(&mut pet, &config, &options).await;
+        }
+        Command::List => {
+            let saves = saved_pets(&pets_dir(&config)?, config.format)?;
+            for line in list_lines(&saves, config.active_pet.as_deref(), config.lang) {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        Command::Use { name } => {
+            let saves = saved_pets(&pets_dir(&config)?, config.format)?;
+            let slot = match roster::find(&saves, name) {
+                Some(save) => save.slot.clone(),
+                None if valid_slot(name) => {
+                    println!("There's no pet called {name} yet; one will be adopted next time");
+                    Some(name.clone())
+                }
+                None => bail!("{name} can't be used as the name of a save"),
+            };
+            config::save_active_pet(slot.as_deref())?;
+            println!("{name} is now the active pet");
+            return Ok(());
+        }
+        Command::Line { format } => {
+            // Caught up for display only; the next real session saves
+            let mut pet = load_state(&file).context(LOAD_FAILED)?;
+            catch_up(&mut pet, &config, &clock);
+            let format = format.as_deref().unwrap_or(&config.status_line);
+            let ascii = AsciiMode::detect(cli.ascii).is_on();
+            let paint = cli.color.for_stdout();
+            println!(
+                "{}",
+                statusline::render(format, &pet, &config.thresholds, ascii, paint)
+            );
+            return Ok(());
+        }
+        Command::Diary => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            println!("{}", pet.favorites.line(config.lang));
+            for line in diary_entries(&pet.log, pet.birth, config.lang) {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        Command::Achievements => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            for line in achievement_lines(&pet, config.lang, cli.color.for_stdout()) {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        Command::Copy => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            let card = card_lines(&pet, &config, AsciiMode::detect(cli.ascii)).join("\n");
+            match card::copy(card.clone()) {
+                Ok(()) => println!("Copied {}'s card to the clipboard", pet.name),
+                // Headless or unsupported: the card can still be copied by hand
+                Err(e) => {
+                    eprintln!("Could not reach the clipboard: {e}");
+                    println!("{card}");
+                }
+            }
+            return Ok(());
+        }
+        Command::Replay { speed } => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            let moments = timeline(&pet);
+            if moments.is_empty() {
+                println!("{} has nothing in its log to replay yet", pet.name);
+                return Ok(());
+            }
+            let speed = speed.unwrap_or(replay::DEFAULT_SPEED);
+            return run_replay(&moments, &config, AsciiMode::detect(cli.ascii), speed).await;
+        }
+        Command::Gallery => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            for line in gallery_lines(&pet.gallery, pet.birth, config.lang) {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+        Command::ExportState => {
+            let pet = load_state(&file).context(LOAD_FAILED)?;
+            let mut stdout = io::stdout().lock();
+            export_state(&pet, &mut stdout)?;
+            stdout.flush()?;
+            return Ok(());
+        }
+        Command::ImportState => {
+            // Don't swap the save out from under a running instance
+            let _lock = if config.lock {
+                Some(lock_state(&file)?)
+            } else {
+                None
+            };
+            let pet = import_state(io::stdin().lock())?;
+            backup_state(&file)?;
+            save_state_to(&file, &pet)?;
+            eprintln!("Imported {} into {}", pet.name, file.path.display());
+            return Ok(());
+        }
+        Command::Restore { yes } => {
+            let _lock = if config.lock {
+                Some(lock_state(&file)?)
+            } else {
+                None
+            };
+            let backup = match load_backup(&file) {
+                Ok(pet) => pet,
+                Err(Error::NotFound(path)) => {
+                    bail!(
+                        "There's no backup to restore: {} doesn't exist",
+                        path.display()
+                    )
+                }
+                Err(e) => return Err(anyhow::Error::new(e).context("The backup can't be restored")),
+            };
+            let question = format!(
+                "Put {} back as it was at {} (age {}, health {}, hunger {})? \
+                 This replaces the current save.",
+                backup.name,
+                backup
+                    .last_seen
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M"),
+                backup.age,
+                backup.health,
+                backup.hunger
+            );
+            if !*yes && !confirm(&question)? {
+                println!("Left the save as it was");
+                return Ok(());
+            }
+            let (restored_from, _) = find_backup(&file);
+            let pet = restore_backup(&file)?;
+            println!("Restored {} from {}", pet.name, restored_from.display());
+            return Ok(());
+        }
+        Command::Companion { name } => {
+            let _lock = if config.lock {
+                Some(lock_state(&file)?)
+            } else {
+                None
+            };
+            let mut pet = load_state(&file).context(LOAD_FAILED)?;
+            if let Some(companion) = &pet.companion {
+                bail!("{} already lives with {}", pet.name, companion.name);
+            }
+            pet.companion = Some(Companion::new(name.clone()));
+            save_state_to(&file, &pet)?;
+            println!("{name} moved in with {}!", pet.name);
+            return Ok(());
+        }
+        Command::Checkin => {
+            let _lock = if config.lock {
+                Some(lock_state(&file)?)
+            } else {
+                None
+            };
+            let mut pet = load_state(&file).context(LOAD_FAILED)?;
+            check_in(&mut pet, &config, &clock)?;
+            backup_state(&file)?;
+            save_state_to(&file, &pet)?;
+            println!("Checked in on {}", pet.name);
+            return Ok(());
+        }
+        Command::ThemePreview { theme } => {
+            let name = theme.clone().unwrap_or_else(|| config.theme.clone());
+            let theme = config.find_theme(&name).ok_or_else(|| {
+                anyhow!(
+                    "Unknown theme: {name} (builtin themes: {})",
+                    Theme::BUILTIN_NAMES.join(", ")
+                )
+            })?;
+            return show_theme_preview(&name, &theme, AsciiMode::detect(cli.ascii));
+        }
+    };
+
+    // Held until main returns, including when unwinding from a panic.
+    // Read-only observers never write, so they don't need the lock.
+    let _lock = if config.lock && !cli.readonly {
+        Some(lock_state(&file)?)
+    } else {
+        None
+    };
+
+    // Anything but a missing save is reported rather than replaced by a new pet
+    let mut pet = match load_state(&file) {
+        Ok(pet) => {
+            // So `petty restore` can undo a bad catch-up or session
+            if !cli.readonly {
+                backup_state(&file)?;
+            }
+            pet
+        }
+        Err(Error::NotFound(_)) => adopt(pet_name, config.hardcore, &file)?,
+        Err(e) => return Err(anyhow::Error::new(e).context(LOAD_FAILED)),
+    };
+
+    let saved = pet.clone();
+    // Only a suspended save can hold a pet that ran away
+    if pet.status == PetStatus::Abandoned {
+        pet.come_home(clock.now());
+    }
+    catch_up(&mut pet, &config, &clock);
+    grace_feed(&mut pet, &config, clock.now());
+    let (start, started) = (pet.clone(), clock.now());
+
+    let outcome = if cli.command == Command::Watch {
+        let mut stdout = io::stdout().lock();
+        run_watch(&mut pet, &config, &mut stdout)
+            .await
+            .and_then(|()| Ok(stdout.flush()?))
+    } else if cli.command == Command::Daemon {
+        run_daemon(&mut pet, &config, &file).await
     } else {
-        "Petty".to_string()
+        let options = UiOptions {
+            readonly: cli.readonly,
+            ascii: AsciiMode::detect(cli.ascii),
+            state_file: Some(file.clone()),
+            reduced_motion: cli.reduced_motion || config.reduced_motion,
+            compact: cli.compact,
+            inline: cli.inline,
+            away: AwayDiff::between(&saved, &pet),
+            focus: match cli.command {
+                Command::Focus { work, rest } => Some((
+                    work.unwrap_or(config.focus.work_minutes * 60),
+                    rest.unwrap_or(config.focus.break_minutes * 60),
+                )),
+                _ => None,
+            },
+            tutorial: false,
+        };
+        run_ui(&mut pet, &config, &options).await
     };
 
-    let mut pet = load_state().unwrap_or_else(|_| Pet::new(pet_name));
+    close_session(&mut pet, &file, cli.readonly, &config, &clock, outcome)?;
+    let headless = matches!(cli.command, Command::Watch | Command::Daemon);
+    if config.exit_digest && !headless {
+        let digest = Digest::between(&start, &pet, clock.now() - started);
+        let lang = if AsciiMode::detect(cli.ascii).is_on() {
+            Lang::En
+        } else {
+            config.lang
+        };
+        for line in digest.lines(lang) {
+            println!("{line}");
+        }
+        if !cli.readonly && file.path.exists() {
+            println!("Saved {} to {}", pet.name, file.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Print what `petty validate` makes of the config and the save, failing
+/// if either has problems
+fn validate_files(profile: Option<&str>) -> Result<()> {
+    let (config_check, config) = validate::check_config(&get_config_path()?, profile);
+    // With the config broken, the save is looked for where it would be by default
+    let file = state_file(&config.unwrap_or_default())?;
+    let checks = vec![config_check, validate::check_state(&file)];
+    for check in &checks {
+        println!("{}", check.report());
+    }
+    validate::verdict(&checks).map_err(|problems| anyhow!("Validation failed: {problems}"))
+}
+
+/// Ask `question` on the terminal and wait for a yes. Anything else,
+/// including no answer at all, is a no.
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
 
-    // Check for abandonment and calculate elapsed time effects
-    let now = Utc::now();
+/// Check for abandonment and apply what happened since the pet was last seen
+fn catch_up(pet: &mut Pet, config: &Config, clock: &impl Clock) {
+    // `last_seen` is still saved, but nothing happens while petty is closed
+    if config.mode == TimeMode::Session {
+        return;
+    }
+    let now = clock.now();
     let duration_since_last_seen = now.signed_duration_since(pet.last_seen);
-    let elapsed_seconds = duration_since_last_seen.num_seconds();
+    if duration_since_last_seen < TimeDelta::zero() {
+        eprintln!(
+            "Warning: the clock is {}s behind when {} was last seen; counting no time as passed",
+            -duration_since_last_seen.num_seconds(),
+            pet.name
+        );
+    }
+    let elapsed_seconds = elapsed_seconds(pet.last_seen, now);
 
-    if elapsed_seconds > ABANDON_SECONDS {
+    if duration_since_last_seen > ABANDON_AFTER {
         pet.status = PetStatus::Abandoned;
     } else if pet.status == PetStatus::Alive && pet.health > 0 {
         // Apply state changes for elapsed time (when pet is alive and not dead)
-        apply_elapsed_time_effects(&mut pet, elapsed_seconds);
+        apply_elapsed_time_effects(pet, config, pet.last_seen, elapsed_seconds);
+    }
+}
+
+/// Give a pet that catch-up left starving one meal before the session
+/// starts, so the owner has time to react before hunger does the worst.
+/// Returns whether it was fed.
+fn grace_feed(pet: &mut Pet, config: &Config, now: DateTime<Utc>) -> bool {
+    if !config.grace_feed
+        || pet.hunger < GRACE_FEED_HUNGER
+        || pet.health == 0
+        || matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired)
+    {
+        return false;
+    }
+    let hunger = pet.hunger;
+    pet.feed(config);
+    pet.record(Event::GraceFed { hunger }, now);
+    true
+}
+
+/// Whole seconds from `last_seen` to `now`. A clock that was set back, or
+/// a save written by a machine whose clock ran ahead, puts `last_seen` in
+/// the future; that counts as no time at all rather than negative time.
+fn elapsed_seconds(last_seen: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    now.signed_duration_since(last_seen).num_seconds().max(0)
+}
+
+/// Reset the abandonment clock without a session of play. The time away
+/// still counts, so checking in can't be used to skip decay.
+fn check_in(pet: &mut Pet, config: &Config, clock: &impl Clock) -> Result<()> {
+    catch_up(pet, config, clock);
+    if pet.status == PetStatus::Abandoned {
+        let next = match config.on_abandon {
+            OnAbandon::Delete => "say goodbye",
+            OnAbandon::Suspend => "bring it home",
+        };
+        bail!("{} has already run away; run `petty` to {next}", pet.name);
+    }
+    if pet.health == 0 {
+        bail!("{} has passed away; run `petty` to say goodbye", pet.name);
+    }
+    if pet.status == PetStatus::Retired {
+        bail!("{} has retired; run `petty` to see it off", pet.name);
     }
+    pet.last_seen = clock.now();
+    Ok(())
+}
+
+/// A new pet called `name`, unless hardcore mode has already buried one by that name
+fn adopt(name: String, hardcore: bool, file: &StateFile) -> Result<Pet> {
+    if hardcore && is_buried(&load_graveyard(&graveyard_path(&file.path))?, &name) {
+        bail!(
+            "{name} died in hardcore mode and the name can't be used again.\n\
+             Adopt a pet with another name: `petty <name>`"
+        );
+    }
+    Ok(Pet::new(name))
+}
+
+/// Save the pet however the session ended, then report how it ended. A
+/// session that failed part way still keeps the progress made before it did.
+fn close_session(
+    pet: &mut Pet,
+    file: &StateFile,
+    readonly: bool,
+    config: &Config,
+    clock: &impl Clock,
+    outcome: Result<()>,
+) -> Result<()> {
+    let saved = finish_session(pet, file, readonly, config, clock);
+    if let Err(e) = outcome {
+        if let Err(save_error) = saved {
+            eprintln!("Could not save your pet: {save_error}");
+        }
+        return Err(e);
+    }
+    Ok(saved?)
+}
 
-    run_ui(&mut pet).await?;
+/// Persist the pet after the UI exits. Read-only sessions leave the state
+/// file untouched. In hardcore mode a death also goes in the graveyard, and
+/// a retired pet always goes in the hall of fame.
+fn finish_session(
+    pet: &mut Pet,
+    file: &StateFile,
+    readonly: bool,
+    config: &Config,
+    clock: &impl Clock,
+) -> error::Result<()> {
+    if readonly {
+        return Ok(());
+    }
 
-    // Handle post-run state
-    if pet.status == PetStatus::Abandoned || pet.health == 0 {
-        // If pet was abandoned or died, delete the state to start fresh next time
-        delete_state()?;
+    if config.hardcore && pet.health == 0 && pet.status != PetStatus::Abandoned {
+        bury(&graveyard_path(&file.path), Grave::of(pet, clock.now()))?;
+    }
+    let retired = pet.status == PetStatus::Retired;
+    if retired {
+        induct(&hall_of_fame_path(&file.path), Plaque::of(pet, clock.now()))?;
+    }
+
+    let abandoned = pet.status == PetStatus::Abandoned;
+    if pet.health == 0 || retired || (abandoned && config.on_abandon == OnAbandon::Delete) {
+        // If pet was abandoned, retired or died, delete the state to start fresh next time
+        delete_state_at(file)
     } else {
         // Otherwise, update last_seen and save
-        pet.last_seen = Utc::now();
-        save_state(&pet)?;
+        pet.last_seen = clock.now();
+        save_state_to(file, pet)
     }
+}
 
+/// Every achievement, unlocked ones ticked off
+fn achievement_lines(pet: &Pet, lang: Lang, paint: Paint) -> Vec<String> {
+    Achievement::ALL
+        .into_iter()
+        .map(|achievement| {
+            let (mark, style) = if pet.achievements.contains(&achievement) {
+                ("[x]", Style::Green)
+            } else {
+                ("[ ]", Style::Dim)
+            };
+            format!(
+                "{} {} - {}",
+                paint.paint(mark, style),
+                paint.paint(achievement.title(lang), Style::Bold),
+                achievement.description(lang)
+            )
+        })
+        .collect()
+}
+
+/// Write the pet as a single line of JSON, whatever the configured save format
+fn export_state(pet: &Pet, out: &mut impl Write) -> Result<()> {
+    serde_json::to_writer(&mut *out, pet)?;
+    writeln!(out)?;
     Ok(())
 }
 
-/// Apply state changes based on elapsed time
-fn apply_elapsed_time_effects(pet: &mut Pet, elapsed_seconds: i64) {
+/// Read a pet exported by `export_state`. Fields this version doesn't know
+/// about are rejected rather than silently dropped.
+fn import_state(input: impl Read) -> Result<Pet> {
+    let value: serde_json::Value =
+        serde_json::from_reader(input).map_err(|e| anyhow!("Not valid JSON: {e}"))?;
+    let pet: Pet =
+        serde_json::from_value(value.clone()).map_err(|e| anyhow!("Not a petty state: {e}"))?;
+    let known = serde_json::to_value(&pet)?;
+    if let (Some(fields), Some(known)) = (value.as_object(), known.as_object())
+        && let Some(field) = fields.keys().find(|field| !known.contains_key(*field))
+    {
+        bail!("Not a petty state: unknown field `{field}`");
+    }
+    Ok(pet)
+}
+
+/// Apply state changes for `elapsed_seconds` from `since`, each step at the
+/// time it would have happened. Negative time is treated as none.
+fn apply_elapsed_time_effects(
+    pet: &mut Pet,
+    config: &Config,
+    since: DateTime<Utc>,
+    elapsed_seconds: i64,
+) {
+    let elapsed_seconds = elapsed_seconds.max(0);
     // Calculate how many 3-second intervals have passed
     let intervals = elapsed_seconds / 3;
 
-    for _ in 0..intervals {
+    let mut awake_seconds = 0;
+    let mut allowance = SleepAllowance::new(config, elapsed_seconds);
+    let at = |second: i64| since + TimeDelta::seconds(second);
+    for interval in 1..=intervals {
         // Apply the same state changes as in the UI loop
-        pet.hunger = pet.hunger.saturating_add(2);
-        pet.cleanliness = pet.cleanliness.saturating_sub(3);
-
-        // Health decreases if stats are poor
-        // More nuanced health decline based on severity
-        if pet.hunger > 90 {
-            pet.health = pet.health.saturating_sub(3);
-        } else if pet.hunger > 80 {
-            pet.health = pet.health.saturating_sub(2);
-        } else if pet.hunger > 70 {
-            pet.health = pet.health.saturating_sub(1);
-        }
-
-        if pet.cleanliness < 10 {
-            pet.health = pet.health.saturating_sub(3);
-        } else if pet.cleanliness < 20 {
-            pet.health = pet.health.saturating_sub(2);
-        } else if pet.cleanliness < 30 {
-            pet.health = pet.health.saturating_sub(1);
-        }
-
-        if pet.mood < 10 {
-            pet.health = pet.health.saturating_sub(3);
-        } else if pet.mood < 20 {
-            pet.health = pet.health.saturating_sub(2);
-        } else if pet.mood < 30 {
-            pet.health = pet.health.saturating_sub(1);
-        }
-
-        // Age affects health decline - older pets decline faster
-        if pet.age > 50 {
-            // Elderly pet - health declines faster
-            if pet.hunger > 60 || pet.cleanliness < 40 || pet.mood < 40 {
-                pet.health = pet.health.saturating_sub(1);
+        if pet.is_sleeping {
+            let before = (pet.health, pet.mood);
+            // Second by second like the live tick, so healing lines up
+            for second in interval * 3 - 2..=interval * 3 {
+                pet.sleep_tick(config, second as u64, at(second));
+            }
+            allowance.spend(pet, before);
+        } else if pet.in_honeymoon(config, at(interval * 3)) {
+            // A new pet settles in before its needs start to run down
+        } else {
+            if (interval * 3) % MESS_INTERVAL as i64 == 0 {
+                pet.make_mess();
+            }
+            pet.decay_needs(config, at(interval * 3));
+            pet.caretake(config);
+            awake_seconds += 3;
+        }
+        if config.aging.mode == AgingMode::Care {
+            pet.care_tally.record(pet.care_score(&config.weights), 3);
+            if (interval * 3) % AGING_SECONDS as i64 == 0 {
+                pet.grow_older(config);
             }
-        } else if pet.age > 20 {
-            // Adult pet - normal health decline
-            // No additional effect
         }
 
         // Check for sickness when health is low
-        if pet.health < 20 && pet.status == PetStatus::Alive {
+        if pet.health < config.thresholds.health.danger && pet.status == PetStatus::Alive {
             pet.status = PetStatus::Sick;
         }
 
@@ -108,22 +664,622 @@ fn apply_elapsed_time_effects(pet: &mut Pet, elapsed_seconds: i64) {
         }
     }
 
-    // Apply aging (every 5 minutes = 300 seconds)
-    let aging_periods = elapsed_seconds / 300;
-    pet.age = pet.age.saturating_add(aging_periods as u32);
+    // Apply aging (every 5 minutes = 300 seconds); care-based aging was
+    // already counted period by period above
+    if config.aging.mode == AgingMode::Time {
+        let aging_periods = elapsed_seconds / AGING_SECONDS as i64;
+        pet.age = pet.age.saturating_add(aging_periods as u32);
+    }
+    pet.retire_if_due(config);
+
+    // Apply mood decline (every second awake), grumpier if the pet ended
+    // up hungry
+    let mood_decline = awake_seconds * (2 + i64::from(pet.grumpiness(config)));
+    pet.bore(config, mood_decline.min(255) as u8);
+}
+
+/// What's left of the health and of the mood sleep may give back while
+/// petty is closed, `rules.sleep.offline_cap` of each for every hour away.
+/// `None` is no limit.
+struct SleepAllowance(Option<(i64, i64)>);
+
+impl SleepAllowance {
+    fn new(config: &Config, elapsed_seconds: i64) -> Self {
+        let cap = i64::from(config.rules.sleep.offline_cap);
+        let each = elapsed_seconds.saturating_mul(cap) / 3600;
+        Self((cap > 0).then_some((each, each)))
+    }
+
+    /// Let `pet` keep what sleep gave it since it had `before` health and
+    /// mood, as far as the allowance goes, and take back the rest
+    fn spend(&mut self, pet: &mut Pet, before: (u8, u8)) {
+        let Some((health, mood)) = &mut self.0 else {
+            return;
+        };
+        for (stat, before, left) in [
+            (&mut pet.health, before.0, health),
+            (&mut pet.mood, before.1, mood),
+        ] {
+            if *stat > before {
+                let kept = i64::from(*stat - before).min(*left);
+                *left -= kept;
+                *stat = before + kept as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use clock::FixedClock;
+    use state::StateFormat;
+    use std::fs;
+
+    /// The clock the tests run by, stopped at a fixed instant so no test
+    /// depends on when it runs
+    fn test_clock() -> FixedClock {
+        FixedClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap())
+    }
+
+    /// A pet as `Pet::new` would have made it at `at`
+    fn adopted_at(at: DateTime<Utc>) -> Pet {
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.birth, pet.last_seen) = (at, at);
+        for entry in &mut pet.log {
+            entry.at = at;
+        }
+        pet
+    }
+
+    /// A moment past the honeymoon of `pet`, so its needs decay from the
+    /// first step
+    fn settled(pet: &Pet) -> DateTime<Utc> {
+        pet.birth + TimeDelta::hours(1)
+    }
+
+    #[test]
+    fn test_achievement_lines_mark_unlocked() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.achievements.insert(Achievement::SurvivedWeek);
+        let lines = achievement_lines(&pet, Lang::En, Paint(false));
+
+        assert_eq!(lines.len(), Achievement::ALL.len());
+        assert!(
+            lines.contains(&"[x] One week together - Keep your pet alive for 7 days".to_string())
+        );
+        assert!(
+            lines.iter().filter(|line| line.starts_with("[ ]")).count()
+                == Achievement::ALL.len() - 1
+        );
+
+        let colored = achievement_lines(&pet, Lang::En, Paint(true));
+        assert!(
+            colored
+                .iter()
+                .any(|line| line.starts_with("\x1b[32m[x]\x1b[0m"))
+        );
+    }
+
+    #[test]
+    fn test_check_in_applies_decay_once() {
+        let config = Config::default();
+        let clock = test_clock();
+        let now = clock.now();
+        let mut pet = adopted_at(now - chrono::TimeDelta::hours(1));
+        pet.last_seen = now - chrono::TimeDelta::minutes(1);
+
+        let mut expected = pet.clone();
+        apply_elapsed_time_effects(&mut expected, &config, pet.last_seen, 60);
+        assert_ne!(expected.hunger, pet.hunger);
+
+        check_in(&mut pet, &config, &clock).unwrap();
+        assert_eq!(pet.last_seen, now);
+        assert_eq!(pet.hunger, expected.hunger);
+        assert_eq!(pet.cleanliness, expected.cleanliness);
+
+        // Checking in again right away has nothing left to apply
+        let before = pet.clone();
+        check_in(&mut pet, &config, &clock).unwrap();
+        assert_eq!(pet, before);
+    }
+
+    #[test]
+    fn test_catch_up_ends_the_honeymoon_on_time() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        // Closed right after adoption, and opened again half a minute
+        // after the honeymoon ran out
+        let since = pet.birth;
+        let honeymoon = i64::from(config.rules.honeymoon_minutes) * 60;
+        let mut early = pet.clone();
+        apply_elapsed_time_effects(&mut early, &config, since, honeymoon - 3);
+        assert_eq!((early.hunger, early.mood, early.messes), (0, pet.mood, 0));
+        // Only the steps from the end of the honeymoon on make it hungry,
+        // bored or messy, as they would have with petty open
+        let mut bored = pet.clone();
+        apply_elapsed_time_effects(&mut pet, &config, since, honeymoon + 30);
+        assert_eq!(pet.hunger, 22);
+        bored.hunger = pet.hunger;
+        bored.bore(&config, 11 * 3 * (2 + bored.grumpiness(&config)));
+        assert_eq!(pet.mood, bored.mood);
+        // The only mess due after it, at 630 seconds
+        assert_eq!(pet.messes, 1);
+    }
+
+    #[test]
+    fn test_offline_sleep_follows_the_live_rules() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.hunger, pet.is_sleeping) = (50, 10, true);
+        let mut elder = pet.clone();
+        elder.age = 60;
+
+        let since = settled(&pet);
+        apply_elapsed_time_effects(&mut pet, &config, since, 30);
+        apply_elapsed_time_effects(&mut elder, &config, since, 30);
+        // Needs hold while asleep, and elderly pets heal at half the rate
+        assert_eq!((pet.hunger, pet.mood, pet.messes), (10, 100, 0));
+        assert_eq!(pet.health, 80);
+        assert_eq!(elder.health, 65);
+    }
+
+    #[test]
+    fn test_live_and_offline_sleep_agree() {
+        let mut config = Config::default();
+        config.rules.sleep.hunger = 1;
+        config.rules.sleep.cleanliness = 2;
+        config.rules.sleep.mood = 1;
+        let mut live = Pet::new("TestPet".to_string());
+        (live.health, live.hunger, live.cleanliness, live.mood) = (40, 10, 90, 50);
+        (live.fatigue, live.age, live.is_sleeping) = (80, 60, true);
+        let mut offline = live.clone();
+
+        let since = settled(&live);
+        for second in 1..=90 {
+            live.tick(&config, second, since + TimeDelta::seconds(second as i64));
+        }
+        apply_elapsed_time_effects(&mut offline, &config, since, 90);
+        let stats = |pet: &Pet| {
+            (
+                pet.health,
+                pet.hunger,
+                pet.cleanliness,
+                pet.mood,
+                pet.fatigue,
+            )
+        };
+        assert_eq!(stats(&offline), stats(&live));
+        assert_eq!(stats(&live), (85, 40, 30, 80, 0));
+    }
+
+    #[test]
+    fn test_care_mode_ages_only_a_cared_for_pet_offline() {
+        let mut config = Config::default();
+        config.aging.mode = AgingMode::Care;
+        let mut cared = Pet::new("TestPet".to_string());
+        let mut neglected = cared.clone();
+        (neglected.hunger, neglected.cleanliness, neglected.mood) = (100, 0, 0);
+
+        let since = cared.last_seen;
+        apply_elapsed_time_effects(&mut cared, &config, since, 600);
+        apply_elapsed_time_effects(&mut neglected, &config, since, 600);
+        assert_eq!((cared.age, neglected.age), (2, 0));
+    }
+
+    #[test]
+    fn test_caretaker_keeps_the_pet_fed_offline() {
+        let mut config = Config::default();
+        config.caretaker.enabled = true;
+        let mut pet = Pet::new("TestPet".to_string());
+        let mut broke = pet.clone();
+        pet.coins = 1000;
+
+        let since = settled(&pet);
+        apply_elapsed_time_effects(&mut pet, &config, since, 600);
+        apply_elapsed_time_effects(&mut broke, &config, since, 600);
+        assert!(pet.hunger <= config.caretaker.hunger);
+        assert!(pet.coins < 1000);
+        assert!(broke.hunger > config.caretaker.hunger);
+    }
+
+    #[test]
+    fn test_clock_going_backwards_passes_no_time() {
+        let clock = test_clock();
+        let now = clock.now();
+        let mut pet = adopted_at(now - chrono::TimeDelta::days(30));
+        (pet.hunger, pet.mood) = (40, 60);
+        for ahead in [
+            chrono::TimeDelta::hours(5),
+            chrono::TimeDelta::days(365 * 100),
+        ] {
+            let mut future = pet.clone();
+            future.last_seen = now + ahead;
+            let saved = future.clone();
+            catch_up(&mut future, &Config::default(), &clock);
+            assert_eq!(future, saved);
+            assert_eq!(elapsed_seconds(future.last_seen, now), 0);
+        }
+
+        apply_elapsed_time_effects(&mut pet, &Config::default(), now, i64::MIN);
+        assert_eq!((pet.age, pet.hunger, pet.mood), (0, 40, 60));
+    }
+
+    #[test]
+    fn test_session_mode_ignores_time_away() {
+        let clock = test_clock();
+        let now = clock.now();
+        let mut pet = adopted_at(now - chrono::TimeDelta::days(30));
+        pet.last_seen = now - chrono::TimeDelta::hours(1);
+        let mut forgotten = pet.clone();
+        forgotten.last_seen = now - ABANDON_AFTER * 2;
+
+        let session = Config {
+            mode: TimeMode::Session,
+            ..Config::default()
+        };
+        for pet in [&pet, &forgotten] {
+            let mut caught_up = pet.clone();
+            catch_up(&mut caught_up, &session, &clock);
+            assert_eq!(&caught_up, pet);
+        }
+
+        let realtime = Config::default();
+        catch_up(&mut pet, &realtime, &clock);
+        assert!(pet.hunger > 0);
+        catch_up(&mut forgotten, &realtime, &clock);
+        assert_eq!(forgotten.status, PetStatus::Abandoned);
+    }
+
+    #[test]
+    fn test_catch_up_applies_exactly_the_time_away() {
+        let config = Config::default();
+        let clock = test_clock();
+        let mut pet = adopted_at(clock.now() - chrono::TimeDelta::days(30));
+        pet.last_seen = clock.now();
+
+        clock.advance(chrono::TimeDelta::seconds(30));
+        catch_up(&mut pet, &config, &clock);
+        // Ten decay steps, then two points of boredom for each second
+        assert_eq!((pet.hunger, pet.cleanliness, pet.mood), (20, 70, 40));
+        assert_eq!(pet.age, 0);
+
+        let mut aged = adopted_at(pet.birth);
+        aged.last_seen = clock.now();
+        clock.advance(chrono::TimeDelta::minutes(5));
+        catch_up(&mut aged, &config, &clock);
+        assert_eq!(aged.age, 1);
+    }
+
+    #[test]
+    fn test_offline_sleep_recovery_is_capped() {
+        let mut config = Config::default();
+        config.rules.sleep.mood = 1;
+        config.rules.sleep.offline_cap = 10;
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.mood, pet.is_sleeping) = (50, 50, true);
+        let since = settled(&pet);
+
+        let mut away = pet.clone();
+        apply_elapsed_time_effects(&mut away, &config, since, 3600);
+        assert_eq!((away.health, away.mood), (60, 60));
+        // The allowance grows with the time away
+        let mut longer = pet.clone();
+        apply_elapsed_time_effects(&mut longer, &config, since, 3 * 3600);
+        assert_eq!((longer.health, longer.mood), (80, 80));
+
+        let mut uncapped = pet.clone();
+        config.rules.sleep.offline_cap = 0;
+        apply_elapsed_time_effects(&mut uncapped, &config, since, 3600);
+        assert_eq!((uncapped.health, uncapped.mood), (100, 100));
+
+        // Sleeping while petty is open is not held back
+        config.rules.sleep.offline_cap = 10;
+        for second in 1..=3600 {
+            pet.tick(&config, second, since + TimeDelta::seconds(second as i64));
+        }
+        assert_eq!((pet.health, pet.mood), (100, 100));
+    }
+
+    #[test]
+    fn test_the_clock_decides_when_the_honeymoon_ends() {
+        let config = Config::default();
+        let clock = test_clock();
+        let mut pet = adopted_at(clock.now());
+        let honeymoon = chrono::TimeDelta::minutes(config.rules.honeymoon_minutes.into());
+
+        clock.advance(honeymoon / 2);
+        check_in(&mut pet, &config, &clock).unwrap();
+        assert_eq!(pet.hunger, 0);
+        // Ten decay steps past the end of it, and the one at the end
+        clock.advance(honeymoon / 2 + chrono::TimeDelta::seconds(30));
+        check_in(&mut pet, &config, &clock).unwrap();
+        assert_eq!(pet.hunger, 22);
+    }
+
+    #[test]
+    fn test_abandonment_starts_right_after_the_limit() {
+        let config = Config::default();
+        let clock = test_clock();
+        let mut pet = adopted_at(clock.now());
+
+        clock.advance(ABANDON_AFTER);
+        let mut waited = pet.clone();
+        catch_up(&mut waited, &config, &clock);
+        assert_ne!(waited.status, PetStatus::Abandoned);
+
+        clock.advance(chrono::TimeDelta::seconds(1));
+        catch_up(&mut pet, &config, &clock);
+        assert_eq!(pet.status, PetStatus::Abandoned);
+    }
+
+    #[test]
+    fn test_grace_feed_helps_a_starving_pet_once() {
+        let mut config = Config::default();
+        let now = test_clock().now();
+        let mut pet = adopted_at(now);
+        pet.hunger = 100;
+        assert!(!grace_feed(&mut pet, &config, now));
+        assert_eq!(pet.hunger, 100);
+
+        config.grace_feed = true;
+        let recorded = pet.recorded;
+        assert!(grace_feed(&mut pet, &config, now));
+        assert!(pet.hunger < GRACE_FEED_HUNGER);
+        assert_eq!(pet.recorded, recorded + 1);
+        assert_eq!(
+            pet.log.last().unwrap().event,
+            Event::GraceFed { hunger: 100 }
+        );
+        // The meal leaves it below the threshold, so there's no second one
+        assert!(!grace_feed(&mut pet, &config, now));
 
-    // Apply mood decline (every second)
-    let mood_decline = elapsed_seconds * 2; // Mood drops faster
-    pet.mood = pet.mood.saturating_sub(mood_decline as u8);
+        let mut peckish = adopted_at(now);
+        peckish.hunger = GRACE_FEED_HUNGER - 1;
+        assert!(!grace_feed(&mut peckish, &config, now));
+    }
 
-    // Apply sleep healing if sleeping
-    if pet.is_sleeping {
-        let healing = elapsed_seconds; // 1 health point per second while sleeping
-        pet.health = pet.health.saturating_add(healing as u8);
+    #[test]
+    fn test_check_in_refuses_abandoned_pet() {
+        let config = Config::default();
+        let clock = test_clock();
+        let now = clock.now();
+        let mut pet = adopted_at(now - ABANDON_AFTER - chrono::TimeDelta::seconds(1));
+        assert!(check_in(&mut pet, &config, &clock).is_err());
+    }
 
-        // Elderly pets heal slower
-        if pet.age > 50 {
-            pet.health = pet.health.saturating_sub(healing as u8);
+    #[test]
+    fn test_naming_a_saved_pet_plays_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            active_pet: Some("Buddy".to_string()),
+            ..Config::default()
+        };
+        save_state_to(
+            &StateFile::in_dir(dir.path(), config.format),
+            &Pet::new("Petty".to_string()),
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join(state::PETS_DIR)).unwrap();
+        for name in ["Buddy", "Mochi"] {
+            let file = StateFile::for_pet(dir.path(), name, config.format);
+            save_state_to(&file, &Pet::new(name.to_string())).unwrap();
         }
+        let named = |name| with_named_pet(config.clone(), name, dir.path()).unwrap();
+
+        assert_eq!(named("Mochi").active_pet.as_deref(), Some("Mochi"));
+        // The first pet lives in the default save
+        assert_eq!(named("Petty").active_pet, None);
+        // A name with no save yet is adopted into the active one
+        assert_eq!(named("Rex").active_pet.as_deref(), Some("Buddy"));
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 42;
+        let mut exported = Vec::new();
+        export_state(&pet, &mut exported).unwrap();
+
+        assert_eq!(import_state(exported.as_slice()).unwrap(), pet);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_state() {
+        assert!(import_state("not json".as_bytes()).is_err());
+        assert!(import_state(r#"{"name": "TestPet"}"#.as_bytes()).is_err());
+        assert!(import_state("[1, 2, 3]".as_bytes()).is_err());
+
+        let mut exported = Vec::new();
+        export_state(&Pet::new("TestPet".to_string()), &mut exported).unwrap();
+        let mut value: serde_json::Value = serde_json::from_slice(&exported).unwrap();
+        value["hunger"] = serde_json::json!(300);
+        assert!(import_state(value.to_string().as_bytes()).is_err());
+        value["hunger"] = serde_json::json!(30);
+        value["gems"] = serde_json::json!(5);
+        let error = import_state(value.to_string().as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("gems"));
+    }
+
+    #[test]
+    fn test_error_kinds_exit_differently() {
+        let errors = [
+            Error::NotFound("state.json".into()),
+            Error::Corrupt {
+                path: "state.json".into(),
+                reason: "bad checksum".to_string(),
+            },
+            Error::Io(io::Error::other("disk full")),
+            Error::Serialization("unsupported".to_string()),
+            Error::NoHomeDir,
+            Error::Locked {
+                pid: "1".to_string(),
+                path: "state.json.lock".into(),
+            },
+        ];
+        let codes: std::collections::HashSet<u8> = errors.iter().map(exit_code).collect();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+        assert!(hint(&errors[5]).unwrap().contains("--readonly"));
+
+        let error = anyhow::Error::new(Error::NoHomeDir).context(LOAD_FAILED);
+        assert_eq!(error.to_string(), LOAD_FAILED);
+        assert!(matches!(error_kind(&error), Some(Error::NoHomeDir)));
+    }
+
+    #[test]
+    fn test_readonly_session_leaves_state_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let path = &file.path;
+        fs::write(path, "original").unwrap();
+
+        let mut pet = Pet::new("TestPet".to_string());
+        finish_session(&mut pet, &file, true, &Config::default(), &SystemClock).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "original");
+
+        // A dead pet's save is kept too
+        pet.health = 0;
+        finish_session(&mut pet, &file, true, &Config::default(), &SystemClock).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "original");
+    }
+
+    #[test]
+    fn test_failed_session_still_saves_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 42;
+        let outcome = Err(anyhow!("draw failed"));
+        let error = close_session(
+            &mut pet,
+            &file,
+            false,
+            &Config::default(),
+            &SystemClock,
+            outcome,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.to_string(), "draw failed");
+        assert_eq!(load_state(&file).unwrap().hunger, 42);
+    }
+
+    #[test]
+    fn test_normal_session_saves_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let path = &file.path;
+        fs::write(path, "original").unwrap();
+
+        let mut pet = Pet::new("TestPet".to_string());
+        finish_session(&mut pet, &file, false, &Config::default(), &SystemClock).unwrap();
+        assert_ne!(fs::read_to_string(path).unwrap(), "original");
+
+        pet.health = 0;
+        finish_session(&mut pet, &file, false, &Config::default(), &SystemClock).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_hardcore_death_retires_the_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+
+        let hardcore = Config {
+            hardcore: true,
+            ..Config::default()
+        };
+
+        let mut pet = Pet::new("Mochi".to_string());
+        pet.health = 0;
+        finish_session(&mut pet, &file, false, &hardcore, &SystemClock).unwrap();
+
+        let error = adopt("Mochi".to_string(), true, &file).unwrap_err();
+        assert!(error.to_string().contains("Mochi died in hardcore mode"));
+        assert!(adopt("Tofu".to_string(), true, &file).is_ok());
+    }
+
+    #[test]
+    fn test_retired_pet_enters_the_hall_of_fame() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut config = Config::default();
+        config.retirement.enabled = true;
+
+        let clock = test_clock();
+        let mut pet = adopted_at(clock.now());
+        pet.name = "Mochi".to_string();
+        pet.age = config.retirement.age - 1;
+        save_state_to(&file, &pet).unwrap();
+        // Five minutes away is enough to age into retirement
+        clock.advance(TimeDelta::minutes(5));
+        catch_up(&mut pet, &config, &clock);
+        assert_eq!(pet.status, PetStatus::Retired);
+
+        finish_session(&mut pet, &file, false, &config, &clock).unwrap();
+        let plaques = hall::load_hall_of_fame(&hall_of_fame_path(&file.path)).unwrap();
+        assert_eq!(plaques.len(), 1);
+        assert_eq!((plaques[0].name.as_str(), plaques[0].age), ("Mochi", 100));
+        // The save is freed for the next pet
+        assert!(!file.path.exists());
+        assert!(adopt("Mochi".to_string(), true, &file).is_ok());
+    }
+
+    #[test]
+    fn test_normal_death_keeps_the_name_free() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+
+        let mut pet = Pet::new("Mochi".to_string());
+        pet.health = 0;
+        finish_session(&mut pet, &file, false, &Config::default(), &SystemClock).unwrap();
+        assert!(adopt("Mochi".to_string(), true, &file).is_ok());
+
+        // Turning hardcore off lets a buried name come back
+        let hardcore = Config {
+            hardcore: true,
+            ..Config::default()
+        };
+        finish_session(&mut pet, &file, false, &hardcore, &SystemClock).unwrap();
+        assert!(adopt("Mochi".to_string(), false, &file).is_ok());
+    }
+
+    #[test]
+    fn test_abandoned_pet_is_deleted_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+
+        let mut pet = Pet::new("TestPet".to_string());
+        save_state_to(&file, &pet).unwrap();
+        pet.status = PetStatus::Abandoned;
+        finish_session(&mut pet, &file, false, &Config::default(), &SystemClock).unwrap();
+        assert!(!file.path.exists());
+    }
+
+    #[test]
+    fn test_suspended_pet_waits_to_come_home() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let config = Config {
+            on_abandon: OnAbandon::Suspend,
+            ..Config::default()
+        };
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.status = PetStatus::Abandoned;
+        finish_session(&mut pet, &file, false, &config, &SystemClock).unwrap();
+        let mut saved = load_state(&file).unwrap();
+        assert_eq!(saved.status, PetStatus::Abandoned);
+
+        let error = check_in(&mut saved.clone(), &config, &SystemClock).unwrap_err();
+        assert!(error.to_string().contains("bring it home"));
+
+        // A dead pet is still deleted
+        saved.health = 0;
+        finish_session(&mut saved, &file, false, &config, &SystemClock).unwrap();
+        assert!(!file.path.exists());
     }
 }
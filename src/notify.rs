@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+/// Minimum time between two desktop notifications
+pub const NOTIFY_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// Tracks what was sent so notifications fire on the transition into
+/// critical health, and at most once per cooldown
+#[derive(Debug, Default)]
+pub struct Notifier {
+    was_critical: bool,
+    last_sent: Option<Instant>,
+}
+
+impl Notifier {
+    /// Whether a notification should go out now given the current health state
+    pub fn should_notify(&mut self, critical: bool, now: Instant, cooldown: Duration) -> bool {
+        let rising = critical && !self.was_critical;
+        self.was_critical = critical;
+
+        let cooled_down = self
+            .last_sent
+            .is_none_or(|sent| now.duration_since(sent) >= cooldown);
+        if rising && cooled_down {
+            self.last_sent = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// Show a desktop notification without blocking the caller
+#[cfg(feature = "desktop-notify")]
+pub fn send(summary: String, body: String) {
+    std::thread::spawn(move || {
+        // Nothing useful to do if the notification daemon isn't available
+        notify_rust::Notification::new()
+            .appname("petty")
+            .summary(&summary)
+            .body(&body)
+            .show()
+            .ok();
+    });
+}
+
+#[cfg(not(feature = "desktop-notify"))]
+pub fn send(_summary: String, _body: String) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifies_on_rising_edge_only() {
+        let mut notifier = Notifier::default();
+        let now = Instant::now();
+
+        assert!(!notifier.should_notify(false, now, NOTIFY_COOLDOWN));
+        assert!(notifier.should_notify(true, now, NOTIFY_COOLDOWN));
+        // Staying critical doesn't notify again
+        let later = now + NOTIFY_COOLDOWN * 2;
+        assert!(!notifier.should_notify(true, later, NOTIFY_COOLDOWN));
+    }
+
+    #[test]
+    fn test_notifications_are_rate_limited() {
+        let mut notifier = Notifier::default();
+        let now = Instant::now();
+        let cooldown = Duration::from_secs(60);
+
+        assert!(notifier.should_notify(true, now, cooldown));
+        notifier.should_notify(false, now + Duration::from_secs(1), cooldown);
+        // Dipping back into critical within the cooldown is suppressed
+        assert!(!notifier.should_notify(true, now + Duration::from_secs(2), cooldown));
+        notifier.should_notify(false, now + Duration::from_secs(3), cooldown);
+        assert!(notifier.should_notify(true, now + Duration::from_secs(61), cooldown));
+    }
+}
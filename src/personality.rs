@@ -0,0 +1,121 @@
+use crate::i18n::Lang;
+use serde::{Deserialize, Serialize};
+
+/// How far a trait has to lean before it shows in who the pet is
+pub const NOTICEABLE: i8 = 20;
+/// Share of care actions, in thousandths, that's play for a pet that's
+/// neither energetic nor laid-back: one in four
+pub const USUAL_PLAY_SHARE: i32 = 250;
+
+/// Who the pet is growing into. Each trait leans between the
+/// `max_drift` of `[personality]` either way and starts out at 0.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct Traits {
+    /// Energetic when above 0, laid-back below, after how much of its
+    /// care is play
+    pub energy: i8,
+    /// Cheerful or gloomy, after the mood it's kept in
+    pub cheer: i8,
+    /// Fastidious or scruffy, after how clean it's kept
+    pub tidiness: i8,
+    /// Seconds of care towards the next step of drift
+    pub seconds: u32,
+}
+
+impl Traits {
+    /// Where care with `play_share` thousandths of play, at `mood` and
+    /// `cleanliness`, would take each trait if kept up forever
+    pub fn targets(play_share: u32, mood: u8, cleanliness: u8) -> Self {
+        let play = play_share.min(1000) as i32;
+        Self {
+            energy: lean((play - USUAL_PLAY_SHARE) * 100 / USUAL_PLAY_SHARE),
+            cheer: lean((i32::from(mood) - 50) * 2),
+            tidiness: lean((i32::from(cleanliness) - 50) * 2),
+            seconds: 0,
+        }
+    }
+
+    /// Lean each trait one step further towards `targets`, going no
+    /// further than `max_drift` either way
+    pub fn drift_towards(&mut self, targets: Traits, max_drift: u8) {
+        self.energy = drift(self.energy, targets.energy, max_drift);
+        self.cheer = drift(self.cheer, targets.cheer, max_drift);
+        self.tidiness = drift(self.tidiness, targets.tidiness, max_drift);
+    }
+
+    /// The traits that show, like "energetic, cheerful"
+    pub fn describe(&self, lang: Lang) -> Vec<&'static str> {
+        let pick = |value: i8, high: [&'static str; 2], low: [&'static str; 2]| {
+            let words = if value >= NOTICEABLE {
+                high
+            } else if value <= -NOTICEABLE {
+                low
+            } else {
+                return None;
+            };
+            Some(match lang {
+                Lang::Zh => words[0],
+                Lang::En => words[1],
+            })
+        };
+        [
+            pick(self.energy, ["活泼", "energetic"], ["慵懒", "laid-back"]),
+            pick(self.cheer, ["开朗", "cheerful"], ["忧郁", "gloomy"]),
+            pick(self.tidiness, ["爱干净", "fastidious"], ["邋遢", "scruffy"]),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// `value` one step closer to `target`, both held within `max` either way
+pub fn drift(value: i8, target: i8, max: u8) -> i8 {
+    let max = max.min(100) as i8;
+    let target = target.clamp(-max, max);
+    value
+        .saturating_add(target.saturating_sub(value).signum())
+        .clamp(-max, max)
+}
+
+fn lean(value: i32) -> i8 {
+    value.clamp(-100, 100) as i8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_moves_one_step_within_bounds() {
+        assert_eq!(drift(0, 50, 60), 1);
+        assert_eq!(drift(10, -100, 60), 9);
+        assert_eq!(drift(7, 7, 60), 7);
+        // Never past the bound, even from a save that went further
+        assert_eq!(drift(60, 100, 60), 60);
+        assert_eq!(drift(-90, -100, 60), -60);
+        assert_eq!(drift(i8::MIN, i8::MAX, 100), -100);
+        assert_eq!(drift(5, 100, 0), 0);
+    }
+
+    #[test]
+    fn test_targets_follow_the_care() {
+        let playful = Traits::targets(1000, 100, 0);
+        assert_eq!(
+            (playful.energy, playful.cheer, playful.tidiness),
+            (100, 100, -100)
+        );
+        let usual = Traits::targets(USUAL_PLAY_SHARE as u32, 50, 50);
+        assert_eq!((usual.energy, usual.cheer, usual.tidiness), (0, 0, 0));
+        assert_eq!(Traits::targets(0, 50, 50).energy, -100);
+
+        let traits = Traits {
+            energy: NOTICEABLE,
+            tidiness: -NOTICEABLE,
+            ..Traits::default()
+        };
+        assert_eq!(traits.describe(Lang::En), ["energetic", "scruffy"]);
+        assert!(Traits::default().describe(Lang::En).is_empty());
+    }
+}
@@ -1,14 +1,312 @@
-use chrono::{DateTime, Utc};
+use crate::achievements::{self, Achievement};
+use crate::bond::{self, BOND_STREAK_SECONDS, MAX_BOND};
+use crate::config::{
+    AgingMode, Config, DANGER_TIER, GrowthSpurts, Medicine, NeedWeights, Threshold, Thresholds,
+    Variety,
+};
+use crate::favorites::{FAVORITE_MEAL, FAVORITE_MOOD, Favorites, Food};
+use crate::gallery::{self, Snapshot};
+use crate::habits::{self, Habits};
+use crate::history::{Event, LogEntry, push_entry};
+use crate::personality::Traits;
+use crate::rng;
+use crate::skills::Skills;
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub enum PetStatus {
     Alive,
     Sick,
     Abandoned,
+    /// Lived to the retirement age and moved to the hall of fame
+    Retired,
 }
 
-#[derive(Serialize, Deserialize)]
+/// How long the owner can stay away before the pet leaves for good
+pub const ABANDON_AFTER: TimeDelta = TimeDelta::days(3);
+
+/// Mood a pet that ran away comes home with, at most
+pub const HOMECOMING_MOOD: u8 = 20;
+
+/// Time left before a pet last seen at `last_seen` is abandoned, never negative
+pub fn abandonment_remaining(last_seen: DateTime<Utc>, now: DateTime<Utc>) -> TimeDelta {
+    (ABANDON_AFTER - now.signed_duration_since(last_seen)).clamp(TimeDelta::zero(), ABANDON_AFTER)
+}
+
+/// Coins earned by every care action
+pub const COINS_PER_ACTION: u32 = 1;
+/// Coins a spa day costs
+pub const SPA_COST: u32 = 10;
+/// Mood from the pampering at a spa day
+pub const SPA_MOOD: u8 = 15;
+/// Health from the pampering at a spa day
+pub const SPA_HEALTH: u8 = 5;
+
+/// Elderly pets regain a health point for this many seconds of sleep
+/// instead of every second
+pub const ELDERLY_HEAL_SECONDS: u64 = 2;
+
+/// Health below which the pet is considered in critical condition
+pub const CRITICAL_HEALTH: u8 = 10;
+/// Health a sick pet must climb back to before it recovers
+pub const RECOVERED_HEALTH: u8 = 50;
+/// Loneliness from which the pet looks it
+pub const WISTFUL: f32 = 0.5;
+
+/// Grams a newly adopted pet weighs
+pub const BIRTH_WEIGHT: u32 = 500;
+
+fn birth_weight() -> u32 {
+    BIRTH_WEIGHT
+}
+
+/// A care action the owner can perform
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Feed,
+    Wash,
+    Play,
+    Train,
+    /// Puts an awake pet to bed
+    Sleep,
+    /// The only action a sleeping pet takes
+    Wake,
+    /// A bath, some pampering and a little health, paid for in coins
+    Spa,
+    /// A dose of health, limited by the `[medicine]` allowance
+    Medicine,
+    /// A bite between meals: less filling than `Feed`, but no digesting
+    Snack,
+}
+
+/// A need that costs health once it is neglected
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Need {
+    Hunger,
+    Cleanliness,
+    Mood,
+}
+
+impl Need {
+    /// The care action that takes care of this need
+    pub fn action(self) -> Action {
+        match self {
+            Need::Hunger => Action::Feed,
+            Need::Cleanliness => Action::Wash,
+            Need::Mood => Action::Play,
+        }
+    }
+}
+
+/// A need counts as urgent once it is this many points from hurting health
+pub const URGENT_MARGIN: i16 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LifeStage {
+    Young,
+    Adult,
+    Elderly,
+}
+
+impl LifeStage {
+    pub fn for_age(age: u32) -> Self {
+        if age > 50 {
+            LifeStage::Elderly
+        } else if age > 20 {
+            LifeStage::Adult
+        } else {
+            LifeStage::Young
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LifeStage::Young => "young",
+            LifeStage::Adult => "adult",
+            LifeStage::Elderly => "elderly",
+        }
+    }
+}
+
+/// Seconds awake between new messes
+pub const MESS_INTERVAL: u64 = 90;
+/// Messes stop piling up past this many
+pub const MAX_MESSES: u8 = 5;
+
+/// Every multiple of this age is celebrated as a milestone
+pub const AGE_MILESTONE: u32 = 10;
+/// Seconds of play or time away per year of age
+pub const AGING_SECONDS: u64 = 300;
+
+/// A notable state transition that happened during a tick
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum TickEvent {
+    Aged,
+    /// Entered a new life stage
+    GrewUp(LifeStage),
+    /// Reached a round age
+    Milestone(u32),
+    GotSick,
+    /// Recovered from sickness
+    Healed,
+    ReachedCritical,
+    Died,
+    /// Reached the retirement age
+    Retired,
+    /// Sulked long enough to hide; see `Pet::sulk`
+    WentIntoHiding,
+    /// Coaxed back out of hiding
+    CameOut,
+    /// Left a new mess to clean up
+    MadeMess,
+    /// Too tired to stay awake and fell asleep on its own
+    DozedOff,
+    /// The caretaker fed the pet
+    AutoFed,
+    /// The caretaker washed the pet
+    AutoWashed,
+}
+
+/// What a single tick changed. Deltas are `after - before`.
+#[derive(Serialize, Clone, Default, PartialEq, Debug)]
+pub struct TickReport {
+    pub health: i16,
+    pub hunger: i16,
+    pub cleanliness: i16,
+    pub mood: i16,
+    pub events: Vec<TickEvent>,
+}
+
+impl TickReport {
+    pub fn has(&self, event: TickEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+/// The care score added up second by second since the pet last had a
+/// chance to age, for `AgingMode::Care`
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct CareTally {
+    pub sum: u32,
+    pub seconds: u32,
+}
+
+impl CareTally {
+    /// Count `seconds` at care score `score`
+    pub fn record(&mut self, score: u8, seconds: u32) {
+        self.sum = self.sum.saturating_add(u32::from(score) * seconds);
+        self.seconds = self.seconds.saturating_add(seconds);
+    }
+
+    /// The average score, or 0 with nothing recorded
+    pub fn average(&self) -> u32 {
+        self.sum.checked_div(self.seconds).unwrap_or(0)
+    }
+}
+
+/// The medicine given lately, kept in the save so it can't be spammed
+/// into endless health
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Doses {
+    /// Doses taken in the allowance that started at `since`
+    pub taken: u32,
+    pub since: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+}
+
+impl Doses {
+    /// When the allowance fills up again, if any of it's been used
+    pub fn refills_at(&self, rules: &Medicine) -> Option<DateTime<Utc>> {
+        self.since
+            .map(|since| since + TimeDelta::hours(rules.refresh_hours.into()))
+    }
+
+    /// Doses left at `now`
+    pub fn left(&self, rules: &Medicine, now: DateTime<Utc>) -> u32 {
+        match self.refills_at(rules) {
+            Some(refill) if refill > now => rules.daily_doses.saturating_sub(self.taken),
+            _ => rules.daily_doses,
+        }
+    }
+
+    /// When the next dose can be taken, if not at `now`: once the last one
+    /// has cooled down, or once the allowance refills if it's used up
+    pub fn ready_at(&self, rules: &Medicine, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let ready = if self.left(rules, now) == 0 {
+            self.refills_at(rules)?
+        } else {
+            self.last? + TimeDelta::minutes(rules.cooldown_minutes.into())
+        };
+        (ready > now).then_some(ready)
+    }
+
+    /// Use up a dose at `now`. Returns whether there was one to take.
+    pub fn take(&mut self, rules: &Medicine, now: DateTime<Utc>) -> bool {
+        if self.left(rules, now) == 0 || self.ready_at(rules, now).is_some() {
+            return false;
+        }
+        if self.refills_at(rules).is_none_or(|refill| refill <= now) {
+            (self.taken, self.since) = (0, Some(now));
+        }
+        self.taken += 1;
+        self.last = Some(now);
+        true
+    }
+}
+
+/// The ways of cheering the pet up that it can get bored of
+pub const PASTIMES: [Action; 3] = [Action::Play, Action::Train, Action::Spa];
+/// Pastimes remembered at most, however quickly they come
+pub const MAX_PASTIMES: usize = 10;
+
+/// A go at one of the `PASTIMES`, remembered for `Pet::variety`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Pastime {
+    pub action: Action,
+    pub at: DateTime<Utc>,
+}
+
+/// Share of a pastime's mood the pet still gets after `repeats` goes in a
+/// row at it under `rules`
+pub fn variety_benefit(repeats: usize, rules: &Variety) -> f64 {
+    (1.0 - rules.repeat_penalty * repeats as f64).max(rules.min_benefit)
+}
+
+/// Whether a pet `age` years old is in the middle of a growth spurt under
+/// `spurts`: every `every` years while young, not counting its first
+pub fn growth_spurt(age: u32, spurts: &GrowthSpurts) -> bool {
+    spurts.enabled
+        && age > 0
+        && LifeStage::for_age(age) == LifeStage::Young
+        && age.is_multiple_of(spurts.every.max(1))
+}
+
+/// Companion hunger above which it starts dragging the main pet's mood down
+pub const COMPANION_NEGLECTED: u8 = 80;
+/// Mood the main pet gains or loses per decay step because of its companion
+pub const COMPANION_MOOD: u8 = 2;
+
+/// A second pet sharing the home. It only needs feeding, eats from the
+/// same bowl, and keeps the main pet company while both are doing well.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Companion {
+    pub name: String,
+    pub hunger: u8,
+}
+
+impl Companion {
+    pub fn new(name: String) -> Self {
+        Self { name, hunger: 0 }
+    }
+
+    pub fn is_neglected(&self) -> bool {
+        self.hunger > COMPANION_NEGLECTED
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Pet {
     pub name: String,
     pub age: u32,
@@ -18,13 +316,93 @@ pub struct Pet {
     pub mood: u8,
     pub is_sleeping: bool,
     pub status: PetStatus,
+    /// When this state was last saved. Offline catch-up and abandonment
+    /// are measured from here.
     pub last_seen: DateTime<Utc>,
     pub debug_mode: bool,
+    #[serde(default = "Utc::now")]
+    pub birth: DateTime<Utc>,
+    #[serde(default)]
+    pub log: Vec<LogEntry>,
+    /// Uncleaned messes; each one drags cleanliness down until washed away
+    #[serde(default)]
+    pub messes: u8,
+    /// Consecutive decay steps spent hungry enough to hurt health
+    #[serde(default)]
+    pub starving_ticks: u32,
+    #[serde(default)]
+    pub companion: Option<Companion>,
+    #[serde(default)]
+    pub achievements: HashSet<Achievement>,
+    /// Seconds in a row every stat has stayed above 80 (hunger below 20)
+    #[serde(default)]
+    pub high_stats_seconds: u32,
+    /// Seed for the pet's random events; see `PetRng`
+    #[serde(default = "rng::new_seed")]
+    pub rng_seed: u64,
+    /// How many random numbers the pet has drawn so far
+    #[serde(default)]
+    pub rng_draws: u64,
+    /// Rises while awake and only sleep brings it back down
+    #[serde(default)]
+    pub fatigue: u8,
+    /// A snapshot for each life stage reached, oldest first
+    #[serde(default)]
+    pub gallery: Vec<Snapshot>,
+    /// Entries ever added to `log`, counting the ones since dropped
+    #[serde(default)]
+    pub recorded: u64,
+    /// Experience from training
+    #[serde(default)]
+    pub skills: Skills,
+    /// Earned by caring for the pet and spent on the caretaker
+    #[serde(default)]
+    pub coins: u32,
+    /// What the pet has learned to expect from its owner
+    #[serde(default)]
+    pub habits: Habits,
+    #[serde(default)]
+    pub favorites: Favorites,
+    /// Grows slowly with steady care and never wears off; see `bond`
+    #[serde(default)]
+    pub bond: u32,
+    #[serde(default)]
+    pub care_tally: CareTally,
+    /// Sulking out of sight; it won't be washed or trained until coaxed out
+    #[serde(default)]
+    pub hiding: bool,
+    /// Seconds in a row spent sulking, or while hiding, being coaxed out
+    #[serde(default)]
+    pub sulk_seconds: u32,
+    #[serde(default)]
+    pub doses: Doses,
+    /// Seconds awake since the owner last pressed a key, while
+    /// `loneliness` is on
+    #[serde(default)]
+    pub idle_seconds: u32,
+    /// The pastimes done lately, oldest first, while `variety` is on
+    #[serde(default)]
+    pub pastimes: Vec<Pastime>,
+    /// Who the pet is growing into; see `personality`
+    #[serde(default)]
+    pub traits: Traits,
+    /// The year the pet's last birthday was celebrated in, so it only gets
+    /// the bonus once
+    #[serde(default)]
+    pub birthday_celebrated: Option<i32>,
+    /// When the last full meal has gone down, under `[meals]`
+    #[serde(default)]
+    pub digested_at: Option<DateTime<Utc>>,
+    /// Grams, put on by full meals; a snack is too small to show
+    #[serde(default = "birth_weight")]
+    pub weight: u32,
 }
 
 impl Pet {
     pub fn new(name: String) -> Self {
-        Self {
+        let now = Utc::now();
+        let rng_seed = rng::new_seed();
+        let mut pet = Self {
             name,
             age: 0,
             health: 100,
@@ -33,18 +411,315 @@ impl Pet {
             mood: 100,
             is_sleeping: false,
             status: PetStatus::Alive,
-            last_seen: Utc::now(),
+            last_seen: now,
             debug_mode: false,
+            birth: now,
+            log: Vec::new(),
+            messes: 0,
+            starving_ticks: 0,
+            companion: None,
+            achievements: HashSet::new(),
+            high_stats_seconds: 0,
+            rng_seed,
+            rng_draws: 0,
+            fatigue: 0,
+            gallery: Vec::new(),
+            recorded: 0,
+            skills: Skills::default(),
+            coins: 0,
+            habits: Habits::default(),
+            favorites: Favorites::for_seed(rng_seed),
+            bond: 0,
+            care_tally: CareTally::default(),
+            hiding: false,
+            sulk_seconds: 0,
+            doses: Doses::default(),
+            idle_seconds: 0,
+            pastimes: Vec::new(),
+            traits: Traits::default(),
+            birthday_celebrated: None,
+            digested_at: None,
+            weight: BIRTH_WEIGHT,
+        };
+        pet.record(Event::Adopted, now);
+        pet
+    }
+
+    /// Take back a pet whose save was kept after it ran away. It comes
+    /// home sad, and the clock restarts so the days it was gone aren't
+    /// counted as neglect all over again.
+    pub fn come_home(&mut self, now: DateTime<Utc>) {
+        self.status = PetStatus::Alive;
+        self.mood = self.mood.min(HOMECOMING_MOOD);
+        self.last_seen = now;
+    }
+
+    /// Add an entry to the pet's event log
+    pub fn record(&mut self, event: Event, at: DateTime<Utc>) {
+        push_entry(&mut self.log, event, at);
+        self.recorded += 1;
+    }
+
+    /// Log every transition in a tick report
+    pub fn record_report(&mut self, report: &TickReport, at: DateTime<Utc>) {
+        for event in &report.events {
+            self.record(Event::Tick(*event), at);
         }
     }
 
-    pub fn feed(&mut self) {
-        self.hunger = self.hunger.saturating_sub(20);
-        self.health = self.health.saturating_add(5);
+    /// Whether `action` would take effect right now. Anything it would
+    /// cost is only charged when it does.
+    pub fn can_act(&self, action: Action) -> bool {
+        match action {
+            Action::Feed => self.can_feed(),
+            Action::Wash => self.can_wash(),
+            Action::Play => self.can_play(),
+            Action::Train => self.can_train(),
+            Action::Sleep => self.can_sleep(),
+            Action::Wake => self.can_wake(),
+            Action::Spa => self.can_spa(),
+            Action::Medicine => self.can_take_medicine(),
+            Action::Snack => self.can_feed(),
+        }
+    }
+
+    /// Still around to be looked after, and not frozen for debugging
+    fn is_in_care(&self) -> bool {
+        !self.debug_mode
+            && self.health > 0
+            && !matches!(self.status, PetStatus::Abandoned | PetStatus::Retired)
+    }
+
+    /// Awake and in care; a sleeping pet can only be woken
+    fn is_awake_in_care(&self) -> bool {
+        !self.is_sleeping && self.is_in_care()
+    }
+
+    pub fn can_feed(&self) -> bool {
+        self.is_awake_in_care()
+    }
+
+    /// A hiding pet won't come out for a bath
+    pub fn can_wash(&self) -> bool {
+        self.is_awake_in_care() && !self.hiding
+    }
+
+    pub fn can_play(&self) -> bool {
+        self.is_awake_in_care()
+    }
+
+    /// Nor for training
+    pub fn can_train(&self) -> bool {
+        self.is_awake_in_care() && !self.hiding
+    }
+
+    pub fn can_sleep(&self) -> bool {
+        self.is_awake_in_care()
+    }
+
+    pub fn can_wake(&self) -> bool {
+        self.is_sleeping && self.is_in_care()
+    }
+
+    /// A spa day also takes `SPA_COST` coins
+    pub fn can_spa(&self) -> bool {
+        self.is_awake_in_care() && self.coins >= SPA_COST
+    }
+
+    /// Whether there's anyone awake to give medicine to. Whether there's
+    /// a dose left is up to `doses`.
+    pub fn can_take_medicine(&self) -> bool {
+        self.is_awake_in_care()
+    }
+
+    /// Whether feeding would do nothing for the hunger of a pet (and a
+    /// companion) that is already full
+    pub fn feed_would_waste(&self) -> bool {
+        self.hunger == 0 && self.companion.as_ref().is_none_or(|c| c.hunger == 0)
+    }
+
+    /// Whether washing would do nothing for a pet that is already spotless
+    pub fn wash_would_waste(&self) -> bool {
+        self.cleanliness >= 100 && self.messes == 0
+    }
+
+    /// Whether `action` would be wasted right now
+    pub fn would_waste(&self, action: Action) -> bool {
+        match action {
+            Action::Feed => self.feed_would_waste(),
+            Action::Wash => self.wash_would_waste(),
+            Action::Medicine => self.health >= 100,
+            Action::Snack => self.hunger == 0,
+            _ => false,
+        }
+    }
+
+    /// Perform a care action, applying the life-stage modifiers and logging
+    /// it. Actions the pet can't take right now are ignored.
+    pub fn act(&mut self, action: Action, config: &Config, at: DateTime<Utc>) {
+        if !self.can_act(action) {
+            return;
+        }
+        // Out of doses, or too soon after the last, medicine does nothing
+        if action == Action::Medicine && !self.doses.take(&config.medicine, at) {
+            return;
+        }
+        // Nor will a pet still digesting a full meal eat anything
+        if matches!(action, Action::Feed | Action::Snack) && self.digesting(at).is_some() {
+            return;
+        }
+        let event = Event::for_action(action, self.hunger, self.cleanliness, self.mood);
+        let elderly = self.life_stage() == "elderly";
+        let (variety, mood) = (self.variety(action, config, at), self.mood);
+
+        match action {
+            Action::Feed => {
+                self.feed(config);
+                if Food::on_menu(self.birth, at) == self.favorites.food {
+                    self.hunger = self.hunger.saturating_sub(FAVORITE_MEAL);
+                }
+                // Elderly pets get less benefit from feeding
+                if elderly {
+                    self.health = self.health.saturating_sub(2);
+                }
+                let minutes = config.meals.digest_minutes;
+                if minutes > 0 {
+                    self.digested_at = Some(at + TimeDelta::minutes(minutes.into()));
+                }
+            }
+            Action::Snack => self.snack(config),
+            Action::Wash => {
+                self.wash();
+                // Elderly pets get stressed from washing
+                if elderly {
+                    self.mood = self.mood.saturating_sub(5);
+                }
+            }
+            Action::Play => {
+                self.play();
+                // Elderly pets get tired more easily
+                if elderly {
+                    self.health = self.health.saturating_sub(3);
+                }
+            }
+            Action::Train => {
+                self.train();
+                // Elderly pets get tired more easily from training
+                if elderly {
+                    self.health = self.health.saturating_sub(2);
+                }
+            }
+            Action::Sleep => self.sleep(),
+            Action::Wake => self.wake(),
+            Action::Spa => {
+                self.spend(SPA_COST);
+                self.spa();
+            }
+            Action::Medicine => {
+                self.health = self.health.saturating_add(config.medicine.heal).min(100);
+            }
+        }
+        if action == self.favorites.activity {
+            self.mood = self.mood.saturating_add(FAVORITE_MOOD).min(100);
+        }
+        if self.mood > mood {
+            let gain = f64::from(self.mood - mood) * variety;
+            self.mood = mood + gain.round() as u8;
+        }
+        self.remember_pastime(action, config, at);
+        if action != Action::Spa {
+            self.coins = self.coins.saturating_add(COINS_PER_ACTION);
+        }
+        self.habits.observe(action, at);
+        self.strengthen_bond(bond::earned_by(action));
+
+        self.record(event, at);
+    }
+
+    pub fn feed(&mut self, config: &Config) {
+        self.starving_ticks = 0;
+        // A companion shares the meal, so each gets a smaller portion
+        let meal = config.meals.meal;
+        let portion: u8 = match &mut self.companion {
+            Some(companion) => {
+                let share = (u16::from(meal) * 3 / 4) as u8;
+                companion.hunger = companion.hunger.saturating_sub(share);
+                share
+            }
+            None => meal,
+        };
+        let portion = (f32::from(portion) * self.appetite(config)).round() as u8;
+        self.hunger = self.hunger.saturating_sub(portion);
+        self.weight = self.weight.saturating_add(config.meals.weight_gain);
+        let health = self.recover(config.rules.recovery.feed_health, config);
+        self.health = self.health.saturating_add(health);
+    }
+
+    /// A bite between meals, under `[meals]`: it does less for hunger than
+    /// a meal, and nothing for health
+    pub fn snack(&mut self, config: &Config) {
+        self.starving_ticks = 0;
+        let bite = (f32::from(config.meals.snack) * self.appetite(config)).round() as u8;
+        self.hunger = self.hunger.saturating_sub(bite);
+    }
+
+    /// When the last full meal will have gone down, if it hasn't by `now`
+    pub fn digesting(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.digested_at.filter(|done| *done > now)
+    }
+
+    /// `amount` of health scaled by how badly the pet needs it under
+    /// `rules.recovery`: up to `1 + coupling` times as much at no health,
+    /// as little as `1 - coupling` times at full health
+    pub fn recover(&self, amount: u8, config: &Config) -> u8 {
+        let need = (50.0 - f32::from(self.health)) / 50.0;
+        let scale = 1.0 + config.rules.recovery.coupling * need;
+        (f32::from(amount) * scale).round() as u8
+    }
+
+    /// Share of a meal the pet eats: all of it, unless `rules.couplings`
+    /// is on and the pet is too filthy or miserable to have much appetite
+    pub fn appetite(&self, config: &Config) -> f32 {
+        let couplings = &config.rules.couplings;
+        let put_off = self.cleanliness <= couplings.filthy || self.mood <= couplings.miserable;
+        if couplings.enabled && put_off {
+            couplings.appetite
+        } else {
+            1.0
+        }
+    }
+
+    /// Extra mood the pet loses each second awake from being hungry, under
+    /// `rules.couplings`
+    pub fn grumpiness(&self, config: &Config) -> u8 {
+        let couplings = &config.rules.couplings;
+        if couplings.enabled && self.hunger >= couplings.hungry {
+            couplings.grumpiness
+        } else {
+            0
+        }
     }
 
     pub fn wash(&mut self) {
         self.cleanliness = 100;
+        self.messes = 0;
+    }
+
+    /// Wash the pet and pamper it: the mood of playing without the hunger,
+    /// and a little health on top
+    pub fn spa(&mut self) {
+        self.wash();
+        self.mood = self.mood.saturating_add(SPA_MOOD).min(100);
+        self.health = self.health.saturating_add(SPA_HEALTH).min(100);
+    }
+
+    /// Leave a new mess unless the cap is reached. Returns whether one was added.
+    pub fn make_mess(&mut self) -> bool {
+        if self.messes >= MAX_MESSES {
+            return false;
+        }
+        self.messes += 1;
+        true
     }
 
     pub fn play(&mut self) {
@@ -53,7 +728,20 @@ impl Pet {
     }
 
     pub fn sleep(&mut self) {
-        self.is_sleeping = !self.is_sleeping;
+        self.is_sleeping = true;
+    }
+
+    pub fn wake(&mut self) {
+        self.is_sleeping = false;
+    }
+
+    /// Seconds of sleep per health point regained
+    pub fn seconds_per_heal(&self) -> u64 {
+        if self.stage() == LifeStage::Elderly {
+            ELDERLY_HEAL_SECONDS
+        } else {
+            1
+        }
     }
 
     pub fn train(&mut self) {
@@ -61,22 +749,513 @@ impl Pet {
         self.health = self.health.saturating_add(3);
         self.mood = self.mood.saturating_add(5);
         self.hunger = self.hunger.saturating_add(10);
+        self.skills.train();
     }
 
-    pub fn life_stage(&self) -> &'static str {
-        if self.age > 50 {
-            "elderly"
-        } else if self.age > 20 {
-            "adult"
+    /// Advance the simulation by one second of play, the one ending at
+    /// `now`. `second` counts the seconds since the session started and
+    /// decides which periodic rules (aging every 5 minutes, needs every 3
+    /// seconds) apply this tick.
+    pub fn tick(&mut self, config: &Config, second: u64, now: DateTime<Utc>) -> TickReport {
+        let (health, hunger, cleanliness, mood) =
+            (self.health, self.hunger, self.cleanliness, self.mood);
+        let mut events = Vec::new();
+
+        if config.aging.mode == AgingMode::Care {
+            self.care_tally.record(self.care_score(&config.weights), 1);
+        }
+        // Age the pet every 5 minutes (300 seconds)
+        let stage = self.stage();
+        if second > 0 && second.is_multiple_of(AGING_SECONDS) && self.grow_older(config) {
+            events.push(TickEvent::Aged);
+            if self.stage() != stage {
+                events.push(TickEvent::GrewUp(self.stage()));
+            }
+            if self.age.is_multiple_of(AGE_MILESTONE) {
+                events.push(TickEvent::Milestone(self.age));
+            }
+            if self.retire_if_due(config) {
+                events.push(TickEvent::Retired);
+            }
+        }
+
+        if self.is_sleeping {
+            self.sleep_tick(config, second, now);
+        } else if self.in_honeymoon(config, now) {
+            // A new pet settles in before its needs start to run down
+        } else {
+            // Mood drops faster, and faster still on an empty stomach
+            self.bore(config, 2 + self.grumpiness(config));
+            if second > 0 && second.is_multiple_of(MESS_INTERVAL) && self.make_mess() {
+                events.push(TickEvent::MadeMess);
+            }
+            if second.is_multiple_of(3) {
+                self.decay_needs(config, now);
+                if self.is_sleeping {
+                    events.push(TickEvent::DozedOff);
+                }
+                events.extend(self.caretake(config));
+            }
+        }
+
+        events.extend(self.sulk(config));
+        self.grow_personality(config);
+        if config.loneliness.enabled && self.is_awake_in_care() {
+            self.idle_seconds = self.idle_seconds.saturating_add(1);
+        }
+
+        if self.health < config.thresholds.health.danger && self.status == PetStatus::Alive {
+            self.status = PetStatus::Sick;
+            events.push(TickEvent::GotSick);
+        } else if self.health >= RECOVERED_HEALTH && self.status == PetStatus::Sick {
+            self.status = PetStatus::Alive;
+            events.push(TickEvent::Healed);
+        }
+
+        if self.health > 80 && self.hunger < 20 && self.cleanliness > 80 && self.mood > 80 {
+            self.high_stats_seconds = self.high_stats_seconds.saturating_add(1);
+            if self.high_stats_seconds.is_multiple_of(BOND_STREAK_SECONDS) {
+                self.strengthen_bond(1);
+            }
+        } else {
+            self.high_stats_seconds = 0;
+        }
+
+        if self.health == 0 && health > 0 {
+            events.push(TickEvent::Died);
+        } else if self.health < CRITICAL_HEALTH && health >= CRITICAL_HEALTH {
+            events.push(TickEvent::ReachedCritical);
+        }
+
+        TickReport {
+            health: self.health as i16 - health as i16,
+            hunger: self.hunger as i16 - hunger as i16,
+            cleanliness: self.cleanliness as i16 - cleanliness as i16,
+            mood: self.mood as i16 - mood as i16,
+            events,
+        }
+    }
+
+    /// Whether `now` is within `rules.honeymoon_minutes` of adoption
+    pub fn in_honeymoon(&self, config: &Config, now: DateTime<Utc>) -> bool {
+        let honeymoon = TimeDelta::minutes(config.rules.honeymoon_minutes.into());
+        now.signed_duration_since(self.birth) < honeymoon
+    }
+
+    /// One 3-second step of need decay, the one ending at `now`, shared by
+    /// the live ticker and offline catch-up. Nothing decays during the
+    /// honeymoon.
+    pub fn decay_needs(&mut self, config: &Config, now: DateTime<Utc>) {
+        if self.in_honeymoon(config, now) {
+            return;
+        }
+        // Needs get worse faster with messes around
+        let hunger_step = if config.rules.habits.enabled {
+            self.habits.hunger_step(now)
+        } else {
+            habits::HUNGER_STEP
+        };
+        let spurt = if self.in_growth_spurt(config) {
+            config.growth_spurts.hunger
+        } else {
+            0
+        };
+        self.hunger = self
+            .hunger
+            .saturating_add(hunger_step)
+            .saturating_add(spurt);
+        self.cleanliness = self.cleanliness.saturating_sub(3 + self.messes);
+        self.tire(config);
+
+        // Health decreases if stats are poor, and recovers if they are all good
+        self.decline_health(config);
+        self.thrive(config);
+        self.keep_company();
+    }
+
+    /// Lower mood by `amount`, stopping at the baseline a playful routine
+    /// has built up. A mood already below it isn't raised.
+    pub fn bore(&mut self, config: &Config, amount: u8) {
+        let habits = &config.rules.habits;
+        let baseline = if habits.enabled {
+            self.habits.mood_baseline(habits.max_mood_baseline)
+        } else {
+            0
+        };
+        self.mood = self
+            .mood
+            .saturating_sub(amount)
+            .max(baseline.min(self.mood));
+    }
+
+    /// Let the caretaker feed or wash an awake pet whose need has crossed
+    /// its threshold, paying `caretaker.cost` coins each time. Nothing is
+    /// done once the coins run out.
+    pub fn caretake(&mut self, config: &Config) -> Vec<TickEvent> {
+        let caretaker = &config.caretaker;
+        let mut events = Vec::new();
+        if !caretaker.enabled || self.is_sleeping {
+            return events;
+        }
+        if caretaker.feed && self.hunger >= caretaker.hunger && self.spend(caretaker.cost) {
+            self.feed(config);
+            events.push(TickEvent::AutoFed);
+        }
+        if caretaker.wash && self.cleanliness <= caretaker.cleanliness && self.spend(caretaker.cost)
+        {
+            self.wash();
+            events.push(TickEvent::AutoWashed);
+        }
+        events
+    }
+
+    /// Add a year at the end of an aging period, unless `AgingMode::Care`
+    /// finds the care over the period fell short. Returns whether the pet
+    /// aged.
+    pub fn grow_older(&mut self, config: &Config) -> bool {
+        let cared = match config.aging.mode {
+            AgingMode::Time => true,
+            AgingMode::Care => self.care_tally.average() >= u32::from(config.aging.min_care),
+        };
+        self.care_tally = CareTally::default();
+        if cared {
+            self.age = self.age.saturating_add(1);
+        }
+        cared
+    }
+
+    /// How lonely the pet is, from 0 to 1: none for `after_minutes` of
+    /// `idle_seconds`, then more and more until `full_minutes` later
+    pub fn loneliness(&self, config: &Config) -> f32 {
+        let rules = &config.loneliness;
+        if !rules.enabled {
+            return 0.0;
+        }
+        let after = f64::from(rules.after_minutes) * 60.0;
+        let lonely =
+            (f64::from(self.idle_seconds) - after) / (f64::from(rules.full_minutes) * 60.0);
+        lonely.clamp(0.0, 1.0) as f32
+    }
+
+    /// One second of the pet's upbringing: every `step_minutes` of care
+    /// each trait leans a step towards what that care has been like
+    fn grow_personality(&mut self, config: &Config) {
+        let rules = &config.personality;
+        if !rules.enabled || !self.is_in_care() {
+            return;
+        }
+        self.traits.seconds = self.traits.seconds.saturating_add(1);
+        if self.traits.seconds < rules.step_minutes.saturating_mul(60) {
+            return;
+        }
+        self.traits.seconds = 0;
+        let targets = Traits::targets(self.habits.play_share, self.mood, self.cleanliness);
+        self.traits.drift_towards(targets, rules.max_drift);
+    }
+
+    /// Share of `action`'s mood the pet would get at `now`: all of it,
+    /// unless `variety` is on and it's a pastime the pet has just been
+    /// doing over and over
+    pub fn variety(&self, action: Action, config: &Config, now: DateTime<Utc>) -> f64 {
+        let rules = &config.variety;
+        if !rules.enabled || !PASTIMES.contains(&action) {
+            return 1.0;
+        }
+        let since = now - TimeDelta::minutes(rules.memory_minutes.into());
+        let repeats = self
+            .pastimes
+            .iter()
+            .rev()
+            .take_while(|pastime| pastime.action == action && pastime.at > since)
+            .count();
+        variety_benefit(repeats, rules)
+    }
+
+    fn remember_pastime(&mut self, action: Action, config: &Config, at: DateTime<Utc>) {
+        if !config.variety.enabled || !PASTIMES.contains(&action) {
+            return;
+        }
+        self.pastimes.push(Pastime { action, at });
+        if self.pastimes.len() > MAX_PASTIMES {
+            let excess = self.pastimes.len() - MAX_PASTIMES;
+            self.pastimes.drain(..excess);
+        }
+    }
+
+    /// The owner did something, so the pet isn't lonely any more
+    pub fn interact(&mut self) {
+        self.idle_seconds = 0;
+    }
+
+    /// One second of the owner's attention under `sulking`: hide after
+    /// `hide_after_seconds` in a row at a miserable mood, come back out
+    /// after `coax_seconds` in a row at `coax_mood`. Only the live ticker
+    /// calls this, so a pet sulks at an owner who is there to see it.
+    pub fn sulk(&mut self, config: &Config) -> Option<TickEvent> {
+        let sulking = &config.sulking;
+        if !sulking.enabled || !self.is_in_care() {
+            self.sulk_seconds = 0;
+            return None;
+        }
+        let (streak, needed) = if self.hiding {
+            (self.mood >= sulking.coax_mood, sulking.coax_seconds)
+        } else {
+            (self.mood <= sulking.mood, sulking.hide_after_seconds)
+        };
+        if !streak {
+            self.sulk_seconds = 0;
+            return None;
+        }
+        self.sulk_seconds += 1;
+        if self.sulk_seconds < needed {
+            return None;
+        }
+        self.sulk_seconds = 0;
+        self.hiding = !self.hiding;
+        Some(if self.hiding {
+            TickEvent::WentIntoHiding
+        } else {
+            TickEvent::CameOut
+        })
+    }
+
+    /// Retire a living pet that has reached the configured age. Returns
+    /// whether it retired just now.
+    pub fn retire_if_due(&mut self, config: &Config) -> bool {
+        let due = config.retirement.enabled && self.age >= config.retirement.age;
+        let living = matches!(self.status, PetStatus::Alive | PetStatus::Sick) && self.health > 0;
+        if due && living {
+            self.status = PetStatus::Retired;
+        }
+        due && living
+    }
+
+    /// Add `amount` to the bond, up to `MAX_BOND`
+    pub fn strengthen_bond(&mut self, amount: u32) {
+        self.bond = self.bond.saturating_add(amount).min(MAX_BOND);
+    }
+
+    /// Take `amount` coins if there are enough
+    fn spend(&mut self, amount: u32) -> bool {
+        let Some(left) = self.coins.checked_sub(amount) else {
+            return false;
+        };
+        self.coins = left;
+        true
+    }
+
+    /// A well-fed companion cheers up a healthy pet; a hungry one gets it down
+    fn keep_company(&mut self) {
+        let Some(companion) = &mut self.companion else {
+            return;
+        };
+        companion.hunger = companion.hunger.saturating_add(3);
+        if companion.is_neglected() {
+            self.mood = self.mood.saturating_sub(COMPANION_MOOD);
+        } else if self.status == PetStatus::Alive && self.health >= RECOVERED_HEALTH {
+            self.mood = self.mood.saturating_add(COMPANION_MOOD).min(100);
+        }
+    }
+
+    /// Build up fatigue while awake, falling asleep on the spot once it
+    /// reaches `rules.fatigue.collapse`
+    fn tire(&mut self, config: &Config) {
+        if self.is_sleeping {
+            return;
+        }
+        let fatigue = &config.rules.fatigue;
+        let spurt = if self.in_growth_spurt(config) {
+            config.growth_spurts.fatigue
+        } else {
+            0
+        };
+        self.fatigue = self
+            .fatigue
+            .saturating_add(fatigue.rise)
+            .saturating_add(spurt)
+            .min(100);
+        if self.fatigue >= fatigue.collapse {
+            self.is_sleeping = true;
+        }
+    }
+
+    /// Sleep off `seconds` worth of fatigue
+    pub fn rest(&mut self, config: &Config, seconds: u32) {
+        let relief = u32::from(config.rules.fatigue.relief).saturating_mul(seconds);
+        self.fatigue = self.fatigue.saturating_sub(relief.min(100) as u8);
+    }
+
+    /// One second asleep under `rules.sleep`, the one ending at `now`, with
+    /// `second` deciding whether it heals and whether it's a decay step.
+    /// Needs stay put during the honeymoon like they do awake.
+    pub fn sleep_tick(&mut self, config: &Config, second: u64, now: DateTime<Utc>) {
+        let sleep = &config.rules.sleep;
+        if second.is_multiple_of(self.seconds_per_heal()) {
+            let heal = self.recover(sleep.heal, config);
+            self.health = self.health.saturating_add(heal).min(100);
+        }
+        self.rest(config, 1);
+        if !second.is_multiple_of(3) {
+            return;
+        }
+        if !self.in_honeymoon(config, now) {
+            self.hunger = self.hunger.saturating_add(sleep.hunger).min(100);
+            self.cleanliness = self.cleanliness.saturating_sub(sleep.cleanliness);
+        }
+        self.mood = self.mood.saturating_add(sleep.mood).min(100);
+    }
+
+    /// Recover a little health and mood while every need is in the
+    /// `rules.thriving` range. An overtired pet only gets the mood.
+    pub fn thrive(&mut self, config: &Config) {
+        let thriving = &config.rules.thriving;
+        if self.hunger > thriving.max_hunger
+            || self.cleanliness < thriving.min_cleanliness
+            || self.mood < thriving.min_mood
+        {
+            return;
+        }
+        if self.fatigue < config.rules.fatigue.tired {
+            self.health = self.health.saturating_add(thriving.health).min(100);
+        }
+        // A lonely pet doesn't cheer up on its own as much
+        let mood = f32::from(thriving.mood) * (1.0 - self.loneliness(config));
+        self.mood = self.mood.saturating_add(mood.round() as u8).min(100);
+    }
+
+    /// The need closest to its danger zone in `decline_health`, if any is
+    /// within `URGENT_MARGIN` points of it. Ties go to hunger, then
+    /// cleanliness, then mood.
+    pub fn most_urgent_need(&self, thresholds: &Thresholds) -> Option<Need> {
+        // Points left before each need starts costing health
+        let danger = |threshold: Threshold| i16::from(threshold.danger);
+        let margins = [
+            (
+                Need::Hunger,
+                danger(thresholds.hunger) - DANGER_TIER + 1 - self.hunger as i16,
+            ),
+            (
+                Need::Cleanliness,
+                self.cleanliness as i16 - (danger(thresholds.cleanliness) + DANGER_TIER - 1),
+            ),
+            (
+                Need::Mood,
+                self.mood as i16 - (danger(thresholds.mood) + DANGER_TIER - 1),
+            ),
+        ];
+        margins
+            .into_iter()
+            .filter(|(_, margin)| *margin <= URGENT_MARGIN)
+            .min_by_key(|(_, margin)| *margin)
+            .map(|(need, _)| need)
+    }
+
+    /// One second of the pet's life as every live loop runs it: the tick,
+    /// logged, then the achievements and snapshots it earned. Returns the
+    /// tick's report and the achievements it unlocked.
+    pub fn live(
+        &mut self,
+        config: &Config,
+        second: u64,
+        now: DateTime<Utc>,
+    ) -> (TickReport, Vec<Achievement>) {
+        let report = self.tick(config, second, now);
+        self.record_report(&report, now);
+        let unlocked = self.unlock_achievements(&report, now);
+        self.capture_growth(&report, now);
+        (report, unlocked)
+    }
+
+    /// Add a gallery snapshot for each life stage this tick reached
+    pub fn capture_growth(&mut self, report: &TickReport, now: DateTime<Utc>) {
+        let snapshots = gallery::capture(self, report, now);
+        self.gallery.extend(snapshots);
+    }
+
+    /// Unlock every achievement earned by this tick, returning the new ones
+    pub fn unlock_achievements(
+        &mut self,
+        report: &TickReport,
+        now: DateTime<Utc>,
+    ) -> Vec<Achievement> {
+        achievements::earned(self, report, now)
+            .into_iter()
+            .filter(|achievement| self.achievements.insert(*achievement))
+            .collect()
+    }
+
+    /// Overall wellbeing from 0 to 100, weighting each need by `weights`
+    pub fn care_score(&self, weights: &NeedWeights) -> u8 {
+        let [hunger, cleanliness, mood] = weights.normalized();
+        let score = hunger * (100 - self.hunger.min(100)) as f32
+            + cleanliness * self.cleanliness.min(100) as f32
+            + mood * self.mood.min(100) as f32;
+        score.round().clamp(0.0, 100.0) as u8
+    }
+
+    /// Lose health for every need in its danger zone. The base penalty for
+    /// each need is scaled by its normalized weight, so the default equal
+    /// weights keep the original 1-3 point penalties. When two or more
+    /// needs are in danger at once the combined penalty is multiplied by
+    /// `rules.compound_neglect`. Hunger only starts hurting once it has
+    /// stayed high for more than `rules.starving_grace` steps in a row.
+    pub fn decline_health(&mut self, config: &Config) {
+        let [hunger, cleanliness, mood] = config.weights.normalized();
+        let scaled = |penalty: u8, weight: f32| penalty as f32 * weight * 3.0;
+
+        let thresholds = &config.thresholds;
+        let mut hunger_penalty = thresholds.hunger.penalty_above(self.hunger);
+        if hunger_penalty > 0 {
+            self.starving_ticks = self.starving_ticks.saturating_add(1);
         } else {
-            "young"
+            self.starving_ticks = 0;
+        }
+        if self.starving_ticks <= config.rules.starving_grace {
+            hunger_penalty = 0;
+        }
+
+        let cleanliness_penalty = thresholds.cleanliness.penalty_below(self.cleanliness);
+        let mood_penalty = thresholds.mood.penalty_below(self.mood);
+
+        let penalties = [hunger_penalty, cleanliness_penalty, mood_penalty];
+        let mut penalty = scaled(hunger_penalty, hunger).round()
+            + scaled(cleanliness_penalty, cleanliness).round()
+            + scaled(mood_penalty, mood).round();
+        // The two highest tiers are the danger zone
+        if penalties.iter().filter(|p| **p >= 2).count() >= 2 {
+            penalty *= config.rules.compound_neglect;
         }
+        self.health = self.health.saturating_sub(penalty.round() as u8);
+
+        // Age affects health decline - older pets decline faster
+        let warned = self.hunger > thresholds.hunger.warn
+            || self.cleanliness < thresholds.cleanliness.warn
+            || self.mood < thresholds.mood.warn;
+        if self.age > 50 && warned {
+            self.health = self.health.saturating_sub(1);
+        }
+    }
+
+    pub fn stage(&self) -> LifeStage {
+        LifeStage::for_age(self.age)
+    }
+
+    /// Whether the pet is shooting up right now, see `growth_spurt`
+    pub fn in_growth_spurt(&self, config: &Config) -> bool {
+        growth_spurt(self.age, &config.growth_spurts)
+    }
+
+    pub fn life_stage(&self) -> &'static str {
+        self.stage().as_str()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Meals;
+    use crate::personality::NOTICEABLE;
+    use crate::rng::PetRng;
 
     #[test]
     fn test_pet_creation() {
@@ -107,10 +1286,306 @@ mod tests {
     fn test_feed() {
         let mut pet = Pet::new("TestPet".to_string());
         pet.hunger = 50;
-        pet.feed();
+        pet.feed(&Config::default());
         assert_eq!(pet.hunger, 30);
     }
 
+    #[test]
+    fn test_meals_do_more_for_a_sick_pet() {
+        let mut config = Config::default();
+        let healed_by_a_meal = |health: u8, status: PetStatus, config: &Config| {
+            let mut pet = Pet::new("TestPet".to_string());
+            (pet.health, pet.status) = (health, status);
+            pet.feed(config);
+            pet.health - health
+        };
+        let sick = healed_by_a_meal(20, PetStatus::Sick, &config);
+        let healthy = healed_by_a_meal(90, PetStatus::Alive, &config);
+        assert!(sick > healthy, "{sick} vs {healthy}");
+        assert_eq!(healed_by_a_meal(50, PetStatus::Alive, &config), 5);
+
+        // No coupling is the old flat bonus
+        config.rules.recovery.coupling = 0.0;
+        for health in [5, 20, 50, 90] {
+            assert_eq!(healed_by_a_meal(health, PetStatus::Alive, &config), 5);
+        }
+    }
+
+    #[test]
+    fn test_filthy_pet_eats_less() {
+        let mut config = Config::default();
+        config.rules.couplings.enabled = true;
+        let eaten = |cleanliness: u8, mood: u8, config: &Config| {
+            let mut pet = Pet::new("TestPet".to_string());
+            (pet.hunger, pet.cleanliness, pet.mood) = (80, cleanliness, mood);
+            pet.feed(config);
+            80 - pet.hunger
+        };
+        assert_eq!(eaten(100, 100, &config), 20);
+        assert_eq!(eaten(10, 100, &config), 10);
+        assert_eq!(eaten(100, 10, &config), 10);
+
+        config.rules.couplings.enabled = false;
+        assert_eq!(eaten(10, 10, &config), 20);
+    }
+
+    #[test]
+    fn test_starving_pet_sulks_faster() {
+        let mut config = Config::default();
+        config.rules.honeymoon_minutes = 0;
+        config.rules.couplings.enabled = true;
+        let mood_lost = |hunger: u8, config: &Config| {
+            let mut pet = Pet::new("TestPet".to_string());
+            (pet.hunger, pet.mood) = (hunger, 100);
+            // Second 1 skips the periodic decay, leaving only the mood drop
+            pet.tick(config, 1, pet.birth);
+            100 - pet.mood
+        };
+        let (starving, fed) = (mood_lost(95, &config), mood_lost(0, &config));
+        assert!(starving > fed, "{starving} vs {fed}");
+
+        config.rules.couplings.enabled = false;
+        assert_eq!(mood_lost(95, &config), mood_lost(0, &config));
+    }
+
+    #[test]
+    fn test_medicine_runs_out_until_it_refreshes() {
+        let config = Config::default();
+        let rules = &config.medicine;
+        let start = Utc::now();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 10;
+        let cooled =
+            |doses: i64| start + TimeDelta::minutes(doses * i64::from(rules.cooldown_minutes));
+
+        pet.act(Action::Medicine, &config, start);
+        assert_eq!(pet.health, 10 + rules.heal);
+        // Straight away again is too soon
+        pet.act(Action::Medicine, &config, start + TimeDelta::seconds(1));
+        assert_eq!(pet.health, 10 + rules.heal);
+        assert_eq!(pet.doses.left(rules, start), rules.daily_doses - 1);
+
+        for dose in 1..i64::from(rules.daily_doses) {
+            pet.act(Action::Medicine, &config, cooled(dose));
+        }
+        let health = pet.health;
+        assert_eq!(pet.doses.left(rules, cooled(3)), 0);
+        // Used up, it does nothing however long the cooldown's been over
+        let later = start + TimeDelta::hours(i64::from(rules.refresh_hours) - 1);
+        pet.act(Action::Medicine, &config, later);
+        assert_eq!(pet.health, health);
+        assert_eq!(
+            pet.doses.ready_at(rules, later),
+            pet.doses.refills_at(rules)
+        );
+
+        let refreshed = start + TimeDelta::hours(rules.refresh_hours.into());
+        assert_eq!(pet.doses.left(rules, refreshed), rules.daily_doses);
+        pet.act(Action::Medicine, &config, refreshed);
+        assert_eq!(pet.health, health + rules.heal);
+        assert_eq!(pet.doses.left(rules, refreshed), rules.daily_doses - 1);
+    }
+
+    #[test]
+    fn test_a_full_meal_has_to_go_down_before_the_next() {
+        let config = Config::default();
+        let meals = &config.meals;
+        let mut pet = Pet::new("TestPet".to_string());
+        // Not today's menu, so the meal is a plain one
+        pet.favorites.food = Food::Cookies;
+        let start = pet.birth;
+        pet.hunger = 60;
+
+        pet.act(Action::Snack, &config, start);
+        assert_eq!(pet.hunger, 60 - meals.snack);
+        assert_eq!(pet.weight, BIRTH_WEIGHT);
+        assert_eq!(pet.digesting(start), None);
+        pet.act(Action::Feed, &config, start);
+        assert_eq!(pet.hunger, 60 - meals.snack - meals.meal);
+        assert_eq!(pet.weight, BIRTH_WEIGHT + meals.weight_gain);
+        let done = start + TimeDelta::minutes(meals.digest_minutes.into());
+        assert_eq!(pet.digesting(start), Some(done));
+
+        // Not even a snack goes down until the meal has
+        let hunger = pet.hunger;
+        let soon = done - TimeDelta::minutes(1);
+        pet.act(Action::Snack, &config, soon);
+        pet.act(Action::Feed, &config, soon);
+        assert_eq!(pet.hunger, hunger);
+        assert_eq!(pet.digesting(done), None);
+        pet.act(Action::Snack, &config, done);
+        assert_eq!(pet.hunger, hunger - meals.snack);
+
+        // With no time to digest, meals can come back to back
+        let mut hungry = config.clone();
+        hungry.meals.digest_minutes = 0;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.favorites.food = Food::Cookies;
+        pet.hunger = 60;
+        pet.act(Action::Feed, &hungry, start);
+        pet.act(Action::Feed, &hungry, start);
+        assert_eq!(pet.hunger, 60 - 2 * meals.meal);
+        assert_eq!(pet.digested_at, None);
+        assert_eq!(pet.weight, BIRTH_WEIGHT + 2 * meals.weight_gain);
+    }
+
+    #[test]
+    fn test_care_shapes_the_personality() {
+        let mut config = Config::default();
+        config.personality.step_minutes = 1;
+        // A meal a minute, with no waiting for the last to go down
+        config.meals.digest_minutes = 0;
+        let raise = |pet: &mut Pet, action: Action, minutes: u32| {
+            for _ in 0..minutes {
+                pet.act(action, &config, Utc::now());
+                // Second 1 leaves out the periodic rules but still counts
+                for _ in 0..60 {
+                    pet.tick(&config, 1, pet.birth);
+                }
+            }
+        };
+
+        let mut playful = Pet::new("TestPet".to_string());
+        raise(&mut playful, Action::Play, 30);
+        assert!(playful.traits.energy > NOTICEABLE, "{:?}", playful.traits);
+        let mut fed = Pet::new("TestPet".to_string());
+        raise(&mut fed, Action::Feed, 30);
+        assert!(fed.traits.energy < -NOTICEABLE, "{:?}", fed.traits);
+
+        // However long it's kept up, a trait only leans so far
+        raise(&mut playful, Action::Play, 200);
+        assert_eq!(playful.traits.energy, config.personality.max_drift as i8);
+        let energy = playful.traits.energy;
+        raise(&mut playful, Action::Feed, 20);
+        assert!(playful.traits.energy < energy);
+
+        config.personality.enabled = false;
+        let mut unshaped = Pet::new("TestPet".to_string());
+        for _ in 0..600 {
+            unshaped.tick(&config, 1, unshaped.birth);
+        }
+        assert_eq!(unshaped.traits, Traits::default());
+    }
+
+    #[test]
+    fn test_same_play_over_and_over_bores_the_pet() {
+        let mut config = Config::default();
+        config.variety.enabled = true;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.favorites.activity = Action::Wash;
+        let start = Utc::now();
+        let play = |pet: &mut Pet, action: Action, minutes: i64| {
+            pet.mood = 0;
+            pet.act(action, &config, start + TimeDelta::minutes(minutes));
+            pet.mood
+        };
+
+        let gains: Vec<u8> = (0..5).map(|n| play(&mut pet, Action::Play, n)).collect();
+        assert_eq!(gains, [10, 8, 5, 3, 3]);
+        assert!(pet.variety(Action::Play, &config, start) < 1.0);
+        // Training in between makes playing fun again
+        assert_eq!(play(&mut pet, Action::Train, 5), 5);
+        assert_eq!(play(&mut pet, Action::Play, 6), 10);
+        assert_eq!(play(&mut pet, Action::Play, 7), 8);
+        // So does leaving it long enough to forget
+        let later = 7 + i64::from(config.variety.memory_minutes);
+        assert_eq!(play(&mut pet, Action::Play, later), 10);
+        assert!(pet.pastimes.len() <= MAX_PASTIMES);
+
+        config.variety.enabled = false;
+        assert_eq!(pet.variety(Action::Play, &config, start), 1.0);
+    }
+
+    #[test]
+    fn test_variety_benefit_has_a_floor() {
+        let rules = Variety::default();
+        let shares: Vec<f64> = (0..6).map(|n| variety_benefit(n, &rules)).collect();
+        assert_eq!(shares, [1.0, 0.75, 0.5, 0.25, 0.25, 0.25]);
+        let none = Variety {
+            repeat_penalty: 0.0,
+            ..Variety::default()
+        };
+        assert_eq!(variety_benefit(100, &none), 1.0);
+    }
+
+    #[test]
+    fn test_growth_spurts_come_while_young() {
+        let spurts = GrowthSpurts {
+            enabled: true,
+            ..GrowthSpurts::default()
+        };
+        let ages: Vec<u32> = (0..60).filter(|age| growth_spurt(*age, &spurts)).collect();
+        assert_eq!(ages, [4, 8, 12, 16, 20]);
+        assert!(!growth_spurt(4, &GrowthSpurts::default()));
+    }
+
+    #[test]
+    fn test_growth_spurt_speeds_up_hunger_and_fatigue() {
+        let mut config = no_grace();
+        config.growth_spurts.enabled = true;
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hunger, pet.fatigue) = (20, 0);
+        let mut spurting = pet.clone();
+        (spurting.age, pet.age) = (4, 5);
+        assert!(spurting.in_growth_spurt(&config));
+        assert!(!pet.in_growth_spurt(&config));
+
+        spurting.decay_needs(&config, settled(&spurting));
+        pet.decay_needs(&config, settled(&pet));
+        assert_eq!(spurting.hunger - pet.hunger, config.growth_spurts.hunger);
+        assert_eq!(spurting.fatigue - pet.fatigue, config.growth_spurts.fatigue);
+
+        // Once the year is out, needs run down as usual again
+        let (hunger, fatigue) = (pet.hunger, pet.fatigue);
+        spurting.age = 5;
+        (spurting.hunger, spurting.fatigue) = (pet.hunger, pet.fatigue);
+        spurting.decay_needs(&config, settled(&spurting));
+        pet.decay_needs(&config, settled(&pet));
+        assert_eq!(
+            (spurting.hunger, spurting.fatigue),
+            (pet.hunger, pet.fatigue)
+        );
+        assert!(pet.hunger > hunger && pet.fatigue > fatigue);
+    }
+
+    #[test]
+    fn test_lonely_pet_cheers_up_less() {
+        let mut config = Config::default();
+        config.rules.honeymoon_minutes = 0;
+        config.loneliness.enabled = true;
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hunger, pet.cleanliness, pet.mood) = (0, 100, 80);
+        let after = config.loneliness.after_minutes * 60;
+        let full = after + config.loneliness.full_minutes * 60;
+
+        // Second 1 leaves out the periodic rules but still counts the time
+        for _ in 0..after {
+            pet.tick(&config, 1, pet.birth);
+        }
+        assert_eq!(pet.idle_seconds, after);
+        assert_eq!(pet.loneliness(&config), 0.0);
+        pet.idle_seconds = full;
+        assert_eq!(pet.loneliness(&config), 1.0);
+
+        let cheered = |pet: &Pet| {
+            let mut pet = pet.clone();
+            pet.mood = 80;
+            pet.thrive(&config);
+            pet.mood - 80
+        };
+        assert_eq!(cheered(&pet), 0);
+        pet.interact();
+        assert_eq!(pet.loneliness(&config), 0.0);
+        assert_eq!(cheered(&pet), config.rules.thriving.mood);
+
+        // Turned off, a pet never gets lonely
+        config.loneliness.enabled = false;
+        pet.idle_seconds = full;
+        assert_eq!(pet.loneliness(&config), 0.0);
+        pet.tick(&config, 1, pet.birth);
+        assert_eq!(pet.idle_seconds, full);
+    }
+
     #[test]
     fn test_wash() {
         let mut pet = Pet::new("TestPet".to_string());
@@ -120,23 +1595,325 @@ mod tests {
     }
 
     #[test]
-    fn test_play() {
+    fn test_play() {
+        let mut pet = Pet::new("TestPet".to_string());
+        let initial_hunger = pet.hunger;
+        let initial_mood = pet.mood;
+        pet.play();
+        assert_eq!(pet.mood, initial_mood + 10);
+        assert_eq!(pet.hunger, initial_hunger + 5);
+    }
+
+    #[test]
+    fn test_caretaker_feeds_until_coins_run_out() {
+        let mut config = Config::default();
+        config.caretaker.enabled = true;
+        let cost = config.caretaker.cost;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.coins = cost * 2 - 1;
+
+        pet.hunger = config.caretaker.hunger - 1;
+        assert!(pet.caretake(&config).is_empty());
+        pet.hunger = config.caretaker.hunger;
+        assert_eq!(pet.caretake(&config), [TickEvent::AutoFed]);
+        assert_eq!(pet.hunger, config.caretaker.hunger - 20);
+        assert_eq!(pet.coins, cost - 1);
+
+        // Not enough left for another meal
+        pet.hunger = 100;
+        assert!(pet.caretake(&config).is_empty());
+        assert_eq!((pet.hunger, pet.coins), (100, cost - 1));
+    }
+
+    #[test]
+    fn test_caretaker_is_configured_per_need() {
+        let mut config = Config::default();
+        config.caretaker.enabled = true;
+        config.caretaker.feed = false;
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.coins, pet.hunger, pet.cleanliness) = (100, 100, 0);
+
+        assert_eq!(pet.caretake(&config), [TickEvent::AutoWashed]);
+        assert_eq!((pet.hunger, pet.cleanliness), (100, 100));
+
+        config.caretaker.enabled = false;
+        pet.cleanliness = 0;
+        assert!(pet.caretake(&config).is_empty());
+    }
+
+    #[test]
+    fn test_care_earns_coins() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.act(Action::Feed, &Config::default(), Utc::now());
+        pet.act(Action::Play, &Config::default(), Utc::now());
+        // Actions the pet can't take earn nothing
+        pet.act(Action::Wake, &Config::default(), Utc::now());
+        assert_eq!(pet.coins, 2 * COINS_PER_ACTION);
+    }
+
+    #[test]
+    fn test_wasted_care_is_detected_at_the_limit() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 1;
+        assert!(!pet.feed_would_waste());
+        pet.hunger = 0;
+        assert!(pet.feed_would_waste() && pet.would_waste(Action::Feed));
+        // A hungry companion still wants the meal
+        pet.companion = Some(Companion::new("Buddy".to_string()));
+        pet.companion.as_mut().unwrap().hunger = 1;
+        assert!(!pet.feed_would_waste());
+
+        pet.cleanliness = 99;
+        assert!(!pet.wash_would_waste());
+        pet.cleanliness = 100;
+        assert!(pet.wash_would_waste() && pet.would_waste(Action::Wash));
+        pet.messes = 1;
+        assert!(!pet.wash_would_waste());
+        assert!(!pet.would_waste(Action::Play));
+    }
+
+    #[test]
+    fn test_steady_care_builds_the_bond() {
+        // Meals back to back, with no waiting for the last to go down
+        let config = Config {
+            meals: Meals {
+                digest_minutes: 0,
+                ..Meals::default()
+            },
+            ..Config::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        for _ in 0..3 {
+            pet.act(Action::Feed, &config, Utc::now());
+            pet.act(Action::Wash, &config, Utc::now());
+        }
+        pet.act(Action::Sleep, &config, Utc::now());
+        pet.act(Action::Wake, &config, Utc::now());
+        assert_eq!(pet.bond, 6 * bond::BOND_PER_CARE);
+
+        // A long stretch of high stats adds a point on top
+        (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (100, 0, 100, 100);
+        pet.high_stats_seconds = BOND_STREAK_SECONDS - 1;
+        pet.tick(&config, 1, settled(&pet));
+        assert_eq!(pet.bond, 6 * bond::BOND_PER_CARE + 1);
+
+        pet.bond = MAX_BOND;
+        pet.act(Action::Play, &config, Utc::now());
+        assert_eq!(pet.bond, MAX_BOND);
+    }
+
+    #[test]
+    fn test_spa_day_combines_care_for_coins() {
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (60, 40, 10, 30);
+        pet.messes = 2;
+        assert!(!pet.can_act(Action::Spa));
+        pet.act(Action::Spa, &Config::default(), Utc::now());
+        assert_eq!((pet.cleanliness, pet.mood), (10, 30));
+
+        pet.coins = SPA_COST + 3;
+        pet.act(Action::Spa, &Config::default(), Utc::now());
+        assert_eq!(pet.coins, 3);
+        assert_eq!((pet.cleanliness, pet.messes), (100, 0));
+        assert_eq!(pet.mood, 30 + SPA_MOOD);
+        assert_eq!(pet.health, 60 + SPA_HEALTH);
+        // Unlike playing, it doesn't make the pet hungry
+        assert_eq!(pet.hunger, 40);
+        assert_eq!(pet.log.last().unwrap().event, Event::Pampered);
+    }
+
+    #[test]
+    fn test_favorites_give_a_bonus() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.favorites = Favorites {
+            food: Food::Fish,
+            activity: Action::Train,
+        };
+        let mut picky = pet.clone();
+        picky.favorites.food = Food::Carrots;
+        (pet.hunger, pet.mood, picky.hunger, picky.mood) = (50, 50, 50, 50);
+
+        // Fish is on the menu on the first day
+        pet.act(Action::Feed, &Config::default(), pet.birth);
+        picky.act(Action::Feed, &Config::default(), picky.birth);
+        assert_eq!(pet.hunger, 50 - 20 - FAVORITE_MEAL);
+        assert_eq!(picky.hunger, 50 - 20);
+        // The next day it's carrots
+        let tomorrow = picky.birth + TimeDelta::days(1);
+        picky.act(Action::Feed, &Config::default(), tomorrow);
+        assert_eq!(picky.hunger, 50 - 40 - FAVORITE_MEAL);
+
+        let (before, now) = (pet.mood, Utc::now());
+        pet.act(Action::Train, &Config::default(), now);
+        let trained = pet.mood;
+        assert_eq!(trained, before + 5 + FAVORITE_MOOD);
+        pet.act(Action::Play, &Config::default(), now);
+        assert_eq!(pet.mood, trained + 10);
+    }
+
+    #[test]
+    fn test_habits_shift_hunger_and_mood() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let mut regular = pet.clone();
+        let start = settled(&pet);
+        for hour in 0..5 {
+            regular.act(Action::Feed, &config, start + TimeDelta::hours(hour));
+        }
+        let soon = start + TimeDelta::hours(4) + TimeDelta::minutes(10);
+        (pet.hunger, regular.hunger) = (0, 0);
+        pet.decay_needs(&config, soon);
+        regular.decay_needs(&config, soon);
+        // The next meal isn't due for another 50 minutes
+        assert!(regular.hunger < pet.hunger);
+
+        // Judged by the step's own time, so catch-up gets it right too
+        let late = start + TimeDelta::hours(5) + TimeDelta::minutes(30);
+        let mut fretting = regular.clone();
+        fretting.hunger = 0;
+        fretting.decay_needs(&config, late);
+        assert!(fretting.hunger > pet.hunger);
+
+        let mut off = config.clone();
+        off.rules.habits.enabled = false;
+        regular.hunger = 0;
+        regular.decay_needs(&off, soon);
+        assert_eq!(regular.hunger, pet.hunger);
+
+        for _ in 0..20 {
+            regular.act(Action::Play, &config, Utc::now());
+        }
+        (pet.mood, regular.mood) = (50, 50);
+        pet.bore(&config, 255);
+        regular.bore(&config, 255);
+        assert_eq!(pet.mood, 0);
+        assert!(regular.mood > 20);
+        regular.bore(&off, 255);
+        assert_eq!(regular.mood, 0);
+    }
+
+    #[test]
+    fn test_sleep_and_wake() {
+        let mut pet = Pet::new("TestPet".to_string());
+        assert!(!pet.is_sleeping);
+        assert!(pet.can_act(Action::Sleep) && !pet.can_act(Action::Wake));
+        pet.sleep();
+        assert!(pet.is_sleeping);
+        // Going to sleep again doesn't wake the pet up
+        pet.sleep();
+        assert!(pet.is_sleeping);
+        assert!(pet.can_act(Action::Wake) && !pet.can_act(Action::Feed));
+        pet.wake();
+        assert!(!pet.is_sleeping);
+        pet.wake();
+        assert!(!pet.is_sleeping);
+    }
+
+    #[test]
+    fn test_sleeping_pet_ignores_care() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 50;
+        pet.act(Action::Sleep, &Config::default(), Utc::now());
+        pet.act(Action::Feed, &Config::default(), Utc::now());
+        pet.act(Action::Sleep, &Config::default(), Utc::now());
+        assert_eq!(pet.hunger, 50);
+        assert!(pet.is_sleeping);
+        pet.act(Action::Wake, &Config::default(), Utc::now());
+        assert!(!pet.is_sleeping);
+
+        let events: Vec<Event> = pet.log.iter().map(|entry| entry.event).collect();
+        assert_eq!(events, [Event::Adopted, Event::FellAsleep, Event::WokeUp]);
+    }
+
+    #[test]
+    fn test_blocked_actions_charge_nothing() {
+        let mut asleep = Pet::new("TestPet".to_string());
+        (asleep.coins, asleep.is_sleeping) = (SPA_COST, true);
+        let mut debugging = Pet::new("TestPet".to_string());
+        (debugging.coins, debugging.debug_mode) = (SPA_COST, true);
+        let mut dead = Pet::new("TestPet".to_string());
+        (dead.coins, dead.health) = (SPA_COST, 0);
+        let mut gone = Pet::new("TestPet".to_string());
+        (gone.coins, gone.status) = (SPA_COST, PetStatus::Abandoned);
+
+        for pet in [asleep, debugging, dead, gone] {
+            for action in [Action::Feed, Action::Play, Action::Train, Action::Spa] {
+                assert!(!pet.can_act(action));
+                let mut acted = pet.clone();
+                acted.act(action, &Config::default(), Utc::now());
+                assert_eq!(acted, pet, "{action:?} on {pet:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sustained_misery_sends_the_pet_into_hiding() {
+        let mut config = Config::default();
+        (
+            config.sulking.hide_after_seconds,
+            config.sulking.coax_seconds,
+        ) = (5, 4);
+        // Still in the honeymoon, so the mood only changes when set
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.mood = 10;
+        let mut unbothered = pet.clone();
+        for second in 1..=5 {
+            unbothered.tick(&Config::default(), second, unbothered.birth);
+        }
+        assert!(!unbothered.hiding);
+
+        config.sulking.enabled = true;
+        for second in 1..5 {
+            assert!(pet.tick(&config, second, pet.birth).events.is_empty());
+        }
+        assert!(
+            pet.tick(&config, 5, pet.birth)
+                .has(TickEvent::WentIntoHiding)
+        );
+        assert!(pet.hiding);
+        assert!(!pet.can_act(Action::Wash) && !pet.can_act(Action::Train));
+        assert!(pet.can_act(Action::Play) && pet.can_act(Action::Feed));
+        for second in 6..=20 {
+            pet.tick(&config, second, pet.birth);
+        }
+        assert!(pet.hiding, "misery alone never ends the hiding");
+    }
+
+    #[test]
+    fn test_sustained_good_care_coaxes_the_pet_out() {
+        let mut config = Config::default();
+        config.sulking.enabled = true;
+        config.sulking.coax_seconds = 4;
         let mut pet = Pet::new("TestPet".to_string());
-        let initial_hunger = pet.hunger;
-        let initial_mood = pet.mood;
-        pet.play();
-        assert_eq!(pet.mood, initial_mood + 10);
-        assert_eq!(pet.hunger, initial_hunger + 5);
+        (pet.hiding, pet.mood) = (true, config.sulking.coax_mood);
+        for second in 1..4 {
+            pet.tick(&config, second, pet.birth);
+        }
+        // A bad moment starts the coaxing over
+        pet.mood = 20;
+        pet.tick(&config, 4, pet.birth);
+        pet.mood = config.sulking.coax_mood;
+        for second in 5..8 {
+            assert!(!pet.tick(&config, second, pet.birth).has(TickEvent::CameOut));
+        }
+        assert!(pet.tick(&config, 8, pet.birth).has(TickEvent::CameOut));
+        assert!(!pet.hiding);
+        assert!(pet.can_act(Action::Wash));
     }
 
     #[test]
-    fn test_sleep() {
-        let mut pet = Pet::new("TestPet".to_string());
-        assert!(!pet.is_sleeping);
-        pet.sleep();
-        assert!(pet.is_sleeping);
-        pet.sleep();
-        assert!(!pet.is_sleeping);
+    fn test_elderly_pets_heal_slower_in_sleep() {
+        let config = Config::default();
+        let mut young = Pet::new("TestPet".to_string());
+        (young.health, young.is_sleeping) = (50, true);
+        let mut elder = young.clone();
+        elder.age = 60;
+        for second in 1..=10 {
+            young.tick(&config, second, young.birth);
+            elder.tick(&config, second, elder.birth);
+        }
+        assert_eq!(young.health, 60);
+        assert_eq!(elder.health, 55);
     }
 
     #[test]
@@ -267,6 +2044,670 @@ mod tests {
         assert_eq!(pet.health, initial_health + 3);
         assert_eq!(pet.mood, initial_mood + 5);
         assert_eq!(pet.hunger, initial_hunger + 10);
+        assert_eq!(pet.skills.agility, crate::skills::TRAIN_XP);
+    }
+
+    #[test]
+    fn test_care_score_weights() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 0;
+        pet.cleanliness = 100;
+        pet.mood = 20;
+
+        let equal = NeedWeights::default();
+        let mood_heavy = NeedWeights {
+            mood: 4.0,
+            ..NeedWeights::default()
+        };
+        let cleanliness_heavy = NeedWeights {
+            cleanliness: 4.0,
+            ..NeedWeights::default()
+        };
+
+        assert_eq!(pet.care_score(&equal), 73);
+        // A poor mood hurts more when mood matters most
+        assert!(pet.care_score(&mood_heavy) < pet.care_score(&equal));
+        // ...and less when cleanliness (which is perfect) dominates
+        assert!(pet.care_score(&cleanliness_heavy) > pet.care_score(&equal));
+    }
+
+    /// A moment past the honeymoon of `pet`, so its needs decay from the
+    /// first tick
+    fn settled(pet: &Pet) -> DateTime<Utc> {
+        pet.birth + TimeDelta::hours(1)
+    }
+
+    /// Hunger hurts straight away, like before the starving grace existed
+    fn no_grace() -> Config {
+        let mut config = Config::default();
+        config.rules.starving_grace = 0;
+        config
+    }
+
+    fn config_with_weights(weights: NeedWeights) -> Config {
+        Config {
+            weights,
+            ..no_grace()
+        }
+    }
+
+    #[test]
+    fn test_decline_health_weights() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+        pet.decline_health(&no_grace());
+        assert_eq!(pet.health, 97);
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+        pet.decline_health(&config_with_weights(NeedWeights {
+            hunger: 0.0,
+            ..NeedWeights::default()
+        }));
+        assert_eq!(pet.health, 100);
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+        pet.decline_health(&config_with_weights(NeedWeights {
+            hunger: 2.0,
+            ..NeedWeights::default()
+        }));
+        assert_eq!(pet.health, 95);
+    }
+
+    #[test]
+    fn test_single_neglected_need_is_not_compounded() {
+        let mut config = Config::default();
+        config.rules.compound_neglect = 3.0;
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.cleanliness = 5;
+        // A mild second need doesn't count as danger
+        pet.mood = 25;
+        pet.decline_health(&config);
+        assert_eq!(pet.health, 96);
+    }
+
+    #[test]
+    fn test_multiple_neglected_needs_compound() {
+        let config = no_grace();
+        let neglected = |hunger: u8, cleanliness: u8, mood: u8| {
+            let mut pet = Pet::new("TestPet".to_string());
+            pet.hunger = hunger;
+            pet.cleanliness = cleanliness;
+            pet.mood = mood;
+            pet.decline_health(&config);
+            100 - pet.health
+        };
+
+        let hungry = neglected(95, 100, 100);
+        let dirty = neglected(0, 5, 100);
+        let sad = neglected(0, 100, 5);
+        let all = neglected(95, 5, 5);
+
+        assert_eq!((hungry, dirty, sad), (3, 3, 3));
+        assert!(all > hungry + dirty + sad);
+        assert_eq!(all, 14); // 9 * 1.5, rounded
+
+        let mut linear = no_grace();
+        linear.rules.compound_neglect = 1.0;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+        pet.cleanliness = 5;
+        pet.mood = 5;
+        pet.decline_health(&linear);
+        assert_eq!(100 - pet.health, 9);
+    }
+
+    #[test]
+    fn test_tick_reports_deltas() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 50;
+        pet.cleanliness = 50;
+        pet.mood = 50;
+
+        let report = pet.tick(&config, 3, settled(&pet));
+        assert_eq!(report.hunger, 2);
+        assert_eq!(report.cleanliness, -3);
+        assert_eq!(report.mood, -2);
+        assert_eq!(report.health, 0);
+        assert!(report.events.is_empty());
+
+        // Needs only change every third second
+        let report = pet.tick(&config, 4, settled(&pet));
+        assert_eq!(report.hunger, 0);
+        assert_eq!(report.mood, -2);
+    }
+
+    #[test]
+    fn test_tick_reports_aging() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        assert!(pet.tick(&config, 300, pet.birth).has(TickEvent::Aged));
+        assert_eq!(pet.age, 1);
+        assert!(!pet.tick(&config, 301, pet.birth).has(TickEvent::Aged));
+    }
+
+    #[test]
+    fn test_tick_reports_adult_milestone_once() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.age = 20;
+
+        let report = pet.tick(&config, 300, pet.birth);
+        assert_eq!(
+            report.events,
+            vec![TickEvent::Aged, TickEvent::GrewUp(LifeStage::Adult)]
+        );
+
+        for second in [600, 900, 1200] {
+            let report = pet.tick(&config, second, pet.birth);
+            assert!(
+                !report
+                    .events
+                    .iter()
+                    .any(|e| matches!(e, TickEvent::GrewUp(_)))
+            );
+        }
+        assert_eq!(pet.age, 24);
+    }
+
+    #[test]
+    fn test_tick_reports_round_age_milestone() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.age = 9;
+
+        let report = pet.tick(&config, 300, pet.birth);
+        assert!(report.has(TickEvent::Milestone(10)));
+        assert!(
+            !pet.tick(&config, 600, pet.birth)
+                .has(TickEvent::Milestone(11))
+        );
+    }
+
+    #[test]
+    fn test_only_a_cared_for_pet_ages_in_care_mode() {
+        let mut config = Config::default();
+        config.aging.mode = AgingMode::Care;
+        // New pets are in their honeymoon, so the stats hold still
+        let mut cared = Pet::new("TestPet".to_string());
+        let mut neglected = Pet::new("TestPet".to_string());
+        (neglected.hunger, neglected.cleanliness, neglected.mood) = (100, 0, 0);
+        let mut timed = neglected.clone();
+
+        let mut reports = Vec::new();
+        for second in 1..=2 * AGING_SECONDS {
+            cared.tick(&config, second, cared.birth);
+            reports.push(neglected.tick(&config, second, neglected.birth));
+            timed.tick(&Config::default(), second, timed.birth);
+        }
+        assert_eq!((cared.age, neglected.age, timed.age), (2, 0, 2));
+        assert!(!reports.iter().any(|report| report.has(TickEvent::Aged)));
+
+        // The period starts over, so catching up on care pays off right away
+        (neglected.hunger, neglected.cleanliness, neglected.mood) = (0, 100, 100);
+        for second in 2 * AGING_SECONDS + 1..=3 * AGING_SECONDS {
+            neglected.tick(&config, second, neglected.birth);
+        }
+        assert_eq!(neglected.age, 1);
+    }
+
+    #[test]
+    fn test_old_pet_retires_when_enabled() {
+        let mut config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.age = config.retirement.age - 1;
+        pet.tick(&config, 300, pet.birth);
+        assert_eq!(pet.status, PetStatus::Alive);
+
+        config.retirement.enabled = true;
+        let report = pet.tick(&config, 600, pet.birth);
+        assert!(report.has(TickEvent::Retired));
+        assert_eq!(pet.status, PetStatus::Retired);
+        assert!(!pet.retire_if_due(&config));
+
+        // A pet that has already gone doesn't retire
+        let mut dead = Pet::new("TestPet".to_string());
+        (dead.age, dead.health) = (config.retirement.age, 0);
+        assert!(!dead.retire_if_due(&config));
+    }
+
+    #[test]
+    fn test_tick_reports_sickness_and_critical() {
+        let config = no_grace();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 21;
+        pet.hunger = 95;
+
+        let report = pet.tick(&config, 3, settled(&pet));
+        assert_eq!(pet.health, 18);
+        assert_eq!(report.health, -3);
+        assert_eq!(report.events, vec![TickEvent::GotSick]);
+
+        pet.health = 11;
+        let report = pet.tick(&config, 6, settled(&pet));
+        assert_eq!(report.events, vec![TickEvent::ReachedCritical]);
+
+        // Already critical, so it isn't reported again
+        let report = pet.tick(&config, 9, settled(&pet));
+        assert!(!report.has(TickEvent::ReachedCritical));
+    }
+
+    #[test]
+    fn test_tick_reports_death() {
+        let config = no_grace();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.status = PetStatus::Sick;
+        pet.health = 2;
+        pet.hunger = 95;
+
+        let report = pet.tick(&config, 3, settled(&pet));
+        assert_eq!(pet.health, 0);
+        assert_eq!(report.events, vec![TickEvent::Died]);
+        assert!(pet.tick(&config, 6, settled(&pet)).events.is_empty());
+    }
+
+    #[test]
+    fn test_tick_reports_healed() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.status = PetStatus::Sick;
+        pet.is_sleeping = true;
+        pet.health = RECOVERED_HEALTH - 1;
+
+        let report = pet.tick(&config, 1, pet.birth);
+        assert_eq!(report.health, 1);
+        assert_eq!(report.events, vec![TickEvent::Healed]);
+        assert_eq!(pet.status, PetStatus::Alive);
+    }
+
+    #[test]
+    fn test_act_logs_action() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 85;
+        // Not what's served on its first day
+        pet.favorites.food = Food::Cookies;
+        let now = Utc::now();
+
+        pet.act(Action::Feed, &Config::default(), now);
+        pet.act(Action::Sleep, &Config::default(), now);
+        pet.act(Action::Wake, &Config::default(), now);
+
+        let events: Vec<Event> = pet.log.iter().map(|entry| entry.event).collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::Adopted,
+                Event::Fed { hunger: 85 },
+                Event::FellAsleep,
+                Event::WokeUp
+            ]
+        );
+        assert_eq!(pet.hunger, 65);
+    }
+
+    #[test]
+    fn test_act_applies_elderly_modifiers() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.age = 60;
+        pet.health = 50;
+        pet.act(Action::Feed, &Config::default(), Utc::now());
+        assert_eq!(pet.health, 53);
+    }
+
+    #[test]
+    fn test_messes_accumulate_over_ticks() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+
+        for second in 1..MESS_INTERVAL {
+            pet.tick(&config, second, settled(&pet));
+        }
+        assert_eq!(pet.messes, 0);
+
+        let report = pet.tick(&config, MESS_INTERVAL, settled(&pet));
+        assert!(report.has(TickEvent::MadeMess));
+        assert_eq!(pet.messes, 1);
+        // Three for the usual decay plus one for the mess
+        assert_eq!(report.cleanliness, -4);
+    }
+
+    #[test]
+    fn test_messes_are_capped() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        for n in 1..=(MAX_MESSES as u64 + 3) {
+            pet.tick(&config, n * MESS_INTERVAL, settled(&pet));
+        }
+        assert_eq!(pet.messes, MAX_MESSES);
+        assert!(!pet.make_mess());
+    }
+
+    #[test]
+    fn test_wash_clears_messes() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.messes = 3;
+        pet.cleanliness = 20;
+        pet.wash();
+        assert_eq!(pet.messes, 0);
+        assert_eq!(pet.cleanliness, 100);
+    }
+
+    #[test]
+    fn test_most_urgent_need() {
+        let thresholds = Thresholds::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hunger, pet.cleanliness, pet.mood) = (0, 100, 100);
+        assert_eq!(pet.most_urgent_need(&thresholds), None);
+
+        // Just outside the margin is still fine
+        pet.hunger = 71 - URGENT_MARGIN as u8 - 1;
+        assert_eq!(pet.most_urgent_need(&thresholds), None);
+        pet.hunger = 65;
+        assert_eq!(pet.most_urgent_need(&thresholds), Some(Need::Hunger));
+
+        // Cleanliness already hurting beats hunger that is only close
+        pet.cleanliness = 20;
+        assert_eq!(pet.most_urgent_need(&thresholds), Some(Need::Cleanliness));
+
+        // Mood furthest into the danger zone wins
+        pet.mood = 5;
+        assert_eq!(pet.most_urgent_need(&thresholds), Some(Need::Mood));
+
+        // Equal margins go to hunger first
+        (pet.hunger, pet.cleanliness, pet.mood) = (66, 34, 34);
+        assert_eq!(pet.most_urgent_need(&thresholds), Some(Need::Hunger));
+        pet.hunger = 0;
+        assert_eq!(pet.most_urgent_need(&thresholds), Some(Need::Cleanliness));
+        assert_eq!(Need::Cleanliness.action(), Action::Wash);
+    }
+
+    #[test]
+    fn test_custom_thresholds_move_the_danger_zone() {
+        let mut config = no_grace();
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hunger, pet.cleanliness, pet.mood) = (45, 100, 100);
+        let mut strict = pet.clone();
+
+        pet.decline_health(&config);
+        assert_eq!(pet.health, 100);
+        assert_eq!(pet.most_urgent_need(&config.thresholds), None);
+
+        config.thresholds.hunger.warn = 30;
+        config.thresholds.hunger.danger = 50;
+        strict.decline_health(&config);
+        assert!(strict.health < 100);
+        assert_eq!(
+            strict.most_urgent_need(&config.thresholds),
+            Some(Need::Hunger)
+        );
+
+        // Sickness follows the health threshold
+        config.thresholds.health.danger = 40;
+        (strict.hunger, strict.health) = (0, 35);
+        strict.tick(&config, 1, settled(&strict));
+        assert_eq!(strict.status, PetStatus::Sick);
+    }
+
+    #[test]
+    fn test_companion_cheers_up_healthy_pet() {
+        let config = Config::default();
+        let mut alone = Pet::new("TestPet".to_string());
+        alone.mood = 50;
+        let mut together = alone.clone();
+        together.companion = Some(Companion::new("Buddy".to_string()));
+
+        alone.decay_needs(&config, settled(&alone));
+        together.decay_needs(&config, settled(&together));
+        assert_eq!(together.mood, alone.mood + COMPANION_MOOD);
+        assert_eq!(together.companion.as_ref().unwrap().hunger, 3);
+    }
+
+    #[test]
+    fn test_neglected_companion_drags_mood_down() {
+        let config = Config::default();
+        let mut alone = Pet::new("TestPet".to_string());
+        alone.mood = 50;
+        let mut together = alone.clone();
+        let mut companion = Companion::new("Buddy".to_string());
+        companion.hunger = 90;
+        together.companion = Some(companion);
+
+        alone.decay_needs(&config, settled(&alone));
+        together.decay_needs(&config, settled(&together));
+        assert_eq!(together.mood, alone.mood - COMPANION_MOOD);
+
+        // Feeding shares the bowl
+        together.hunger = 50;
+        together.feed(&Config::default());
+        assert_eq!(together.hunger, 35);
+        assert_eq!(together.companion.as_ref().unwrap().hunger, 78);
+    }
+
+    #[test]
+    fn test_reloading_does_not_reroll() {
+        let roll = |pet: &mut Pet| {
+            let mut rng = PetRng::resume(pet.rng_seed, pet.rng_draws);
+            let rolls: Vec<bool> = (0..20).map(|_| rng.one_in(3)).collect();
+            pet.rng_draws = rng.draws;
+            rolls
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.rng_seed = 7;
+        let rolls = roll(&mut pet);
+        assert!(rolls.contains(&true) && rolls.contains(&false));
+
+        // A restart resumes from the save instead of drawing the same numbers again
+        let saved = serde_json::to_string(&pet).unwrap();
+        let mut reloaded: Pet = serde_json::from_str(&saved).unwrap();
+        assert_eq!(reloaded.rng_draws, 20);
+        assert_eq!(roll(&mut reloaded), roll(&mut pet));
+    }
+
+    #[test]
+    fn test_abandonment_remaining() {
+        let now = Utc::now();
+        assert_eq!(abandonment_remaining(now, now), ABANDON_AFTER);
+        assert_eq!(
+            abandonment_remaining(now - TimeDelta::days(1), now),
+            TimeDelta::days(2)
+        );
+        assert_eq!(
+            abandonment_remaining(now - TimeDelta::days(4), now),
+            TimeDelta::zero()
+        );
+        // A clock that went backwards doesn't grant extra time
+        assert_eq!(
+            abandonment_remaining(now + TimeDelta::hours(1), now),
+            ABANDON_AFTER
+        );
+    }
+
+    #[test]
+    fn test_needs_hold_during_honeymoon() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let birth = pet.birth;
+        let at = |second: u64| birth + TimeDelta::seconds(second as i64);
+        for second in 1..=30 {
+            pet.tick(&config, second, at(second));
+        }
+        assert_eq!((pet.hunger, pet.cleanliness, pet.mood), (0, 100, 100));
+
+        // Needs start decaying once the honeymoon is over
+        let over = config.rules.honeymoon_minutes as u64 * 60;
+        assert!(pet.in_honeymoon(&config, at(over - 1)));
+        assert!(!pet.in_honeymoon(&config, at(over)));
+        for second in over + 1..=over + 30 {
+            pet.tick(&config, second, at(second));
+        }
+        assert_eq!(pet.hunger, 20);
+        assert!(pet.cleanliness < 100);
+    }
+
+    #[test]
+    fn test_pet_comes_home_sad() {
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.status = PetStatus::Abandoned;
+        pet.last_seen -= ABANDON_AFTER * 2;
+        let now = Utc::now();
+        pet.come_home(now);
+
+        assert_eq!(pet.status, PetStatus::Alive);
+        assert_eq!(pet.mood, HOMECOMING_MOOD);
+        assert_eq!(abandonment_remaining(pet.last_seen, now), ABANDON_AFTER);
+    }
+
+    #[test]
+    fn test_fatigue_builds_while_awake() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        for second in 1..=30 {
+            pet.tick(&config, second, settled(&pet));
+        }
+        assert_eq!(pet.fatigue, 10 * config.rules.fatigue.rise);
+    }
+
+    #[test]
+    fn test_sleep_relieves_fatigue() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.fatigue = 50;
+        pet.is_sleeping = true;
+        for second in 1..=10 {
+            pet.tick(&config, second, pet.birth);
+        }
+        assert_eq!(pet.fatigue, 50 - 10 * config.rules.fatigue.relief);
+
+        pet.rest(&config, 1000);
+        assert_eq!(pet.fatigue, 0);
+    }
+
+    #[test]
+    fn test_sleep_applies_the_sleep_rules() {
+        let mut config = no_grace();
+        config.rules.sleep = crate::config::SleepRules {
+            heal: 2,
+            hunger: 1,
+            cleanliness: 0,
+            mood: 3,
+            offline_cap: 0,
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (50, 10, 60, 40);
+        pet.is_sleeping = true;
+        for second in 1..=6 {
+            pet.tick(&config, second, settled(&pet));
+        }
+        assert_eq!(
+            (pet.health, pet.hunger, pet.cleanliness, pet.mood),
+            (62, 12, 60, 46)
+        );
+    }
+
+    #[test]
+    fn test_exhausted_pet_dozes_off() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.fatigue = config.rules.fatigue.collapse - 2;
+
+        let report = pet.tick(&config, 3, settled(&pet));
+        assert!(!report.has(TickEvent::DozedOff));
+        assert!(!pet.is_sleeping);
+
+        let report = pet.tick(&config, 6, settled(&pet));
+        assert!(report.has(TickEvent::DozedOff));
+        assert!(pet.is_sleeping);
+    }
+
+    #[test]
+    fn test_tired_pet_does_not_heal() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.fatigue) = (80, config.rules.fatigue.tired);
+        pet.thrive(&config);
+        assert_eq!(pet.health, 80);
+    }
+
+    #[test]
+    fn test_healthy_pet_thrives() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 80;
+        pet.hunger = 0;
+        pet.cleanliness = 100;
+        pet.mood = 90;
+
+        for _ in 0..5 {
+            pet.decay_needs(&config, settled(&pet));
+        }
+        assert_eq!(pet.health, 85);
+        assert_eq!(pet.mood, 95);
+
+        // Never past full health
+        pet.health = 100;
+        pet.decay_needs(&config, settled(&pet));
+        assert_eq!(pet.health, 100);
+    }
+
+    #[test]
+    fn test_neglected_pet_does_not_thrive() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 80;
+        pet.hunger = 0;
+        pet.cleanliness = 60;
+        pet.mood = 90;
+
+        for _ in 0..5 {
+            pet.decay_needs(&config, pet.birth);
+        }
+        assert_eq!(pet.health, 80);
+        assert_eq!(pet.mood, 90);
+    }
+
+    #[test]
+    fn test_starving_grace_preserves_health() {
+        let mut config = Config::default();
+        config.rules.starving_grace = 3;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+
+        for _ in 0..3 {
+            pet.decline_health(&config);
+        }
+        assert_eq!(pet.health, 100);
+
+        pet.decline_health(&config);
+        assert_eq!(pet.health, 97);
+        assert_eq!(pet.starving_ticks, 4);
+    }
+
+    #[test]
+    fn test_feeding_resets_starving_grace() {
+        let mut config = Config::default();
+        config.rules.starving_grace = 3;
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 95;
+
+        for _ in 0..3 {
+            pet.decline_health(&config);
+        }
+        pet.feed(&Config::default());
+        assert_eq!(pet.starving_ticks, 0);
+
+        // Still hungry after one meal, but the grace window starts over
+        let fed_health = pet.health;
+        pet.decline_health(&config);
+        assert_eq!(pet.health, fed_health);
+
+        // Dropping out of the danger zone resets the count too
+        pet.starving_ticks = 3;
+        pet.hunger = 50;
+        pet.decline_health(&config);
+        assert_eq!(pet.starving_ticks, 0);
     }
 
     #[test]
@@ -0,0 +1,76 @@
+use crate::pet::Pet;
+use std::f64::consts::TAU;
+
+/// Spoke labels, clockwise from the top. Every spoke points outwards for
+/// "better", so hunger is drawn as fullness and fatigue as energy.
+pub const SPOKES: [&str; 5] = ["Health", "Fullness", "Cleanliness", "Mood", "Energy"];
+
+/// Radius of a spoke at 100
+pub const RADIUS: f64 = 100.0;
+
+/// The pet's value on each of `SPOKES`, 0 to 100
+pub fn spoke_values(pet: &Pet) -> [u8; 5] {
+    [
+        pet.health.min(100),
+        100 - pet.hunger.min(100),
+        pet.cleanliness.min(100),
+        pet.mood.min(100),
+        100 - pet.fatigue.min(100),
+    ]
+}
+
+/// Where the end of a spoke at `value` lands, with the first spoke
+/// pointing straight up and the rest spread evenly clockwise
+pub fn spoke_point(index: usize, count: usize, value: u8) -> (f64, f64) {
+    let angle = TAU * index as f64 / count as f64;
+    let length = RADIUS * f64::from(value.min(100)) / 100.0;
+    (length * angle.sin(), length * angle.cos())
+}
+
+/// Corners of the radar polygon for `values`, in spoke order
+pub fn radar_points(values: &[u8]) -> Vec<(f64, f64)> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| spoke_point(i, values.len(), *value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_spoke_endpoints() {
+        let points = radar_points(&[100, 50, 0, 100]);
+        assert_near(points[0], (0.0, 100.0));
+        assert_near(points[1], (50.0, 0.0));
+        assert_near(points[2], (0.0, 0.0));
+        assert_near(points[3], (-100.0, 0.0));
+
+        // Five spokes are 72 degrees apart
+        let (x, y) = spoke_point(1, 5, 100);
+        assert_near(
+            (x, y),
+            (
+                72f64.to_radians().sin() * 100.0,
+                72f64.to_radians().cos() * 100.0,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_spoke_values_point_outwards_for_better() {
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.health, pet.hunger, pet.cleanliness) = (90, 20, 70);
+        (pet.mood, pet.fatigue) = (60, 30);
+        assert_eq!(spoke_values(&pet), [90, 80, 70, 60, 70]);
+    }
+}
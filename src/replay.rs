@@ -0,0 +1,226 @@
+use crate::config::{Meals, Medicine};
+use crate::diary::{day_label, sentence};
+use crate::history::Event;
+use crate::i18n::Lang;
+use crate::pet::{
+    CRITICAL_HEALTH, LifeStage, MAX_MESSES, Pet, PetStatus, RECOVERED_HEALTH, SPA_MOOD, TickEvent,
+};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Seconds of the pet's life each second of `petty replay` gets through
+pub const DEFAULT_SPEED: u64 = 3600;
+/// No moment flashes by quicker than this, however close the next one is
+pub const MIN_PAUSE: Duration = Duration::from_millis(300);
+/// Nor stays up longer, so days away don't stall the replay
+pub const MAX_PAUSE: Duration = Duration::from_secs(3);
+
+/// The pet as it was right after one entry of its log
+#[derive(Clone, Debug, PartialEq)]
+pub struct Moment {
+    pub at: DateTime<Utc>,
+    pub event: Event,
+    pub pet: Pet,
+}
+
+/// The pet's life rebuilt from its log, one moment per entry. The log only
+/// keeps the stat each care action was about, so the others carry on from
+/// the last thing known about them.
+///
+/// A log whose oldest entries were dropped starts from the saved pet's
+/// age less the birthdays still in the log, with every stat at its best.
+pub fn timeline(pet: &Pet) -> Vec<Moment> {
+    let mut shown = Pet {
+        log: Vec::new(),
+        gallery: Vec::new(),
+        birth: pet.birth,
+        favorites: pet.favorites,
+        ..Pet::new(pet.name.clone())
+    };
+    let birthdays = pet
+        .log
+        .iter()
+        .filter(|entry| entry.event == Event::Tick(TickEvent::Aged))
+        .count() as u32;
+    shown.age = pet.age.saturating_sub(birthdays);
+
+    pet.log
+        .iter()
+        .map(|entry| {
+            relive(&mut shown, entry.event);
+            Moment {
+                at: entry.at,
+                event: entry.event,
+                pet: shown.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Apply what the log says `event` did to `pet`
+fn relive(pet: &mut Pet, event: Event) {
+    match event {
+        Event::Adopted => {
+            pet.age = 0;
+            (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (100, 0, 100, 100);
+            (pet.status, pet.is_sleeping, pet.messes) = (PetStatus::Alive, false, 0);
+        }
+        Event::Fed { hunger } | Event::GraceFed { hunger } => {
+            pet.hunger = hunger.saturating_sub(20);
+        }
+        // The log doesn't say how big a snack was either, so take the default
+        Event::Snacked { hunger } => {
+            pet.hunger = hunger.saturating_sub(Meals::default().snack);
+        }
+        Event::Washed { .. } => wash(pet),
+        Event::Played { mood } => pet.mood = mood.saturating_add(10).min(100),
+        Event::Trained => pet.mood = pet.mood.saturating_add(5).min(100),
+        Event::FellAsleep | Event::Tick(TickEvent::DozedOff) => pet.is_sleeping = true,
+        Event::WokeUp => pet.is_sleeping = false,
+        Event::Pampered => {
+            wash(pet);
+            pet.mood = pet.mood.saturating_add(SPA_MOOD).min(100);
+        }
+        // The log doesn't say how much a dose did, so take the default
+        Event::Medicated => {
+            let heal = Medicine::default().heal;
+            pet.health = pet.health.saturating_add(heal).min(100);
+        }
+        Event::Tick(TickEvent::Aged) => pet.age = pet.age.saturating_add(1),
+        Event::Tick(TickEvent::GrewUp(stage)) => {
+            // The first age the stage is reached at
+            let start = match stage {
+                LifeStage::Young => 0,
+                LifeStage::Adult => 21,
+                LifeStage::Elderly => 51,
+            };
+            pet.age = pet.age.max(start);
+        }
+        Event::Tick(TickEvent::Milestone(age)) => pet.age = age,
+        Event::Tick(TickEvent::GotSick) => {
+            pet.status = PetStatus::Sick;
+            pet.health = pet.health.min(RECOVERED_HEALTH - 1);
+        }
+        Event::Tick(TickEvent::Healed) => {
+            pet.status = PetStatus::Alive;
+            pet.health = pet.health.max(RECOVERED_HEALTH);
+        }
+        Event::Tick(TickEvent::ReachedCritical) => {
+            pet.health = pet.health.min(CRITICAL_HEALTH - 1);
+        }
+        Event::Tick(TickEvent::Died) => pet.health = 0,
+        Event::Tick(TickEvent::Retired) => pet.status = PetStatus::Retired,
+        Event::Tick(TickEvent::MadeMess) => pet.messes = (pet.messes + 1).min(MAX_MESSES),
+        Event::Tick(TickEvent::AutoFed) => pet.hunger = pet.hunger.saturating_sub(20),
+        Event::Tick(TickEvent::AutoWashed) => wash(pet),
+        Event::Tick(TickEvent::WentIntoHiding) => pet.hiding = true,
+        Event::Tick(TickEvent::CameOut) => pet.hiding = false,
+    }
+}
+
+fn wash(pet: &mut Pet) {
+    pet.cleanliness = 100;
+    pet.messes = 0;
+}
+
+/// How long the moment at `from` stays up before the one at `to`, with
+/// `speed` seconds of life going by per second
+pub fn pause(from: DateTime<Utc>, to: DateTime<Utc>, speed: u64) -> Duration {
+    // A clock that was set back gives a negative gap, which counts as none
+    let gap = to.signed_duration_since(from).to_std().unwrap_or_default();
+    gap.div_f64(speed.max(1) as f64).clamp(MIN_PAUSE, MAX_PAUSE)
+}
+
+/// The line under a moment, like the diary's "Day 3: I got sick...", or
+/// only the day for events the diary doesn't write about
+pub fn caption(moment: &Moment, lang: Lang) -> String {
+    let lived = moment.at.signed_duration_since(moment.pet.birth);
+    let day = day_label(lived.num_days().max(0) + 1, lang);
+    match sentence(moment.event, lang) {
+        Some(text) if lang == Lang::Zh => format!("{day}：{text}"),
+        Some(text) => format!("{day}: {text}"),
+        None => day,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn pet_with_log(events: &[Event]) -> Pet {
+        let mut pet = Pet::new("Mochi".to_string());
+        pet.log.clear();
+        for (hour, event) in events.iter().enumerate() {
+            pet.record(*event, pet.birth + TimeDelta::hours(hour as i64));
+        }
+        pet
+    }
+
+    #[test]
+    fn test_timeline_follows_the_log() {
+        let pet = pet_with_log(&[
+            Event::Adopted,
+            Event::Played { mood: 40 },
+            Event::Tick(TickEvent::MadeMess),
+            Event::Fed { hunger: 90 },
+            Event::Tick(TickEvent::GotSick),
+            Event::FellAsleep,
+            Event::Tick(TickEvent::Aged),
+            Event::Washed { cleanliness: 30 },
+            Event::Tick(TickEvent::Healed),
+            Event::Tick(TickEvent::GrewUp(LifeStage::Adult)),
+            Event::Tick(TickEvent::Died),
+        ]);
+        let moments = timeline(&pet);
+        assert_eq!(moments.len(), pet.log.len());
+        let at = |n: usize| &moments[n].pet;
+
+        assert_eq!((at(0).age, at(0).health, at(0).mood), (0, 100, 100));
+        assert_eq!(at(1).mood, 50);
+        assert_eq!(at(2).messes, 1);
+        assert_eq!(at(3).hunger, 70);
+        assert_eq!(at(4).status, PetStatus::Sick);
+        assert!(at(4).health < RECOVERED_HEALTH);
+        assert!(at(5).is_sleeping);
+        assert_eq!(at(6).age, 1);
+        assert_eq!((at(7).cleanliness, at(7).messes), (100, 0));
+        assert_eq!(at(8).status, PetStatus::Alive);
+        assert_eq!(at(9).stage(), LifeStage::Adult);
+        assert_eq!(at(10).health, 0);
+        assert_eq!(moments[3].at, pet.birth + TimeDelta::hours(3));
+        assert_eq!(
+            caption(&moments[3], Lang::En),
+            "Day 1: I was very hungry but you fed me just in time!"
+        );
+        assert_eq!(caption(&moments[2], Lang::En), "Day 1");
+    }
+
+    #[test]
+    fn test_timeline_copes_with_a_truncated_log() {
+        // The adoption and the first birthdays fell off the capped log
+        let mut pet = pet_with_log(&[
+            Event::Tick(TickEvent::Aged),
+            Event::Played { mood: 10 },
+            Event::Tick(TickEvent::Aged),
+        ]);
+        pet.age = 7;
+        let moments = timeline(&pet);
+        assert_eq!(moments[0].pet.age, 6);
+        assert_eq!(moments[1].pet.mood, 20);
+        assert_eq!(moments[2].pet.age, 7);
+        assert!(timeline(&pet_with_log(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_pause_is_scaled_and_bounded() {
+        let start = Utc::now();
+        let later = |seconds| start + TimeDelta::seconds(seconds);
+        assert_eq!(pause(start, later(3600), 3600), Duration::from_secs(1));
+        assert_eq!(pause(start, later(1), 3600), MIN_PAUSE);
+        // A week away and a clock that ran backwards
+        assert_eq!(pause(start, later(7 * 86_400), 3600), MAX_PAUSE);
+        assert_eq!(pause(later(60), start, 3600), MIN_PAUSE);
+        assert_eq!(pause(start, later(10), 0), MAX_PAUSE);
+    }
+}
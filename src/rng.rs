@@ -0,0 +1,80 @@
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Deterministic random numbers for one pet. Every number is derived from
+/// the seed and how many numbers came before it, so saving those two is
+/// enough to continue the same sequence after a restart. Restarting petty
+/// therefore can't reroll an unlucky event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PetRng {
+    pub seed: u64,
+    pub draws: u64,
+}
+
+impl PetRng {
+    /// Pick up the sequence at a saved position
+    pub fn resume(seed: u64, draws: u64) -> Self {
+        Self { seed, draws }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.draws = self.draws.wrapping_add(1);
+        splitmix64(
+            self.seed
+                .wrapping_add(self.draws.wrapping_mul(GOLDEN_GAMMA)),
+        )
+    }
+
+    /// True with a probability of 1 in `n`
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64().is_multiple_of(n.max(1))
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A seed for a new pet: `PETTY_SEED` if it is set, so tests and bug
+/// reports can pin a pet's luck, otherwise the clock. Seeds keep to 63
+/// bits because TOML saves only hold signed integers.
+pub fn new_seed() -> u64 {
+    let seed = match env::var("PETTY_SEED").ok().and_then(|s| s.parse().ok()) {
+        Some(seed) => seed,
+        None => {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default();
+            splitmix64(nanos ^ u64::from(std::process::id()))
+        }
+    };
+    seed & i64::MAX as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resuming_continues_the_sequence() {
+        let mut rng = PetRng::resume(42, 0);
+        let first: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        let rest: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+
+        let mut resumed = PetRng::resume(42, 3);
+        let resumed_rest: Vec<u64> = (0..3).map(|_| resumed.next_u64()).collect();
+        assert_eq!(resumed_rest, rest);
+        assert_ne!(first, rest);
+        assert_ne!(PetRng::resume(43, 0).next_u64(), first[0]);
+    }
+
+    #[test]
+    fn test_new_seeds_fit_every_save_format() {
+        assert!(new_seed() <= i64::MAX as u64);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::{Error, Result};
+use crate::i18n::Lang;
+use crate::pet::{Pet, PetStatus};
+use crate::state::{StateFile, StateFormat, load_state, pet_slots};
+use std::path::Path;
+
+/// One save found by `petty list`
+#[derive(Debug)]
+pub struct SavedPet {
+    /// Name of the save under `PETS_DIR`, or `None` for the default save
+    pub slot: Option<String>,
+    /// `None` if the save couldn't be read
+    pub pet: Option<Pet>,
+}
+
+/// Every pet saved in the data dir `dir`: the default save first, if
+/// there is one, then the others by name
+pub fn saved_pets(dir: &Path, format: StateFormat) -> Result<Vec<SavedPet>> {
+    let mut saves = Vec::new();
+    match load_state(&StateFile::in_dir(dir, format)) {
+        Ok(pet) => saves.push(SavedPet {
+            slot: None,
+            pet: Some(pet),
+        }),
+        Err(Error::NotFound(_)) => {}
+        Err(_) => saves.push(SavedPet {
+            slot: None,
+            pet: None,
+        }),
+    }
+    for slot in pet_slots(dir)? {
+        let pet = load_state(&StateFile::for_pet(dir, &slot, format)).ok();
+        saves.push(SavedPet {
+            slot: Some(slot),
+            pet,
+        });
+    }
+    Ok(saves)
+}
+
+/// The save of the pet called `name`, looking at the pets' own names
+/// before the names of the saves
+pub fn find<'a>(saves: &'a [SavedPet], name: &str) -> Option<&'a SavedPet> {
+    saves
+        .iter()
+        .find(|save| save.pet.as_ref().is_some_and(|pet| pet.name == name))
+        .or_else(|| saves.iter().find(|save| save.slot.as_deref() == Some(name)))
+}
+
+/// One line per save like `* Buddy  age 3, health 80, hunger 20, mood 90`,
+/// with the pet that launches by default marked
+pub fn list_lines(saves: &[SavedPet], active: Option<&str>, lang: Lang) -> Vec<String> {
+    if saves.is_empty() {
+        return vec![match lang {
+            Lang::Zh => "还没有保存的宠物".to_string(),
+            Lang::En => "No saved pets yet".to_string(),
+        }];
+    }
+    let width = saves
+        .iter()
+        .map(|save| label(save).chars().count())
+        .max()
+        .unwrap_or(0);
+    saves
+        .iter()
+        .map(|save| {
+            let marker = if save.slot.as_deref() == active {
+                "*"
+            } else {
+                " "
+            };
+            let name = label(save);
+            let padding = " ".repeat(width - name.chars().count());
+            let status = match &save.pet {
+                Some(pet) => status(pet, lang),
+                None => match lang {
+                    Lang::Zh => "存档无法读取".to_string(),
+                    Lang::En => "save can't be read".to_string(),
+                },
+            };
+            format!("{marker} {name}{padding}  {status}")
+        })
+        .collect()
+}
+
+fn label(save: &SavedPet) -> &str {
+    match (&save.pet, &save.slot) {
+        (Some(pet), _) => &pet.name,
+        (None, Some(slot)) => slot,
+        (None, None) => "state",
+    }
+}
+
+fn status(pet: &Pet, lang: Lang) -> String {
+    let mut status = match lang {
+        Lang::Zh => format!(
+            "{} 岁, 健康 {}, 饥饿 {}, 心情 {}",
+            pet.age, pet.health, pet.hunger, pet.mood
+        ),
+        Lang::En => format!(
+            "age {}, health {}, hunger {}, mood {}",
+            pet.age, pet.health, pet.hunger, pet.mood
+        ),
+    };
+    let condition = match (&pet.status, lang) {
+        (PetStatus::Sick, Lang::Zh) => Some("生病"),
+        (PetStatus::Sick, Lang::En) => Some("sick"),
+        (PetStatus::Abandoned, Lang::Zh) => Some("离家出走"),
+        (PetStatus::Abandoned, Lang::En) => Some("ran away"),
+        (PetStatus::Retired, Lang::Zh) => Some("已退休"),
+        (PetStatus::Retired, Lang::En) => Some("retired"),
+        (PetStatus::Alive, _) => None,
+    };
+    if let Some(condition) = condition {
+        status.push_str(", ");
+        status.push_str(condition);
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::save_state_to;
+    use std::fs;
+
+    #[test]
+    fn test_every_save_is_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let format = StateFormat::Json;
+        assert_eq!(
+            list_lines(&saved_pets(dir.path(), format).unwrap(), None, Lang::En),
+            vec!["No saved pets yet".to_string()]
+        );
+
+        let mut first = Pet::new("Petty".to_string());
+        first.age = 3;
+        save_state_to(&StateFile::in_dir(dir.path(), format), &first).unwrap();
+        fs::create_dir_all(dir.path().join("pets")).unwrap();
+        let mut buddy = Pet::new("Buddy".to_string());
+        (buddy.health, buddy.hunger, buddy.mood) = (15, 70, 40);
+        buddy.status = PetStatus::Sick;
+        save_state_to(&StateFile::for_pet(dir.path(), "Buddy", format), &buddy).unwrap();
+        fs::write(dir.path().join("pets/Broken.json"), "not json").unwrap();
+
+        let saves = saved_pets(dir.path(), format).unwrap();
+        assert_eq!(
+            list_lines(&saves, Some("Buddy"), Lang::En),
+            vec![
+                "  Petty   age 3, health 100, hunger 0, mood 100".to_string(),
+                "  Broken  save can't be read".to_string(),
+                "* Buddy   age 0, health 15, hunger 70, mood 40, sick".to_string(),
+            ]
+        );
+        assert_eq!(find(&saves, "Petty").unwrap().slot, None);
+        assert_eq!(
+            find(&saves, "Buddy").unwrap().slot.as_deref(),
+            Some("Buddy")
+        );
+        assert!(find(&saves, "Nobody").is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::i18n::Lang;
+use serde::{Deserialize, Serialize};
+
+/// Experience one training session earns
+pub const TRAIN_XP: u32 = 10;
+/// Experience the first level takes. Each level after it takes this much
+/// more than the one before, so training pays off less and less.
+pub const LEVEL_STEP: u32 = 20;
+/// Skills stop growing here
+pub const MAX_LEVEL: u8 = 10;
+
+/// Something the pet gets better at by training
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Skill {
+    Agility,
+    Intelligence,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 2] = [Skill::Agility, Skill::Intelligence];
+
+    pub fn name(self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Skill::Agility, Lang::Zh) => "敏捷",
+            (Skill::Agility, Lang::En) => "Agility",
+            (Skill::Intelligence, Lang::Zh) => "智力",
+            (Skill::Intelligence, Lang::En) => "Intelligence",
+        }
+    }
+}
+
+/// Total experience needed to reach `level`: one step for level 1, three
+/// for level 2, six for level 3 and so on
+pub fn xp_for_level(level: u8) -> u32 {
+    let level = u32::from(level.min(MAX_LEVEL));
+    LEVEL_STEP * level * (level + 1) / 2
+}
+
+/// The level `xp` experience has earned, at most `MAX_LEVEL`
+pub fn level_for_xp(xp: u32) -> u8 {
+    (1..=MAX_LEVEL)
+        .take_while(|level| xp >= xp_for_level(*level))
+        .last()
+        .unwrap_or(0)
+}
+
+/// Experience in each skill
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct Skills {
+    pub agility: u32,
+    pub intelligence: u32,
+}
+
+impl Skills {
+    pub fn xp(&self, skill: Skill) -> u32 {
+        match skill {
+            Skill::Agility => self.agility,
+            Skill::Intelligence => self.intelligence,
+        }
+    }
+
+    pub fn level(&self, skill: Skill) -> u8 {
+        level_for_xp(self.xp(skill))
+    }
+
+    /// Train whichever skill is furthest behind, agility on a tie. Nothing
+    /// grows past `MAX_LEVEL`.
+    pub fn train(&mut self) {
+        let xp = if self.intelligence < self.agility {
+            &mut self.intelligence
+        } else {
+            &mut self.agility
+        };
+        *xp = xp.saturating_add(TRAIN_XP).min(xp_for_level(MAX_LEVEL));
+    }
+
+    /// The first skill that is a level higher than in `before`, with its new level
+    pub fn leveled_up(&self, before: &Skills) -> Option<(Skill, u8)> {
+        Skill::ALL
+            .into_iter()
+            .map(|skill| (skill, self.level(skill)))
+            .find(|(skill, level)| *level > before.level(*skill))
+    }
+}
+
+/// One line per skill, like `Agility       Lv 2  [##--------]  60/120 XP`
+pub fn skill_lines(skills: &Skills, lang: Lang) -> Vec<String> {
+    let (title, maxed) = match lang {
+        Lang::Zh => ("技能", "已满级"),
+        Lang::En => ("Skills", "max level"),
+    };
+    let mut lines = vec![title.to_string(), String::new()];
+    for skill in Skill::ALL {
+        let (xp, level) = (skills.xp(skill), skills.level(skill));
+        let bar = format!(
+            "[{}{}]",
+            "#".repeat(level.into()),
+            "-".repeat((MAX_LEVEL - level).into())
+        );
+        let progress = if level == MAX_LEVEL {
+            maxed.to_string()
+        } else {
+            format!("{xp}/{} XP", xp_for_level(level + 1))
+        };
+        lines.push(format!(
+            "{:<14}Lv {level:<3}{bar}  {progress}",
+            skill.name(lang)
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_level_takes_longer() {
+        assert_eq!(xp_for_level(0), 0);
+        assert_eq!(xp_for_level(1), 20);
+        assert_eq!(xp_for_level(2), 60);
+        assert_eq!(xp_for_level(3), 120);
+        assert_eq!(level_for_xp(0), 0);
+        assert_eq!(level_for_xp(59), 1);
+        assert_eq!(level_for_xp(60), 2);
+        assert_eq!(level_for_xp(u32::MAX), MAX_LEVEL);
+        for level in 1..MAX_LEVEL {
+            let this = xp_for_level(level) - xp_for_level(level - 1);
+            let next = xp_for_level(level + 1) - xp_for_level(level);
+            assert!(next > this, "level {level}");
+        }
+    }
+
+    #[test]
+    fn test_training_follows_the_curve_up_to_the_cap() {
+        let mut skills = Skills::default();
+        // Two sessions per level-1 step: one for each skill
+        for _ in 0..4 {
+            skills.train();
+        }
+        assert_eq!((skills.agility, skills.intelligence), (20, 20));
+        assert_eq!(skills.level(Skill::Agility), 1);
+
+        let before = skills;
+        for _ in 0..7 {
+            skills.train();
+        }
+        assert_eq!(skills.level(Skill::Agility), 2);
+        assert_eq!(skills.leveled_up(&before), Some((Skill::Agility, 2)));
+
+        for _ in 0..1000 {
+            skills.train();
+        }
+        for skill in Skill::ALL {
+            assert_eq!(skills.level(skill), MAX_LEVEL);
+            assert_eq!(skills.xp(skill), xp_for_level(MAX_LEVEL));
+        }
+        assert_eq!(skills.leveled_up(&skills), None);
+    }
+
+    #[test]
+    fn test_skill_lines() {
+        let skills = Skills {
+            agility: 60,
+            intelligence: xp_for_level(MAX_LEVEL),
+        };
+        let lines = skill_lines(&skills, Lang::En);
+        assert_eq!(lines[2], "Agility       Lv 2  [##--------]  60/120 XP");
+        assert_eq!(lines[3], "Intelligence  Lv 10 [##########]  max level");
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The terminal bell
+pub const BELL: u8 = 0x07;
+/// Anything longer is cut off so a pattern can't ring for ages
+pub const MAX_PATTERN_LEN: usize = 8;
+/// Minimum time between the starts of two patterns
+pub const SOUND_COOLDOWN: Duration = Duration::from_secs(1);
+
+const SHORT_GAP: Duration = Duration::from_millis(120);
+const LONG_GAP: Duration = Duration::from_millis(400);
+const PAUSE: Duration = Duration::from_millis(250);
+
+/// One step of a bell pattern: ring (or not) and then wait
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub bell: bool,
+    pub wait: Duration,
+}
+
+/// Parse a pattern like `".."` or `"- -"`. `.` rings with a short gap
+/// after it, `-` rings with a long gap and a space is a pause.
+pub fn parse_pattern(pattern: &str) -> Result<Vec<Step>, String> {
+    if pattern.chars().count() > MAX_PATTERN_LEN {
+        return Err(format!(
+            "pattern {pattern:?} is longer than {MAX_PATTERN_LEN} steps"
+        ));
+    }
+    pattern
+        .chars()
+        .map(|c| match c {
+            '.' => Ok(Step {
+                bell: true,
+                wait: SHORT_GAP,
+            }),
+            '-' => Ok(Step {
+                bell: true,
+                wait: LONG_GAP,
+            }),
+            ' ' => Ok(Step {
+                bell: false,
+                wait: PAUSE,
+            }),
+            other => Err(format!(
+                "unknown step {other:?} in pattern {pattern:?}; use '.', '-' or ' '"
+            )),
+        })
+        .collect()
+}
+
+/// The bytes to write for each step, each followed by its wait
+pub fn bell_sequence(steps: &[Step]) -> Vec<(&'static [u8], Duration)> {
+    steps
+        .iter()
+        .map(|step| {
+            let bytes: &'static [u8] = if step.bell { &[BELL] } else { &[] };
+            (bytes, step.wait)
+        })
+        .collect()
+}
+
+/// Stops a burst of actions or events from ringing over each other
+#[derive(Debug, Default)]
+pub struct SoundPlayer {
+    last_played: Option<Instant>,
+}
+
+impl SoundPlayer {
+    /// Ring `pattern` in the background, unless it's empty or another
+    /// pattern started less than `SOUND_COOLDOWN` ago
+    pub fn play(&mut self, pattern: &str, now: Instant) {
+        let Ok(steps) = parse_pattern(pattern) else {
+            return;
+        };
+        if steps.is_empty() || !self.try_start(now) {
+            return;
+        }
+        thread::spawn(move || {
+            let mut out = io::stdout();
+            for (bytes, wait) in bell_sequence(&steps) {
+                // A bell that can't be rung isn't worth reporting
+                out.write_all(bytes).and_then(|()| out.flush()).ok();
+                thread::sleep(wait);
+            }
+        });
+    }
+
+    fn try_start(&mut self, now: Instant) -> bool {
+        let cooled_down = self
+            .last_played
+            .is_none_or(|played| now.duration_since(played) >= SOUND_COOLDOWN);
+        if cooled_down {
+            self.last_played = Some(now);
+        }
+        cooled_down
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(parse_pattern(""), Ok(Vec::new()));
+        assert_eq!(parse_pattern("..").unwrap().len(), 2);
+        assert!(parse_pattern(".x").is_err());
+        assert!(parse_pattern(&".".repeat(MAX_PATTERN_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_bell_sequence() {
+        let steps = parse_pattern(". -").unwrap();
+        let empty: &[u8] = &[];
+        assert_eq!(
+            bell_sequence(&steps),
+            vec![
+                (&[BELL][..], SHORT_GAP),
+                (empty, PAUSE),
+                (&[BELL][..], LONG_GAP)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sounds_are_rate_limited() {
+        let mut player = SoundPlayer::default();
+        let now = Instant::now();
+        assert!(player.try_start(now));
+        assert!(!player.try_start(now + SOUND_COOLDOWN / 2));
+        assert!(player.try_start(now + SOUND_COOLDOWN));
+    }
+}
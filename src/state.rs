@@ -1,39 +1,1331 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::history::{LogEntry, MAX_LOG_ENTRIES};
 use crate::pet::Pet;
-use std::fs;
-use std::io::{self, ErrorKind};
-use std::path::PathBuf;
-
-fn get_state_path() -> io::Result<PathBuf> {
-    let mut path = dirs::home_dir().ok_or(io::Error::new(
-        ErrorKind::NotFound,
-        "Could not find home directory",
-    ))?;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub fn get_data_dir() -> Result<PathBuf> {
+    data_dir_in(dirs::home_dir())
+}
+
+/// `~/.petty` under `home`, created if it's missing
+fn data_dir_in(home: Option<PathBuf>) -> Result<PathBuf> {
+    let mut path = home.ok_or(Error::NoHomeDir)?;
     path.push(".petty");
     fs::create_dir_all(&path)?;
-    path.push("state.json");
     Ok(path)
 }
 
-pub fn save_state(pet: &Pet) -> io::Result<()> {
-    let path = get_state_path()?;
-    let data = serde_json::to_string(pet)?;
-    fs::write(path, data)
+/// Directory under the data dir holding a save for each pet picked with
+/// `petty use`
+pub const PETS_DIR: &str = "pets";
+
+/// On-disk encoding of the saved pet
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StateFormat {
+    #[default]
+    Json,
+    /// Human-editable
+    Toml,
+    /// Compact, but not readable by builds with different `Pet` fields
+    Bincode,
+}
+
+impl StateFormat {
+    pub const ALL: [StateFormat; 3] = [StateFormat::Json, StateFormat::Toml, StateFormat::Bincode];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            StateFormat::Json => "json",
+            StateFormat::Toml => "toml",
+            StateFormat::Bincode => "bin",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+
+    pub fn encode(self, pet: &Pet) -> Result<Vec<u8>> {
+        match self {
+            StateFormat::Json => serde_json::to_vec(pet).map_err(|e| e.to_string()),
+            StateFormat::Toml => toml::to_string(pet)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string()),
+            StateFormat::Bincode => bincode::serde::encode_to_vec(pet, bincode::config::standard())
+                .map_err(|e| e.to_string()),
+        }
+        .map_err(Error::Serialization)
+    }
+
+    /// The pet in `data`. The error is only the reason; the caller knows
+    /// which file was corrupt.
+    pub fn decode(self, data: &[u8]) -> std::result::Result<Pet, String> {
+        match self {
+            StateFormat::Json => serde_json::from_slice(data).map_err(|e| e.to_string()),
+            StateFormat::Toml => {
+                let text = std::str::from_utf8(data).map_err(|e| e.to_string())?;
+                toml::from_str(text).map_err(|e| e.to_string())
+            }
+            StateFormat::Bincode => {
+                bincode::serde::decode_from_slice(data, bincode::config::standard())
+                    .map(|(pet, _)| pet)
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Megabytes a save or journal may take up before loading refuses it,
+/// unless the config sets `max_state_mb`
+pub const DEFAULT_MAX_STATE_MB: u64 = 16;
+
+/// Where the pet is saved and how it's encoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateFile {
+    pub path: PathBuf,
+    pub format: StateFormat,
+    /// Saves in other formats that are read when `path` doesn't exist yet,
+    /// and removed once the pet is saved in the new format
+    pub alternates: Vec<(PathBuf, StateFormat)>,
+    /// Bytes past which the save or one of its journals is rejected as
+    /// corrupt instead of read into memory
+    pub max_bytes: u64,
+}
+
+impl StateFile {
+    pub fn new(path: PathBuf, format: StateFormat) -> Self {
+        Self {
+            path,
+            format,
+            alternates: Vec::new(),
+            max_bytes: DEFAULT_MAX_STATE_MB << 20,
+        }
+    }
+
+    /// `state.<ext>` in `dir`, falling back to saves in the other formats
+    pub fn in_dir(dir: &Path, format: StateFormat) -> Self {
+        Self::with_stem(dir, "state", format)
+    }
+
+    /// The save of the pet called `slot` in the `PETS_DIR` under `dir`
+    pub fn for_pet(dir: &Path, slot: &str, format: StateFormat) -> Self {
+        Self::with_stem(&dir.join(PETS_DIR), slot, format)
+    }
+
+    fn with_stem(dir: &Path, stem: &str, format: StateFormat) -> Self {
+        let path_for = |format: StateFormat| dir.join(format!("{stem}.{}", format.extension()));
+        Self {
+            path: path_for(format),
+            format,
+            alternates: StateFormat::ALL
+                .into_iter()
+                .filter(|other| *other != format)
+                .map(|other| (path_for(other), other))
+                .collect(),
+            max_bytes: DEFAULT_MAX_STATE_MB << 20,
+        }
+    }
+
+    /// The same save, accepting up to `megabytes` when loaded
+    pub fn with_max_mb(self, megabytes: u64) -> Self {
+        Self {
+            max_bytes: megabytes.saturating_mul(1 << 20),
+            ..self
+        }
+    }
+}
+
+/// Resolve the state file from the config. An explicit `state_file` picks
+/// its format from the extension, falling back to the configured format.
+/// Otherwise the `active_pet` is loaded, or the default save if none is set.
+pub fn state_file(config: &Config) -> Result<StateFile> {
+    let file = match &config.state_file {
+        Some(path) => {
+            let format = StateFormat::from_path(path).unwrap_or(config.format);
+            StateFile::new(path.clone(), format)
+        }
+        None => state_file_in(&get_data_dir()?, config)?,
+    };
+    Ok(file.with_max_mb(config.max_state_mb))
+}
+
+fn state_file_in(dir: &Path, config: &Config) -> Result<StateFile> {
+    match &config.active_pet {
+        Some(slot) => {
+            fs::create_dir_all(dir.join(PETS_DIR))?;
+            Ok(StateFile::for_pet(dir, slot, config.format))
+        }
+        None => Ok(StateFile::in_dir(dir, config.format)),
+    }
+}
+
+/// All of `path`, unless it's over `max_bytes`: a save bloated by accident
+/// or on purpose is refused before it can eat the memory. Only that many
+/// bytes are ever read. Nesting is capped separately by serde_json, which
+/// gives up on JSON deeper than 128 levels.
+fn read_capped(path: &Path, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    File::open(path)?
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > max_bytes {
+        return Err(Error::Corrupt {
+            path: path.to_path_buf(),
+            reason: format!(
+                "it's bigger than the {} MB limit (raise `max_state_mb` in the config to load it)",
+                max_bytes >> 20
+            ),
+        });
+    }
+    Ok(data)
+}
+
+/// Whether `name` can be used as the file name of a pet's save
+pub fn valid_slot(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// Names of the pets saved in the `PETS_DIR` under `dir`, sorted
+pub fn pet_slots(dir: &Path) -> Result<Vec<String>> {
+    let entries = match fs::read_dir(dir.join(PETS_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut slots = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let stem = path.file_stem().and_then(|stem| stem.to_str());
+        if let (Some(_), Some(stem)) = (StateFormat::from_path(&path), stem) {
+            slots.push(stem.to_string());
+        }
+    }
+    slots.sort();
+    slots.dedup();
+    Ok(slots)
+}
+
+/// CRC-32 (IEEE), as used by zip and PNG
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// The checksum sits next to the save, e.g. `state.json.sum`, so the save
+/// itself stays a plain document in its format. It's written before the
+/// save and also holds the sum of the save being replaced, so a crash in
+/// between leaves the old save still vouched for.
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf().into_os_string();
+    path.push(".sum");
+    PathBuf::from(path)
+}
+
+/// The checksum file for `data`, about to replace the save `replacing`
+fn checksum_lines(data: &[u8], replacing: Option<&[u8]>) -> String {
+    let sum = crc32(data);
+    match replacing.map(crc32) {
+        Some(old) if old != sum => format!("{sum:08x}\n{old:08x}\n"),
+        _ => format!("{sum:08x}\n"),
+    }
+}
+
+/// Check `data` read from `path` against its checksum. Saves written
+/// before checksums existed have none and are trusted.
+fn verify_checksum(path: &Path, data: &[u8]) -> Result<()> {
+    let expected = match fs::read_to_string(checksum_path(path)) {
+        Ok(expected) => expected,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    let sum = format!("{:08x}", crc32(data));
+    if !expected.split_whitespace().any(|expected| expected == sum) {
+        return Err(Error::Corrupt {
+            path: path.to_path_buf(),
+            reason: format!(
+                "its checksum doesn't match {}",
+                checksum_path(path).display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// The event log and gallery only ever grow, so they're kept out of the
+/// save in journals of their own, e.g. `state.log.jsonl`. That way an
+/// autosave appends what's new instead of re-encoding the whole history.
+/// Every format shares the same journals.
+fn log_path(path: &Path) -> PathBuf {
+    path.with_extension("log.jsonl")
+}
+
+fn gallery_path(path: &Path) -> PathBuf {
+    path.with_extension("gallery.jsonl")
+}
+
+/// First line of a journal. A journal whose pet was born at another time
+/// belongs to an earlier pet and is ignored.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct JournalHeader {
+    birth: DateTime<Utc>,
+}
+
+/// One item in a journal, numbered from 0 in the order it was added
+#[derive(Serialize, Deserialize)]
+struct JournalLine<T> {
+    seq: u64,
+    item: T,
+}
+
+/// Longest journal line `journal_tail` expects to find
+const MAX_LINE_LEN: u64 = 4096;
+
+/// The header and the numbers of the first and last items, read without
+/// going through the whole journal. `None` if any of them can't be read.
+fn journal_ends(path: &Path) -> io::Result<Option<(JournalHeader, u64, u64)>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut reader = BufReader::new(&mut file);
+    let (mut header, mut first) = (String::new(), String::new());
+    reader.read_line(&mut header)?;
+    reader.read_line(&mut first)?;
+    let (Ok(header), Ok(first)) = (
+        serde_json::from_str::<JournalHeader>(&header),
+        serde_json::from_str::<JournalLine<serde::de::IgnoredAny>>(&first),
+    ) else {
+        return Ok(None);
+    };
+
+    let length = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(length.saturating_sub(MAX_LINE_LEN)))?;
+    let mut tail = String::new();
+    file.read_to_string(&mut tail)?;
+    let last = tail
+        .lines()
+        .last()
+        .and_then(|line| serde_json::from_str::<JournalLine<serde::de::IgnoredAny>>(line).ok());
+    Ok(last.map(|last| (header, first.seq, last.seq)))
+}
+
+/// Bring the journal at `path` up to date with `items`, numbered from
+/// `first_seq`. Items already in the journal are skipped. The journal is
+/// written afresh if it belongs to another pet, is ahead of `items`, has
+/// a torn last line or has grown to more than twice what it needs to hold.
+fn write_journal<T: Serialize>(
+    path: &Path,
+    birth: DateTime<Utc>,
+    items: &[T],
+    first_seq: u64,
+) -> io::Result<()> {
+    let end = first_seq + items.len() as u64;
+    let append_after = match journal_ends(path)? {
+        Some((header, first, last))
+            if header.birth == birth && last < end && end - first <= 2 * items.len() as u64 =>
+        {
+            Some(last)
+        }
+        _ => None,
+    };
+
+    let mut data = Vec::new();
+    if append_after.is_none() {
+        serde_json::to_writer(&mut data, &JournalHeader { birth })?;
+        data.push(b'\n');
+    }
+    let skip = append_after.map_or(0, |last| (last + 1).saturating_sub(first_seq));
+    for (seq, item) in (first_seq..).zip(items).skip(skip as usize) {
+        serde_json::to_writer(&mut data, &JournalLine { seq, item })?;
+        data.push(b'\n');
+    }
+    match append_after {
+        Some(_) => OpenOptions::new().append(true).open(path)?.write_all(&data),
+        None => fs::write(path, data),
+    }
+}
+
+/// Every item in the journal at `path` by number, or nothing if there's no
+/// journal for the pet born at `birth`. A line torn by a crash is skipped.
+fn read_journal<T: DeserializeOwned>(
+    path: &Path,
+    birth: DateTime<Utc>,
+    max_bytes: u64,
+) -> Result<BTreeMap<u64, T>> {
+    let data = match read_capped(path, max_bytes) {
+        Ok(data) => String::from_utf8_lossy(&data).into_owned(),
+        Err(Error::Io(e)) if e.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e),
+    };
+    let mut lines = data.lines();
+    let header = lines
+        .next()
+        .and_then(|line| serde_json::from_str::<JournalHeader>(line).ok());
+    if header != Some(JournalHeader { birth }) {
+        return Ok(BTreeMap::new());
+    }
+    Ok(lines
+        .filter_map(|line| serde_json::from_str::<JournalLine<T>>(line).ok())
+        .map(|line| (line.seq, line.item))
+        .collect())
+}
+
+/// Put the journals back into a pet loaded from the save at `path`
+fn reassemble(path: &Path, pet: &mut Pet, max_bytes: u64) -> Result<()> {
+    // Saves from before the journals keep everything inline
+    pet.recorded = pet.recorded.max(pet.log.len() as u64);
+    if pet.log.is_empty() {
+        // Entries past `recorded` made it to the journal just before a
+        // crash kept the save itself from being written
+        let mut kept: Vec<(u64, LogEntry)> = read_journal(&log_path(path), pet.birth, max_bytes)?
+            .into_iter()
+            .filter(|(seq, _)| *seq < pet.recorded)
+            .collect();
+        kept.drain(..kept.len().saturating_sub(MAX_LOG_ENTRIES));
+        // Keep the numbering lined up with the journal if it lost entries
+        if let Some((seq, _)) = kept.last() {
+            pet.recorded = seq + 1;
+        }
+        pet.log = kept.into_iter().map(|(_, entry)| entry).collect();
+    }
+    if pet.gallery.is_empty() {
+        pet.gallery = read_journal(&gallery_path(path), pet.birth, max_bytes)?
+            .into_values()
+            .collect();
+    }
+    Ok(())
+}
+
+pub fn save_state_to(file: &StateFile, pet: &Pet) -> Result<()> {
+    let first_seq = pet.recorded.saturating_sub(pet.log.len() as u64);
+    write_journal(&log_path(&file.path), pet.birth, &pet.log, first_seq)?;
+    write_journal(&gallery_path(&file.path), pet.birth, &pet.gallery, 0)?;
+
+    let mut core = pet.clone();
+    core.log.clear();
+    core.gallery.clear();
+    let data = file.format.encode(&core)?;
+    let sums = checksum_lines(&data, fs::read(&file.path).ok().as_deref());
+    write_whole(&checksum_path(&file.path), sums.as_bytes())?;
+    write_whole(&file.path, &data)?;
+    // The pet now lives in the new format, so old saves mustn't shadow it
+    for (path, _) in &file.alternates {
+        delete_save(path)?;
+    }
+    Ok(())
+}
+
+/// Write `data` to `path` whole or not at all, through a file next to it
+/// that is renamed over it
+fn write_whole(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temp = path.to_path_buf().into_os_string();
+    temp.push(".tmp");
+    fs::write(&temp, data)?;
+    fs::rename(&temp, path)
+}
+
+/// Where `file`'s save may be, in the order `load_state` looks for it
+fn candidates(file: &StateFile) -> impl Iterator<Item = (&PathBuf, StateFormat)> {
+    std::iter::once((&file.path, file.format))
+        .chain(file.alternates.iter().map(|(path, format)| (path, *format)))
 }
 
-pub fn load_state() -> io::Result<Pet> {
-    let path = get_state_path()?;
-    if !path.exists() {
-        return Err(io::Error::new(ErrorKind::NotFound, "State file not found"));
+pub fn load_state(file: &StateFile) -> Result<Pet> {
+    for (path, format) in candidates(file) {
+        if path.exists() {
+            let data = read_capped(path, file.max_bytes)?;
+            verify_checksum(path, &data)?;
+            let mut pet = format.decode(&data).map_err(|reason| Error::Corrupt {
+                path: path.clone(),
+                reason,
+            })?;
+            reassemble(path, &mut pet, file.max_bytes)?;
+            return Ok(pet);
+        }
     }
-    let data = fs::read_to_string(path)?;
-    let pet = serde_json::from_str(&data)?;
+    Err(Error::NotFound(file.path.clone()))
+}
+
+/// The copy of the save kept from before the last session, e.g.
+/// `state.json.bak`, for `petty restore`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf().into_os_string();
+    path.push(".bak");
+    PathBuf::from(path)
+}
+
+/// Keep the save `load_state` would read as it is now at its
+/// `backup_path`, replacing any older backup. Only the save itself is
+/// copied; the journals are shared.
+pub fn backup_state(file: &StateFile) -> Result<()> {
+    let Some((path, _)) = candidates(file).find(|(path, _)| path.exists()) else {
+        return Ok(());
+    };
+    let backup = backup_path(path);
+    // The save's own sums vouch for the copy, so a damaged save doesn't
+    // leave a backup that looks sound. Like a save, the sums go first and
+    // keep vouching for the old backup until it's replaced.
+    match fs::read_to_string(checksum_path(path)) {
+        Ok(mut sums) => {
+            if let Ok(old) = fs::read(&backup) {
+                sums.push_str(&format!("{:08x}\n", crc32(&old)));
+            }
+            write_whole(&checksum_path(&backup), sums.as_bytes())?;
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => delete_file(&checksum_path(&backup))?,
+        Err(e) => return Err(e.into()),
+    }
+    write_whole(&backup, &fs::read(path)?)?;
+    // A backup of the save in another format is older than this one
+    for (other, _) in candidates(file).filter(|(other, _)| *other != path) {
+        delete_save(&backup_path(other))?;
+    }
+    Ok(())
+}
+
+/// The backup of `file` and the format it's in, or where it would be
+/// in `file`'s own format if there's none
+pub fn find_backup(file: &StateFile) -> (PathBuf, StateFormat) {
+    candidates(file)
+        .map(|(path, format)| (backup_path(path), format))
+        .find(|(backup, _)| backup.exists())
+        .unwrap_or_else(|| (backup_path(&file.path), file.format))
+}
+
+/// The pet in the backup of `file`, checked to load like a save would
+pub fn load_backup(file: &StateFile) -> Result<Pet> {
+    let (path, format) = find_backup(file);
+    let data = match read_capped(&path, file.max_bytes) {
+        Ok(data) => data,
+        Err(Error::Io(e)) if e.kind() == ErrorKind::NotFound => {
+            return Err(Error::NotFound(path));
+        }
+        Err(e) => return Err(e),
+    };
+    verify_checksum(&path, &data)?;
+    let mut pet = format
+        .decode(&data)
+        .map_err(|reason| Error::Corrupt { path, reason })?;
+    reassemble(&file.path, &mut pet, file.max_bytes)?;
+    Ok(pet)
+}
+
+/// Make the backup of `file` the save again, dropping the backup. The
+/// save is only touched once the backup has loaded, so a bad backup
+/// leaves it as it was.
+pub fn restore_backup(file: &StateFile) -> Result<Pet> {
+    let pet = load_backup(file)?;
+    let (backup, _) = find_backup(file);
+    save_state_to(file, &pet)?;
+    delete_save(&backup)?;
     Ok(pet)
 }
 
-pub fn delete_state() -> io::Result<()> {
-    let path = get_state_path()?;
+pub fn delete_state_at(file: &StateFile) -> Result<()> {
+    delete_save(&file.path)?;
+    delete_save(&backup_path(&file.path))?;
+    delete_file(&log_path(&file.path))?;
+    delete_file(&gallery_path(&file.path))?;
+    for (path, _) in &file.alternates {
+        delete_save(path)?;
+        delete_save(&backup_path(path))?;
+    }
+    Ok(())
+}
+
+fn delete_save(path: &Path) -> io::Result<()> {
+    delete_file(path)?;
+    delete_file(&checksum_path(path))
+}
+
+fn delete_file(path: &Path) -> io::Result<()> {
     if path.exists() {
         fs::remove_file(path)?;
     }
     Ok(())
 }
+
+/// Advisory lock preventing two instances from writing the same state file.
+/// The lock file holds the owner's PID and is removed when the guard drops.
+#[derive(Debug)]
+pub struct StateLock {
+    path: PathBuf,
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// The lock sits next to the state file, e.g. `state.json.lock`
+pub fn lock_state(file: &StateFile) -> Result<StateLock> {
+    let mut path = file.path.clone().into_os_string();
+    path.push(".lock");
+    acquire_lock(Path::new(&path))
+}
+
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks whether the process exists
+    unsafe {
+        libc::kill(pid, 0) == 0 || io::Error::last_os_error().kind() == ErrorKind::PermissionDenied
+    }
+}
+
+/// A lock is stale when its owner is gone or the file is unreadable
+fn lock_is_stale(path: &Path) -> bool {
+    match fs::read_to_string(path) {
+        Ok(data) => data
+            .trim()
+            .parse::<u32>()
+            .map_or(true, |pid| !process_alive(pid)),
+        Err(_) => true,
+    }
+}
+
+fn acquire_lock(path: &Path) -> Result<StateLock> {
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                write!(file, "{}", process::id())?;
+                return Ok(StateLock {
+                    path: path.to_path_buf(),
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if !lock_is_stale(path) {
+                    break;
+                }
+                fs::remove_file(path)?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    // Held, or taken again by someone else as soon as the stale lock went
+    let owner = fs::read_to_string(path).unwrap_or_default();
+    Err(Error::Locked {
+        pid: owner.trim().to_string(),
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::Achievement;
+    use crate::bond::MAX_BOND;
+    use crate::favorites::Favorites;
+    use crate::gallery::Snapshot;
+    use crate::habits::Habits;
+    use crate::history::{Event, LogEntry};
+    use crate::personality::Traits;
+    use crate::pet::{
+        CareTally, Companion, Doses, LifeStage, MAX_PASTIMES, PASTIMES, Pastime, PetStatus,
+        TickEvent,
+    };
+    use crate::skills::Skills;
+    use chrono::TimeDelta;
+    use proptest::prelude::*;
+
+    fn sample_pet() -> Pet {
+        let mut pet = Pet::new("Roundtrip".to_string());
+        pet.hunger = 42;
+        pet.record(Event::Fed { hunger: 90 }, Utc::now());
+        pet.record(Event::Tick(TickEvent::Milestone(10)), Utc::now());
+        pet
+    }
+
+    fn any_time() -> impl Strategy<Value = DateTime<Utc>> {
+        // Year 1900 to 2200, down to the nanosecond
+        (-2_208_988_800i64..7_258_118_400, 0u32..1_000_000_000)
+            .prop_map(|(secs, nanos)| DateTime::from_timestamp(secs, nanos).unwrap())
+    }
+
+    fn any_stage() -> impl Strategy<Value = LifeStage> {
+        prop_oneof![
+            Just(LifeStage::Young),
+            Just(LifeStage::Adult),
+            Just(LifeStage::Elderly)
+        ]
+    }
+
+    fn any_event() -> impl Strategy<Value = Event> {
+        let tick = prop_oneof![
+            Just(TickEvent::Aged),
+            any_stage().prop_map(TickEvent::GrewUp),
+            any::<u32>().prop_map(TickEvent::Milestone),
+            Just(TickEvent::GotSick),
+            Just(TickEvent::Healed),
+            Just(TickEvent::ReachedCritical),
+            Just(TickEvent::Died),
+            Just(TickEvent::Retired),
+            Just(TickEvent::MadeMess),
+            Just(TickEvent::DozedOff),
+            Just(TickEvent::AutoFed),
+            Just(TickEvent::AutoWashed),
+            Just(TickEvent::WentIntoHiding),
+            Just(TickEvent::CameOut),
+        ];
+        prop_oneof![
+            Just(Event::Adopted),
+            any::<u8>().prop_map(|hunger| Event::Fed { hunger }),
+            any::<u8>().prop_map(|cleanliness| Event::Washed { cleanliness }),
+            any::<u8>().prop_map(|mood| Event::Played { mood }),
+            Just(Event::Trained),
+            Just(Event::FellAsleep),
+            Just(Event::WokeUp),
+            Just(Event::Pampered),
+            any::<u8>().prop_map(|hunger| Event::GraceFed { hunger }),
+            tick.prop_map(Event::Tick),
+            Just(Event::Medicated),
+            any::<u8>().prop_map(|hunger| Event::Snacked { hunger }),
+        ]
+    }
+
+    fn any_pet() -> impl Strategy<Value = Pet> {
+        let stats = (any::<u32>(), any::<[u8; 5]>(), any::<u32>());
+        let flags = (
+            any::<bool>(),
+            prop_oneof![
+                Just(PetStatus::Alive),
+                Just(PetStatus::Sick),
+                Just(PetStatus::Abandoned),
+                Just(PetStatus::Retired)
+            ],
+            any::<bool>(),
+        );
+        let log = prop::collection::vec(
+            (any_time(), any_event()).prop_map(|(at, event)| LogEntry { at, event }),
+            0..20,
+        );
+        let gallery = prop::collection::vec(
+            (any_stage(), any::<u32>(), any_time(), any::<[u8; 4]>()).prop_map(
+                |(stage, age, taken, [health, hunger, cleanliness, mood])| Snapshot {
+                    stage,
+                    age,
+                    taken,
+                    health,
+                    hunger,
+                    cleanliness,
+                    mood,
+                },
+            ),
+            0..4,
+        );
+        let achievements = prop::collection::hash_set(
+            prop::sample::select(Achievement::ALL.to_vec()),
+            0..=Achievement::ALL.len(),
+        );
+        let companion = prop::option::of(
+            (any::<String>(), any::<u8>()).prop_map(|(name, hunger)| Companion { name, hunger }),
+        );
+        let doses = (
+            any::<u32>(),
+            prop::option::of(any_time()),
+            prop::option::of(any_time()),
+        )
+            .prop_map(|(taken, since, last)| Doses { taken, since, last });
+        let pastimes = prop::collection::vec(
+            (prop::sample::select(PASTIMES.to_vec()), any_time())
+                .prop_map(|(action, at)| Pastime { action, at }),
+            0..=MAX_PASTIMES,
+        );
+        let habits = (prop::option::of(any_time()), any::<[u32; 4]>()).prop_map(
+            |(last_meal, [meals, meal_interval, meal_jitter, play_share])| Habits {
+                last_meal,
+                meals,
+                meal_interval,
+                meal_jitter,
+                play_share,
+            },
+        );
+        (
+            any::<String>(),
+            stats,
+            flags,
+            any_time(),
+            any_time(),
+            log,
+            companion,
+            gallery,
+            habits,
+            // Anything a TOML integer can hold
+            (
+                achievements,
+                any::<u32>(),
+                0..=i64::MAX as u64,
+                0..=i64::MAX as u64,
+                any::<u8>(),
+                0..1000u64,
+                any::<(u32, u32)>(),
+                (
+                    any::<u32>(),
+                    0..=MAX_BOND,
+                    any::<(u32, u32)>(),
+                    any::<(bool, u32)>(),
+                    prop::option::of(any::<i32>()),
+                    doses,
+                    any::<u32>(),
+                    pastimes,
+                    (any::<[i8; 3]>(), any::<u32>()),
+                    (prop::option::of(any_time()), any::<u32>()),
+                ),
+            ),
+        )
+            .prop_map(
+                |(
+                    name,
+                    (age, [health, hunger, cleanliness, mood, messes], starving_ticks),
+                    flags,
+                    last_seen,
+                    birth,
+                    log,
+                    companion,
+                    gallery,
+                    habits,
+                    (
+                        achievements,
+                        high_stats_seconds,
+                        rng_seed,
+                        rng_draws,
+                        fatigue,
+                        dropped,
+                        (agility, intelligence),
+                        (
+                            coins,
+                            bond,
+                            (sum, seconds),
+                            (hiding, sulk_seconds),
+                            birthday_celebrated,
+                            doses,
+                            idle_seconds,
+                            pastimes,
+                            ([energy, cheer, tidiness], trait_seconds),
+                            (digested_at, weight),
+                        ),
+                    ),
+                )| {
+                    let (is_sleeping, status, debug_mode) = flags;
+                    Pet {
+                        name,
+                        age,
+                        health,
+                        hunger,
+                        cleanliness,
+                        mood,
+                        is_sleeping,
+                        status,
+                        last_seen,
+                        debug_mode,
+                        birth,
+                        messes,
+                        starving_ticks,
+                        companion,
+                        achievements,
+                        high_stats_seconds,
+                        rng_seed,
+                        rng_draws,
+                        fatigue,
+                        recorded: log.len() as u64 + dropped,
+                        log,
+                        gallery,
+                        skills: Skills {
+                            agility,
+                            intelligence,
+                        },
+                        coins,
+                        habits,
+                        favorites: Favorites::for_seed(rng_seed),
+                        bond,
+                        care_tally: CareTally { sum, seconds },
+                        hiding,
+                        sulk_seconds,
+                        doses,
+                        idle_seconds,
+                        pastimes,
+                        traits: Traits {
+                            energy,
+                            cheer,
+                            tidiness,
+                            seconds: trait_seconds,
+                        },
+                        birthday_celebrated,
+                        digested_at,
+                        weight,
+                    }
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn test_every_pet_survives_each_format(pet in any_pet()) {
+            for format in StateFormat::ALL {
+                let decoded = format.decode(&format.encode(&pet).unwrap()).unwrap();
+                prop_assert_eq!(&decoded, &pet, "{:?}", format);
+            }
+        }
+
+        #[test]
+        fn test_split_save_reassembles_the_pet(pet in any_pet()) {
+            let dir = tempfile::tempdir().unwrap();
+            for format in StateFormat::ALL {
+                let file = StateFile::new(dir.path().join("state"), format);
+                save_state_to(&file, &pet).unwrap();
+                prop_assert_eq!(&load_state(&file).unwrap(), &pet, "{:?}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn test_autosave_appends_to_the_journals() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = sample_pet();
+        save_state_to(&file, &pet).unwrap();
+
+        pet.record(Event::Trained, Utc::now());
+        save_state_to(&file, &pet).unwrap();
+        save_state_to(&file, &pet).unwrap();
+
+        // The save holds none of the log, which is written once per entry
+        let core: Pet = serde_json::from_slice(&fs::read(&file.path).unwrap()).unwrap();
+        assert!(core.log.is_empty());
+        let journal = fs::read_to_string(log_path(&file.path)).unwrap();
+        assert_eq!(journal.lines().count(), 1 + pet.log.len());
+        assert_eq!(load_state(&file).unwrap(), pet);
+    }
+
+    #[test]
+    fn test_journal_is_compacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = sample_pet();
+        for _ in 0..5 {
+            for _ in 0..MAX_LOG_ENTRIES / 2 {
+                pet.record(Event::Trained, Utc::now());
+            }
+            save_state_to(&file, &pet).unwrap();
+        }
+
+        let journal = fs::read_to_string(log_path(&file.path)).unwrap();
+        assert!(journal.lines().count() <= 1 + 2 * MAX_LOG_ENTRIES);
+        assert_eq!(load_state(&file).unwrap(), pet);
+    }
+
+    #[test]
+    fn test_save_from_before_journals_loads_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = sample_pet();
+        pet.gallery.push(Snapshot {
+            stage: LifeStage::Young,
+            age: 1,
+            taken: Utc::now(),
+            health: 100,
+            hunger: 10,
+            cleanliness: 90,
+            mood: 80,
+        });
+        let mut monolithic = serde_json::to_value(&pet).unwrap();
+        monolithic.as_object_mut().unwrap().remove("recorded");
+        fs::write(&file.path, serde_json::to_vec(&monolithic).unwrap()).unwrap();
+
+        let loaded = load_state(&file).unwrap();
+        assert_eq!(loaded, pet);
+        save_state_to(&file, &loaded).unwrap();
+        assert_eq!(load_state(&file).unwrap(), pet);
+    }
+
+    #[test]
+    fn test_new_pet_ignores_old_journals() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        save_state_to(&file, &sample_pet()).unwrap();
+
+        let mut pet = Pet::new("Second".to_string());
+        pet.birth += TimeDelta::seconds(1);
+        save_state_to(&file, &pet).unwrap();
+        assert_eq!(load_state(&file).unwrap(), pet);
+
+        delete_state_at(&file).unwrap();
+        assert!(!log_path(&file.path).exists());
+        assert!(!gallery_path(&file.path).exists());
+    }
+
+    #[test]
+    fn test_round_trip_each_format() {
+        let pet = sample_pet();
+        for format in StateFormat::ALL {
+            let encoded = format.encode(&pet).unwrap();
+            let decoded = format.decode(&encoded).unwrap();
+            assert_eq!(decoded.name, pet.name);
+            assert_eq!(decoded.hunger, 42);
+            assert_eq!(decoded.log, pet.log);
+            assert_eq!(format.encode(&decoded).unwrap(), encoded, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_formats_encode_differently() {
+        let pet = sample_pet();
+        let json = StateFormat::Json.encode(&pet).unwrap();
+        let toml = StateFormat::Toml.encode(&pet).unwrap();
+        let bincode = StateFormat::Bincode.encode(&pet).unwrap();
+
+        assert!(json.starts_with(b"{"));
+        assert!(
+            String::from_utf8(toml.clone())
+                .unwrap()
+                .contains("name = \"Roundtrip\"")
+        );
+        assert!(bincode.len() < json.len());
+        assert_ne!(json, toml);
+        assert_ne!(json, bincode);
+        // Decoding with the wrong format fails instead of producing garbage
+        assert!(StateFormat::Json.decode(&bincode).is_err());
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            StateFormat::from_path(Path::new("/tmp/pet.toml")),
+            Some(StateFormat::Toml)
+        );
+        assert_eq!(
+            StateFormat::from_path(Path::new("pet.bin")),
+            Some(StateFormat::Bincode)
+        );
+        assert_eq!(StateFormat::from_path(Path::new("pet")), None);
+    }
+
+    #[test]
+    fn test_active_pet_has_its_own_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        assert_eq!(
+            state_file_in(dir.path(), &config).unwrap(),
+            StateFile::in_dir(dir.path(), config.format)
+        );
+
+        config.active_pet = Some("Buddy".to_string());
+        let file = state_file_in(dir.path(), &config).unwrap();
+        assert_eq!(file.path, dir.path().join("pets/Buddy.json"));
+        save_state_to(&file, &sample_pet()).unwrap();
+        assert_eq!(pet_slots(dir.path()).unwrap(), ["Buddy"]);
+
+        assert!(valid_slot("Buddy"));
+        assert!(!valid_slot("../state") && !valid_slot(".hidden") && !valid_slot(""));
+    }
+
+    #[test]
+    fn test_switching_formats_migrates_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let pet = sample_pet();
+
+        let json = StateFile::in_dir(dir.path(), StateFormat::Json);
+        save_state_to(&json, &pet).unwrap();
+
+        let toml = StateFile::in_dir(dir.path(), StateFormat::Toml);
+        let loaded = load_state(&toml).unwrap();
+        assert_eq!(loaded.name, pet.name);
+
+        save_state_to(&toml, &loaded).unwrap();
+        assert!(toml.path.exists());
+        assert!(!json.path.exists());
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_checksum_guards_the_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let sum_path = checksum_path(&file.path);
+
+        save_state_to(&file, &sample_pet()).unwrap();
+        let sum = fs::read_to_string(&sum_path).unwrap();
+        let loaded = load_state(&file).unwrap();
+        assert_eq!(loaded.hunger, 42);
+
+        // Saving what was loaded gives the same checksum
+        save_state_to(&file, &loaded).unwrap();
+        assert_eq!(fs::read_to_string(&sum_path).unwrap(), sum);
+
+        let mut data = fs::read(&file.path).unwrap();
+        let last = data.len() - 2;
+        data[last] ^= 0x01;
+        fs::write(&file.path, data).unwrap();
+        let error = load_state(&file).unwrap_err();
+        assert!(matches!(error, Error::Corrupt { .. }), "{error:?}");
+        assert!(error.to_string().contains("corrupt"));
+
+        delete_state_at(&file).unwrap();
+        assert!(!sum_path.exists());
+    }
+
+    #[test]
+    fn test_save_cut_short_between_sum_and_body_still_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = sample_pet();
+        save_state_to(&file, &pet).unwrap();
+        let saved = fs::read(&file.path).unwrap();
+
+        // Killed once the next checksum was in but before the save was, so
+        // the save on disk lags its checksum
+        pet.hunger = 7;
+        save_state_to(&file, &pet).unwrap();
+        fs::write(&file.path, &saved).unwrap();
+        assert_eq!(load_state(&file).unwrap().hunger, 42);
+
+        // The next save picks up from there, leaving nothing behind
+        save_state_to(&file, &pet).unwrap();
+        assert_eq!(load_state(&file).unwrap().hunger, 7);
+        let sums = fs::read_to_string(checksum_path(&file.path)).unwrap();
+        assert_eq!(sums.lines().count(), 2);
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert!(
+            !names.iter().any(|name| name.ends_with(".tmp")),
+            "{names:?}"
+        );
+    }
+
+    #[test]
+    fn test_restore_promotes_a_valid_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let error = load_backup(&file).unwrap_err();
+        assert!(
+            matches!(&error, Error::NotFound(path) if *path == backup_path(&file.path)),
+            "{error:?}"
+        );
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 10;
+        save_state_to(&file, &pet).unwrap();
+        backup_state(&file).unwrap();
+        // A bad catch-up after the backup was taken
+        pet.hunger = 100;
+        pet.record(Event::Fed { hunger: 100 }, Utc::now());
+        save_state_to(&file, &pet).unwrap();
+
+        let restored = restore_backup(&file).unwrap();
+        assert_eq!(restored.hunger, 10);
+        assert_eq!(load_state(&file).unwrap(), restored);
+        assert_eq!(restored.log.len(), 1);
+        assert!(!backup_path(&file.path).exists());
+    }
+
+    #[test]
+    fn test_restore_refuses_a_bad_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 42;
+        save_state_to(&file, &pet).unwrap();
+        let saved = fs::read(&file.path).unwrap();
+
+        let backup = backup_path(&file.path);
+        fs::write(&backup, "{ not a pet").unwrap();
+        let error = restore_backup(&file).unwrap_err();
+        assert!(matches!(error, Error::Corrupt { .. }), "{error:?}");
+        assert_eq!(fs::read(&file.path).unwrap(), saved);
+        assert_eq!(load_state(&file).unwrap().hunger, 42);
+        assert!(backup.exists());
+    }
+
+    #[test]
+    fn test_restore_refuses_a_backup_that_fails_its_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let mut pet = Pet::new("TestPet".to_string());
+        save_state_to(&file, &pet).unwrap();
+        backup_state(&file).unwrap();
+        pet.hunger = 42;
+        save_state_to(&file, &pet).unwrap();
+
+        // Still a pet, but not the one the backup was taken of
+        let backup = backup_path(&file.path);
+        let taken = fs::read_to_string(&backup).unwrap();
+        let damaged = taken.replace("\"hunger\":0", "\"hunger\":9");
+        assert_ne!(damaged, taken);
+        fs::write(&backup, damaged).unwrap();
+        let error = restore_backup(&file).unwrap_err();
+        assert!(matches!(error, Error::Corrupt { .. }), "{error:?}");
+        assert_eq!(load_state(&file).unwrap().hunger, 42);
+    }
+
+    #[test]
+    fn test_backup_follows_a_save_in_another_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = StateFile::in_dir(dir.path(), StateFormat::Toml);
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 10;
+        save_state_to(&toml, &pet).unwrap();
+
+        // The config switched to JSON, but the pet still lives in TOML
+        let json = StateFile::in_dir(dir.path(), StateFormat::Json);
+        assert_eq!(load_state(&json).unwrap().hunger, 10);
+        backup_state(&json).unwrap();
+        assert!(backup_path(&toml.path).exists());
+        assert_eq!(find_backup(&json).0, backup_path(&toml.path));
+        pet.hunger = 100;
+        save_state_to(&json, &pet).unwrap();
+
+        let restored = restore_backup(&json).unwrap();
+        assert_eq!(restored.hunger, 10);
+        assert_eq!(load_state(&json).unwrap().hunger, 10);
+        assert!(json.path.exists());
+        assert!(!backup_path(&toml.path).exists());
+        assert!(!checksum_path(&backup_path(&toml.path)).exists());
+    }
+
+    #[test]
+    fn test_failures_are_told_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        let error = load_state(&file).unwrap_err();
+        assert!(
+            matches!(&error, Error::NotFound(path) if *path == file.path),
+            "{error:?}"
+        );
+
+        for format in StateFormat::ALL {
+            let file = StateFile::new(
+                dir.path().join(format!("garbage.{}", format.extension())),
+                format,
+            );
+            fs::write(&file.path, [0xff, 0x00, 0x7b]).unwrap();
+            let error = load_state(&file).unwrap_err();
+            assert!(
+                matches!(error, Error::Corrupt { .. }),
+                "{format:?}: {error:?}"
+            );
+        }
+
+        // The save's directory is a file, so nothing can be written under it
+        let blocked = dir.path().join("blocked");
+        fs::write(&blocked, "").unwrap();
+        let file = StateFile::in_dir(&blocked, StateFormat::Json);
+        let error = save_state_to(&file, &sample_pet()).unwrap_err();
+        assert!(matches!(error, Error::Io(_)), "{error:?}");
+
+        assert!(matches!(data_dir_in(None), Err(Error::NoHomeDir)));
+        assert_eq!(
+            data_dir_in(Some(dir.path().to_path_buf())).unwrap(),
+            dir.path().join(".petty")
+        );
+    }
+
+    #[test]
+    fn test_oversized_saves_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json).with_max_mb(1);
+        let pet = sample_pet();
+        save_state_to(&file, &pet).unwrap();
+        assert_eq!(load_state(&file).unwrap(), pet);
+
+        // A log bloated past the cap
+        let mut journal = fs::read(log_path(&file.path)).unwrap();
+        journal.resize(2 << 20, b'\n');
+        fs::write(log_path(&file.path), journal).unwrap();
+        let error = load_state(&file).unwrap_err();
+        assert!(matches!(error, Error::Corrupt { .. }), "{error:?}");
+        assert!(error.to_string().contains("1 MB limit"));
+        assert!(load_state(&file.clone().with_max_mb(4)).is_ok());
+
+        // And a save nested too deep to decode safely
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        fs::write(&file.path, nested).unwrap();
+        fs::remove_file(checksum_path(&file.path)).unwrap();
+        let error = load_state(&file).unwrap_err();
+        assert!(matches!(error, Error::Corrupt { .. }), "{error:?}");
+    }
+
+    #[test]
+    fn test_save_without_checksum_still_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        fs::write(&file.path, StateFormat::Json.encode(&sample_pet()).unwrap()).unwrap();
+        assert_eq!(load_state(&file).unwrap().name, "Roundtrip");
+    }
+
+    #[test]
+    fn test_acquire_and_release_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json.lock");
+
+        let lock = acquire_lock(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            process::id().to_string()
+        );
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_held_lock_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json.lock");
+
+        let _lock = acquire_lock(&path).unwrap();
+        let err = acquire_lock(&path).unwrap_err();
+        assert!(
+            matches!(&err, Error::Locked { pid, .. } if *pid == process::id().to_string()),
+            "{err:?}"
+        );
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_stale_lock_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json.lock");
+
+        // No process can have this PID
+        fs::write(&path, i32::MAX.to_string()).unwrap();
+        let _lock = acquire_lock(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            process::id().to_string()
+        );
+
+        fs::write(&path, "garbage").unwrap();
+        assert!(lock_is_stale(&path));
+    }
+}
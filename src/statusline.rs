@@ -0,0 +1,194 @@
+use crate::config::{Threshold, Thresholds};
+use crate::pet::{Pet, PetStatus};
+use crate::term::{Paint, Style};
+
+/// What `petty line` prints when the config doesn't say
+pub const DEFAULT_FORMAT: &str = "{face} {name} H{health} M{mood}";
+
+/// `format` with each `{token}` replaced by the pet's stat, for tmux
+/// status bars and shell prompts. Known tokens are `name`, `face`,
+/// `health`, `hunger`, `cleanliness`, `mood`, `fatigue`, `coins`, `age`
+/// and `status`; anything else is left as written. `{{` and `}}` are
+/// literal braces.
+///
+/// With `paint` on, the four needs are colored by how they're doing.
+pub fn render(
+    format: &str,
+    pet: &Pet,
+    thresholds: &Thresholds,
+    ascii: bool,
+    paint: Paint,
+) -> String {
+    let mut line = String::new();
+    let mut rest = format;
+    while let Some(at) = rest.find(['{', '}']) {
+        line.push_str(&rest[..at]);
+        let (brace, after) = rest[at..].split_at(1);
+        // A doubled brace stands for itself
+        if let Some(tail) = after.strip_prefix(brace) {
+            line.push_str(brace);
+            rest = tail;
+            continue;
+        }
+        if brace == "{"
+            && let Some(end) = after.find('}')
+            && let Some(value) = field(&after[..end], pet, thresholds, ascii, paint)
+        {
+            line.push_str(&value);
+            rest = &after[end + 1..];
+            continue;
+        }
+        line.push_str(brace);
+        rest = after;
+    }
+    line.push_str(rest);
+    line
+}
+
+/// The text for one `{token}`, or `None` if it isn't one
+fn field(
+    token: &str,
+    pet: &Pet,
+    thresholds: &Thresholds,
+    ascii: bool,
+    paint: Paint,
+) -> Option<String> {
+    // Low is bad for these, high for hunger
+    let low = |value: u8, threshold: Threshold| {
+        level(
+            value,
+            value < threshold.danger,
+            value < threshold.warn,
+            paint,
+        )
+    };
+    let text = match token {
+        "name" => pet.name.clone(),
+        "face" => face(pet, ascii).to_string(),
+        "health" => low(pet.health, thresholds.health),
+        "hunger" => level(
+            pet.hunger,
+            pet.hunger > thresholds.hunger.danger,
+            pet.hunger > thresholds.hunger.warn,
+            paint,
+        ),
+        "cleanliness" => low(pet.cleanliness, thresholds.cleanliness),
+        "mood" => low(pet.mood, thresholds.mood),
+        "fatigue" => pet.fatigue.to_string(),
+        "coins" => pet.coins.to_string(),
+        "age" => pet.age.to_string(),
+        "status" => status(pet).to_string(),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// `value` in red past danger, yellow past warn and green otherwise
+fn level(value: u8, danger: bool, warn: bool, paint: Paint) -> String {
+    let style = if danger {
+        Style::Red
+    } else if warn {
+        Style::Yellow
+    } else {
+        Style::Green
+    };
+    paint.paint(&value.to_string(), style)
+}
+
+/// A face for how the pet is doing at a glance
+fn face(pet: &Pet, ascii: bool) -> &'static str {
+    let (gone, asleep, sick, happy, sad, fine) = if ascii {
+        ("x_x", "-_-", ":S", ":D", ":(", ":)")
+    } else {
+        ("💀", "😴", "🤒", "😺", "😿", "🐱")
+    };
+    if pet.health == 0 || matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired) {
+        gone
+    } else if pet.is_sleeping {
+        asleep
+    } else if pet.status == PetStatus::Sick {
+        sick
+    } else if pet.mood >= 70 {
+        happy
+    } else if pet.mood < 30 {
+        sad
+    } else {
+        fine
+    }
+}
+
+fn status(pet: &Pet) -> &'static str {
+    match (pet.health, &pet.status, pet.is_sleeping) {
+        (0, _, _) => "dead",
+        (_, PetStatus::Abandoned, _) => "away",
+        (_, PetStatus::Retired, _) => "retired",
+        (_, PetStatus::Sick, _) => "sick",
+        (_, _, true) => "asleep",
+        _ => "awake",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pet() -> Pet {
+        let mut pet = Pet::new("Petty".to_string());
+        (pet.health, pet.hunger, pet.mood, pet.coins) = (82, 10, 60, 7);
+        pet
+    }
+
+    fn plain(format: &str, pet: &Pet) -> String {
+        render(format, pet, &Thresholds::default(), false, Paint(false))
+    }
+
+    #[test]
+    fn test_format_strings() {
+        let pet = pet();
+        assert_eq!(plain(DEFAULT_FORMAT, &pet), "🐱 Petty H82 M60");
+        assert_eq!(plain("{mood}/{health} {coins}c", &pet), "60/82 7c");
+        assert_eq!(
+            plain("{name}: {status}, age {age}", &pet),
+            "Petty: awake, age 0"
+        );
+        assert_eq!(plain("", &pet), "");
+        assert_eq!(
+            render("{face}", &pet, &Thresholds::default(), true, Paint(false)),
+            ":)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_tokens_and_stray_braces_are_kept() {
+        let pet = pet();
+        assert_eq!(plain("{bogus} H{health}", &pet), "{bogus} H82");
+        assert_eq!(plain("{{health}} {health", &pet), "{health} {health");
+        assert_eq!(plain("}{}{mood}", &pet), "}{}60");
+    }
+
+    #[test]
+    fn test_color_toggling() {
+        let mut pet = pet();
+        pet.hunger = 95;
+        let thresholds = Thresholds::default();
+        let colored = render(
+            "H{health} F{hunger} {name}",
+            &pet,
+            &thresholds,
+            false,
+            Paint(true),
+        );
+        assert_eq!(colored, "H\x1b[32m82\x1b[0m F\x1b[31m95\x1b[0m Petty");
+        pet.mood = 30;
+        let warned = render("{mood}", &pet, &thresholds, false, Paint(true));
+        assert_eq!(warned, "\x1b[33m30\x1b[0m");
+        let plain = render(
+            "H{health} F{hunger}",
+            &pet,
+            &thresholds,
+            false,
+            Paint(false),
+        );
+        assert_eq!(plain, "H82 F95");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::config::Overstimulation;
+use crate::pet::Pet;
+use std::time::Instant;
+
+/// How worked up the pet is from recent care, whatever the actions were.
+/// Every interaction adds one and the total halves every
+/// `half_life_seconds` of quiet.
+#[derive(Debug, Default)]
+pub struct Stimulation {
+    intensity: f64,
+    last: Option<Instant>,
+}
+
+impl Stimulation {
+    /// Count an interaction at `now` and return the share of its benefit
+    /// the pet still gets: all of it while the intensity is at most `calm`,
+    /// less the further it goes past that, but never below `min_benefit`
+    pub fn interact(&mut self, now: Instant, rules: &Overstimulation) -> f64 {
+        if let Some(last) = self.last {
+            let quiet = now.saturating_duration_since(last).as_secs_f64();
+            self.intensity *= 0.5f64.powf(quiet / rules.half_life_seconds);
+        }
+        self.intensity += 1.0;
+        self.last = Some(now);
+        if self.intensity <= rules.calm {
+            1.0
+        } else {
+            (rules.calm / self.intensity).max(rules.min_benefit)
+        }
+    }
+}
+
+/// Scale back what an action did for `pet` to `share` of it, starting from
+/// the stats it had `before`. Only the gains shrink; costs such as the
+/// hunger from playing are paid in full.
+pub fn damp(before: &Pet, pet: &mut Pet, share: f64) {
+    let scaled = |from: u8, to: u8| {
+        let gain = f64::from(to) - f64::from(from);
+        (f64::from(from) + gain * share).round() as u8
+    };
+    if pet.health > before.health {
+        pet.health = scaled(before.health, pet.health);
+    }
+    if pet.cleanliness > before.cleanliness {
+        pet.cleanliness = scaled(before.cleanliness, pet.cleanliness);
+    }
+    if pet.mood > before.mood {
+        pet.mood = scaled(before.mood, pet.mood);
+    }
+    // Hunger goes down when the pet is cared for
+    if pet.hunger < before.hunger {
+        pet.hunger = scaled(before.hunger, pet.hunger);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rapid_care_does_less_good_than_spaced_care() {
+        let rules = Overstimulation::default();
+        let start = Instant::now();
+        let total = |gap: u64| {
+            let mut stimulation = Stimulation::default();
+            (0..10)
+                .map(|n| stimulation.interact(start + Duration::from_secs(n * gap), &rules))
+                .sum::<f64>()
+        };
+        let (rapid, spaced) = (total(1), total(5 * rules.half_life_seconds as u64));
+        assert!(rapid < spaced, "{rapid} vs {spaced}");
+        assert!(spaced > 9.9);
+        assert!(rapid >= 10.0 * rules.min_benefit);
+    }
+
+    #[test]
+    fn test_damp_only_shrinks_gains() {
+        let mut before = Pet::new("TestPet".to_string());
+        (
+            before.health,
+            before.hunger,
+            before.cleanliness,
+            before.mood,
+        ) = (50, 80, 20, 40);
+        let mut pet = before.clone();
+        // Played: mood up, hunger up too
+        (pet.mood, pet.hunger, pet.cleanliness) = (60, 90, 20);
+        damp(&before, &mut pet, 0.5);
+        assert_eq!((pet.mood, pet.hunger, pet.cleanliness), (50, 90, 20));
+
+        let mut fed = before.clone();
+        fed.hunger = 40;
+        damp(&before, &mut fed, 0.25);
+        assert_eq!(fed.hunger, 70);
+    }
+}
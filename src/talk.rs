@@ -0,0 +1,132 @@
+use crate::config::Thresholds;
+use crate::i18n::Lang;
+use crate::pet::{Need, Pet, PetStatus};
+
+/// Seconds each line stays up before the pet says the next one
+pub const ROTATE_SECONDS: u64 = 5;
+
+/// What's on the pet's mind, which picks the pool it talks from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Topic {
+    Sick,
+    Asleep,
+    Hungry,
+    Dirty,
+    Sad,
+    Happy,
+    Content,
+}
+
+impl Topic {
+    #[cfg(test)]
+    pub const ALL: [Topic; 7] = [
+        Topic::Sick,
+        Topic::Asleep,
+        Topic::Hungry,
+        Topic::Dirty,
+        Topic::Sad,
+        Topic::Happy,
+        Topic::Content,
+    ];
+
+    /// Sickness and sleep come first, then the most urgent need
+    pub fn for_pet(pet: &Pet, thresholds: &Thresholds) -> Self {
+        if pet.status == PetStatus::Sick {
+            return Topic::Sick;
+        }
+        if pet.is_sleeping {
+            return Topic::Asleep;
+        }
+        match pet.most_urgent_need(thresholds) {
+            Some(Need::Hunger) => Topic::Hungry,
+            Some(Need::Cleanliness) => Topic::Dirty,
+            Some(Need::Mood) => Topic::Sad,
+            None if pet.mood > 80 => Topic::Happy,
+            None => Topic::Content,
+        }
+    }
+}
+
+/// Every line the pet might say about `topic`
+pub fn pool(topic: Topic, lang: Lang) -> &'static [&'static str] {
+    match (topic, lang) {
+        (Topic::Sick, Lang::Zh) => &["我不太舒服...", "能陪陪我吗?", "头好晕..."],
+        (Topic::Sick, Lang::En) => &[
+            "I don't feel so good...",
+            "Stay with me?",
+            "My head is spinning...",
+        ],
+        (Topic::Asleep, Lang::Zh) => &["Zzz...", "呼噜噜...", "(梦到了小鱼干)"],
+        (Topic::Asleep, Lang::En) => &["Zzz...", "*snore*", "(dreaming of snacks)"],
+        (Topic::Hungry, Lang::Zh) => &["好想吃点东西...", "肚子咕咕叫了", "饭呢?"],
+        (Topic::Hungry, Lang::En) => &[
+            "I could really go for a snack...",
+            "My tummy is rumbling",
+            "Is it dinner time?",
+        ],
+        (Topic::Dirty, Lang::Zh) => &["身上好痒...", "我需要洗个澡", "有点臭臭的"],
+        (Topic::Dirty, Lang::En) => &[
+            "I feel so itchy...",
+            "I need a bath",
+            "Something smells funny",
+        ],
+        (Topic::Sad, Lang::Zh) => &["好无聊啊...", "陪我玩一会儿吧", "没人理我..."],
+        (Topic::Sad, Lang::En) => &[
+            "I'm so bored...",
+            "Play with me?",
+            "Nobody pays attention to me...",
+        ],
+        (Topic::Happy, Lang::Zh) => &["今天真开心!", "最喜欢你了!", "嘿嘿~"],
+        (Topic::Happy, Lang::En) => &["Best day ever!", "I love you!", "Hehe~"],
+        (Topic::Content, Lang::Zh) => &["这样挺好的。", "今天天气不错。", "嗯~"],
+        (Topic::Content, Lang::En) => &["This is nice.", "What a lovely day.", "Hmm~"],
+    }
+}
+
+/// What the pet says at `tick`; the line changes every `ROTATE_SECONDS`
+pub fn chatter(pet: &Pet, thresholds: &Thresholds, tick: u64, lang: Lang) -> &'static str {
+    let lines = pool(Topic::for_pet(pet, thresholds), lang);
+    lines[(tick / ROTATE_SECONDS) as usize % lines.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_follows_state() {
+        let thresholds = Thresholds::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.mood = 90;
+        assert_eq!(Topic::for_pet(&pet, &thresholds), Topic::Happy);
+        pet.mood = 60;
+        assert_eq!(Topic::for_pet(&pet, &thresholds), Topic::Content);
+        pet.mood = 30;
+        assert_eq!(Topic::for_pet(&pet, &thresholds), Topic::Sad);
+        pet.hunger = 90;
+        assert_eq!(Topic::for_pet(&pet, &thresholds), Topic::Hungry);
+        pet.status = PetStatus::Sick;
+        assert_eq!(Topic::for_pet(&pet, &thresholds), Topic::Sick);
+    }
+
+    #[test]
+    fn test_chatter_rotates_through_the_pool() {
+        let thresholds = Thresholds::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 90;
+        let hungry = pool(Topic::Hungry, Lang::En);
+
+        assert_eq!(chatter(&pet, &thresholds, 0, Lang::En), hungry[0]);
+        assert_eq!(
+            chatter(&pet, &thresholds, ROTATE_SECONDS - 1, Lang::En),
+            hungry[0]
+        );
+        assert_eq!(
+            chatter(&pet, &thresholds, ROTATE_SECONDS, Lang::En),
+            hungry[1]
+        );
+        let wrapped = ROTATE_SECONDS * hungry.len() as u64;
+        assert_eq!(chatter(&pet, &thresholds, wrapped, Lang::En), hungry[0]);
+        assert!(pool(Topic::Hungry, Lang::Zh).contains(&chatter(&pet, &thresholds, 0, Lang::Zh)));
+    }
+}
@@ -0,0 +1,180 @@
+use ratatui::symbols::border;
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+/// Stick to plain ASCII for art, borders and labels, for terminals or
+/// locales that would turn emoji, box drawing and Chinese into mojibake
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AsciiMode(pub bool);
+
+impl AsciiMode {
+    /// `forced` comes from `--ascii`; otherwise look at `TERM` and the locale
+    pub fn detect(forced: bool) -> Self {
+        let term = env::var("TERM").ok();
+        // The first non-empty one wins, like setlocale does
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()));
+        AsciiMode(forced || !supports_unicode(term.as_deref(), locale.as_deref()))
+    }
+
+    pub fn is_on(self) -> bool {
+        self.0
+    }
+}
+
+/// Whether plain-text output (diary, achievements and so on) may use ANSI
+/// colors, from `--color`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when writing straight to a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Whether to color output going to a terminal (`is_tty`) or elsewhere
+    pub fn use_color(self, is_tty: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_tty,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    pub fn for_stdout(self) -> Paint {
+        Paint(self.use_color(io::stdout().is_terminal()))
+    }
+}
+
+/// An ANSI text style
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    Bold,
+    Dim,
+    Green,
+    Yellow,
+    Red,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "1",
+            Style::Dim => "2",
+            Style::Green => "32",
+            Style::Yellow => "33",
+            Style::Red => "31",
+        }
+    }
+}
+
+/// Styles text with ANSI escapes, or leaves it alone when color is off
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Paint(pub bool);
+
+impl Paint {
+    pub fn paint(self, text: &str, style: Style) -> String {
+        if self.0 {
+            format!("\x1b[{}m{text}\x1b[0m", style.code())
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Whether a terminal and locale can be trusted with UTF-8. No locale at
+/// all is common in containers with a perfectly capable terminal, so only
+/// an explicit non-UTF-8 locale counts against it.
+fn supports_unicode(term: Option<&str>, locale: Option<&str>) -> bool {
+    if matches!(term, Some("dumb" | "linux" | "vt100" | "vt220")) {
+        return false;
+    }
+    locale.is_none_or(|locale| {
+        let locale = locale.to_ascii_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    })
+}
+
+/// Block borders drawn with `+`, `-` and `|`
+pub const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Save the window title on the terminal's title stack so `PopTitle` can
+/// put it back. Terminals without a title stack ignore it.
+pub struct PushTitle;
+
+impl crossterm::Command for PushTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str("\x1b[22;0t")
+    }
+}
+
+/// Restore the title saved by `PushTitle`
+pub struct PopTitle;
+
+impl crossterm::Command for PopTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str("\x1b[23;0t")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_support_detection() {
+        assert!(supports_unicode(
+            Some("xterm-256color"),
+            Some("en_US.UTF-8")
+        ));
+        assert!(supports_unicode(Some("xterm"), Some("zh_CN.utf8")));
+        assert!(supports_unicode(Some("xterm"), None));
+        assert!(!supports_unicode(Some("xterm"), Some("C")));
+        assert!(!supports_unicode(Some("xterm"), Some("en_US.ISO-8859-1")));
+        assert!(!supports_unicode(Some("linux"), Some("en_US.UTF-8")));
+        assert!(!supports_unicode(Some("dumb"), None));
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert_eq!(ColorChoice::parse("never"), Some(ColorChoice::Never));
+        assert_eq!(ColorChoice::parse("sometimes"), None);
+        // Piped output stays plain unless color is forced
+        assert!(!ColorChoice::Auto.use_color(false));
+        assert!(ColorChoice::Auto.use_color(true));
+        assert!(ColorChoice::Always.use_color(false));
+        assert!(!ColorChoice::Never.use_color(true));
+
+        let never = Paint(ColorChoice::Never.use_color(true));
+        assert_eq!(never.paint("[x]", Style::Green), "[x]");
+        let always = Paint(ColorChoice::Always.use_color(false));
+        assert_eq!(always.paint("[x]", Style::Green), "\x1b[32m[x]\x1b[0m");
+    }
+
+    #[test]
+    fn test_forced_ascii_mode() {
+        assert!(AsciiMode::detect(true).is_on());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::term::AsciiMode;
+use ratatui::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Colors used by the renderer. Any element missing from a custom theme in
+/// `config.toml` falls back to the default theme's color.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Theme {
+    pub border: Color,
+    pub title: Color,
+    pub text: Color,
+    pub art: Color,
+    pub good: Color,
+    pub warn: Color,
+    pub danger: Color,
+    pub hint: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            border: Color::Reset,
+            title: Color::Reset,
+            text: Color::Reset,
+            art: Color::Reset,
+            good: Color::Green,
+            warn: Color::Yellow,
+            danger: Color::Red,
+            hint: Color::Reset,
+        }
+    }
+}
+
+impl Theme {
+    pub const BUILTIN_NAMES: [&'static str; 4] = ["default", "ocean", "forest", "high-contrast"];
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "ocean" => Some(Self {
+                border: Color::Blue,
+                title: Color::Cyan,
+                text: Color::White,
+                art: Color::LightCyan,
+                good: Color::LightBlue,
+                warn: Color::Yellow,
+                danger: Color::LightRed,
+                hint: Color::Cyan,
+            }),
+            "forest" => Some(Self {
+                border: Color::Green,
+                title: Color::LightGreen,
+                text: Color::White,
+                art: Color::LightYellow,
+                good: Color::LightGreen,
+                warn: Color::Yellow,
+                danger: Color::Red,
+                hint: Color::Green,
+            }),
+            // Bright colors only, for dark terminals and low vision
+            "high-contrast" => Some(Self {
+                border: Color::White,
+                title: Color::White,
+                text: Color::White,
+                art: Color::White,
+                good: Color::LightGreen,
+                warn: Color::LightYellow,
+                danger: Color::LightRed,
+                hint: Color::White,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Every themeable element paired with its current color.
+    pub fn elements(&self) -> [(&'static str, Color); 8] {
+        [
+            ("border", self.border),
+            ("title", self.title),
+            ("text", self.text),
+            ("art", self.art),
+            ("good", self.good),
+            ("warn", self.warn),
+            ("danger", self.danger),
+            ("hint", self.hint),
+        ]
+    }
+
+    /// Lines for the swatch panel shown by `petty theme-preview`
+    pub fn swatch_lines(&self, ascii: AsciiMode) -> Vec<Line<'static>> {
+        let block = if ascii.is_on() {
+            "  ###  "
+        } else {
+            "  ███  "
+        };
+        self.elements()
+            .into_iter()
+            .map(|(name, color)| {
+                Line::from(vec![
+                    Span::styled(block, Style::default().fg(color)),
+                    Span::styled(format!("{name:<8}"), Style::default().fg(self.text)),
+                    Span::raw(color.to_string()),
+                ])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes_exist() {
+        for name in Theme::BUILTIN_NAMES {
+            assert!(Theme::builtin(name).is_some());
+        }
+        assert!(Theme::builtin("nope").is_none());
+    }
+
+    #[test]
+    fn test_high_contrast_theme_colors() {
+        let theme = Theme::builtin("high-contrast").unwrap();
+        assert_eq!(theme.text, Color::White);
+        assert_eq!(theme.border, Color::White);
+        assert_eq!(theme.danger, Color::LightRed);
+        assert_eq!(theme.good, Color::LightGreen);
+        assert!(
+            theme
+                .elements()
+                .iter()
+                .all(|(_, color)| *color != Color::Reset)
+        );
+    }
+
+    #[test]
+    fn test_swatch_lines_include_every_element() {
+        let theme = Theme::builtin("ocean").unwrap();
+        let lines = theme.swatch_lines(AsciiMode::default());
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(lines.len(), theme.elements().len());
+        for (name, _) in theme.elements() {
+            assert!(text.iter().any(|l| l.contains(name)), "missing {name}");
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::i18n::Lang;
+use crate::pet::{Action, Pet, SPA_COST};
+
+/// The actions the tutorial teaches, in order
+pub const STEPS: [Action; 9] = [
+    // Before the meal, which leaves it too full for a snack
+    Action::Snack,
+    Action::Feed,
+    Action::Wash,
+    Action::Play,
+    Action::Train,
+    Action::Sleep,
+    Action::Wake,
+    Action::Spa,
+    Action::Medicine,
+];
+
+/// Where the owner is in the tutorial. It only moves on once the action
+/// it asks for has been done.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tutorial {
+    /// Steps done so far, an index into `STEPS`
+    done: usize,
+}
+
+impl Tutorial {
+    /// The action the tutorial is waiting for, or `None` once it's over
+    pub fn expected(&self) -> Option<Action> {
+        STEPS.get(self.done).copied()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.expected().is_none()
+    }
+
+    /// Count a care action that just happened. Returns whether it was the
+    /// one asked for, moving the tutorial on; anything else is ignored.
+    pub fn observe(&mut self, action: Action) -> bool {
+        if self.expected() != Some(action) {
+            return false;
+        }
+        self.done += 1;
+        true
+    }
+
+    /// What to do next, like "Tutorial 1/9: press f to feed your pet"
+    pub fn prompt(&self, lang: Lang) -> String {
+        let Some(action) = self.expected() else {
+            return match lang {
+                Lang::Zh => "教程完成! 你已经学会了所有操作, 按 q 退出。".to_string(),
+                Lang::En => {
+                    "Tutorial done! You know every action now; press q to quit.".to_string()
+                }
+            };
+        };
+        let what = match (action, lang) {
+            (Action::Feed, Lang::Zh) => "按 f 喂食, 让它不再饿肚子",
+            (Action::Feed, Lang::En) => "press f to feed your pet",
+            (Action::Wash, Lang::Zh) => "按 w 给它洗澡",
+            (Action::Wash, Lang::En) => "press w to give it a wash",
+            (Action::Play, Lang::Zh) => "按 p 陪它玩, 心情会变好",
+            (Action::Play, Lang::En) => "press p to play and cheer it up",
+            (Action::Train, Lang::Zh) => "按 t 训练它的技能",
+            (Action::Train, Lang::En) => "press t to train its skills",
+            (Action::Sleep, Lang::Zh) => "按 s 让它睡觉, 恢复健康和精力",
+            (Action::Sleep, Lang::En) => "press s to put it to bed to rest",
+            (Action::Wake, Lang::Zh) => "按 u 叫醒它",
+            (Action::Wake, Lang::En) => "press u to wake it up",
+            (Action::Spa, Lang::Zh) => "照顾会赚金币, 按 a 花金币带它去 SPA",
+            (Action::Spa, Lang::En) => "care earns coins; press a to spend some on a spa day",
+            (Action::Medicine, Lang::Zh) => "按 m 喂药恢复健康, 不过每天只有几剂",
+            (Action::Medicine, Lang::En) => {
+                "press m for medicine; there are only a few doses a day"
+            }
+            (Action::Snack, Lang::Zh) => "按 n 给它吃点零食, 先垫垫肚子",
+            (Action::Snack, Lang::En) => "press n for a quick snack to take the edge off",
+        };
+        let step = self.done + 1;
+        match lang {
+            Lang::Zh => format!("教程 {step}/{}: {what}", STEPS.len()),
+            Lang::En => format!("Tutorial {step}/{}: {what}", STEPS.len()),
+        }
+    }
+}
+
+/// The throwaway pet the tutorial is played with, a little hungry, grubby
+/// and poorly so feeding, washing and medicine do something, with the
+/// coins for its spa day
+pub fn practice_pet() -> Pet {
+    let mut pet = Pet::new("Tutor".to_string());
+    (pet.health, pet.hunger, pet.cleanliness, pet.mood) = (60, 50, 50, 50);
+    pet.coins = SPA_COST;
+    pet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use chrono::Utc;
+
+    #[test]
+    fn test_only_the_expected_action_advances() {
+        let mut tutorial = Tutorial::default();
+        assert_eq!(tutorial.expected(), Some(Action::Snack));
+        assert!(!tutorial.observe(Action::Play));
+        assert!(!tutorial.observe(Action::Feed));
+        assert_eq!(tutorial.expected(), Some(Action::Snack));
+        assert!(tutorial.prompt(Lang::En).starts_with("Tutorial 1/9"));
+
+        assert!(tutorial.observe(Action::Snack));
+        assert_eq!(tutorial.expected(), Some(Action::Feed));
+        // Doing it again doesn't skip a step
+        assert!(!tutorial.observe(Action::Snack));
+        assert_eq!(tutorial.expected(), Some(Action::Feed));
+    }
+
+    #[test]
+    fn test_practice_pet_can_finish_the_tutorial() {
+        let (config, mut pet) = (Config::default(), practice_pet());
+        let mut tutorial = Tutorial::default();
+        for action in STEPS {
+            assert!(pet.can_act(action), "{action:?}");
+            assert!(!pet.would_waste(action), "{action:?}");
+            // Nor is it turned away, e.g. for still digesting
+            let recorded = pet.recorded;
+            pet.act(action, &config, Utc::now());
+            assert!(pet.recorded > recorded, "{action:?}");
+            assert!(tutorial.observe(action));
+        }
+        assert!(tutorial.is_done());
+        assert!(!tutorial.observe(Action::Feed));
+        assert!(tutorial.prompt(Lang::En).starts_with("Tutorial done!"));
+    }
+}
@@ -1,22 +1,100 @@
-use crate::pet::{Pet, PetStatus};
+use crate::achievements::Achievement;
+use crate::anim::{Reaction, ReactionQueue};
+use crate::away::AwayDiff;
+use crate::banner;
+use crate::bond;
+use crate::calendar;
+use crate::config::{Config, OnAbandon, Thresholds, TimeMode};
+use crate::cooldown::Cooldowns;
+use crate::display::{Stat, format_stat};
+use crate::dream::Dream;
+use crate::emote::{self, Emote};
+use crate::focus::{self, Phase, Pomodoro};
+use crate::frame::FrameLimiter;
+use crate::gallery::gallery_lines;
+use crate::host::{self, Sampler};
+use crate::i18n::{Lang, format_duration};
+use crate::notify::{self, NOTIFY_COOLDOWN, Notifier};
+use crate::pet::{
+    ABANDON_AFTER, Action, CRITICAL_HEALTH, LifeStage, Need, Pet, PetStatus, SPA_COST, TickEvent,
+    TickReport, WISTFUL, abandonment_remaining,
+};
+use crate::radar::{RADIUS, SPOKES, radar_points, spoke_point, spoke_values};
+use crate::replay::{self, Moment};
+use crate::skills::{Skill, skill_lines};
+use crate::sound::SoundPlayer;
+use crate::state::{StateFile, save_state_to};
+use crate::stimulation::{self, Stimulation};
+use crate::talk::chatter;
+use crate::term::{ASCII_BORDER, AsciiMode, PopTitle, PushTitle};
+use crate::theme::Theme;
+use crate::tutorial::Tutorial;
 use anyhow::Result;
-use crossterm::ExecutableCommand;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers,
+};
 use crossterm::terminal::{
-    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
 };
-use futures_util::StreamExt as FuturesStreamExt;
+use crossterm::{ExecutableCommand, QueueableCommand};
+use futures_util::{Stream, StreamExt};
 use ratatui::prelude::*;
+use ratatui::widgets::canvas::{self, Canvas};
 use ratatui::widgets::*;
-use std::io::stdout;
+use ratatui::{TerminalOptions, Viewport};
+use std::borrow::Cow;
+use std::io::{self, Write, stdout};
 use std::process;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::signal::unix::{SignalKind, signal};
 use tokio::time::interval;
 
-fn suspend_and_restore() {
+/// Rows the UI takes up under the prompt with `--inline`
+const INLINE_HEIGHT: u16 = 24;
+
+/// Switch to the alternate screen, unless drawing inline, and capture the
+/// mouse if asked to
+fn enter_screen(out: &mut impl Write, inline: bool, mouse: bool) -> io::Result<()> {
+    if !inline {
+        out.queue(EnterAlternateScreen)?;
+    }
+    if mouse {
+        out.queue(EnableMouseCapture)?;
+    }
+    out.flush()
+}
+
+/// Undo `enter_screen`
+fn leave_screen(out: &mut impl Write, inline: bool, mouse: bool) -> io::Result<()> {
+    if mouse {
+        out.queue(DisableMouseCapture)?;
+    }
+    if !inline {
+        out.queue(LeaveAlternateScreen)?;
+    }
+    out.flush()
+}
+
+/// A terminal over the whole screen, or over `INLINE_HEIGHT` rows below
+/// the cursor that stay in the scrollback afterwards
+fn new_terminal(inline: bool) -> io::Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
+    let viewport = if inline {
+        Viewport::Inline(INLINE_HEIGHT)
+    } else {
+        Viewport::Fullscreen
+    };
+    Terminal::with_options(
+        CrosstermBackend::new(stdout()),
+        TerminalOptions { viewport },
+    )
+}
+
+fn suspend_and_restore(inline: bool, mouse: bool) {
     // Exit alternate screen and raw mode
     let mut stdout = stdout();
-    stdout.execute(LeaveAlternateScreen).ok();
+    leave_screen(&mut stdout, inline, mouse).ok();
     disable_raw_mode().ok();
 
     unsafe {
@@ -28,51 +106,77 @@ fn suspend_and_restore() {
 
     // When user uses `fg` to resume, re-enter raw mode and alternate screen
     enable_raw_mode().ok();
-    stdout.execute(EnterAlternateScreen).ok();
+    enter_screen(&mut stdout, inline, mouse).ok();
+}
+
+fn show_help_screen(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    ascii: AsciiMode,
+) -> Result<()> {
+    show_page(
+        terminal,
+        help_lines(ascii).into_iter().map(String::from).collect(),
+    )
+}
+
+/// Show the pet's life-stage snapshots until a key is pressed
+fn show_gallery_screen(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    pet: &Pet,
+    lang: Lang,
+    ascii: AsciiMode,
+) -> Result<()> {
+    let (lang, prompt) = if ascii.is_on() {
+        (Lang::En, "Press any key to go back")
+    } else {
+        (lang, "按任意键返回游戏")
+    };
+    let mut lines = vec![String::new()];
+    lines.extend(gallery_lines(&pet.gallery, pet.birth, lang));
+    lines.extend([String::new(), prompt.to_string()]);
+    show_page(terminal, lines)
+}
+
+/// Show the pet's skill levels until a key is pressed
+fn show_skills_screen(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    pet: &Pet,
+    lang: Lang,
+    ascii: AsciiMode,
+) -> Result<()> {
+    let (lang, prompt) = if ascii.is_on() {
+        (Lang::En, "Press any key to go back")
+    } else {
+        (lang, "按任意键返回游戏")
+    };
+    let mut lines = vec![String::new()];
+    lines.extend(skill_lines(&pet.skills, lang));
+    lines.extend([String::new(), prompt.to_string()]);
+    show_page(terminal, lines)
 }
 
-fn show_help_screen(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Result<()> {
+/// Full-screen text whose last line is an italic "press any key" prompt
+fn show_page(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    lines: Vec<String>,
+) -> Result<()> {
     loop {
         terminal.draw(|f| {
-            let help_text = vec![
-                Line::from(""),
-                Line::from("Petty - 终端电子宠物 帮助信息"),
-                Line::from(""),
-                Line::from("基本操作:"),
-                Line::from("  f - 喂食 (降低饥饿度, 稍微增加健康值)"),
-                Line::from("  w - 洗澡 (提高清洁度到最大值)"),
-                Line::from("  p - 玩耍 (提高心情, 但会增加饥饿度)"),
-                Line::from("  t - 训练 (提高健康值和心情, 但会大幅增加饥饿度)"),
-                Line::from("  s - 睡觉 (切换睡眠状态, 睡眠时会恢复健康值)"),
-                Line::from(""),
-                Line::from("其他操作:"),
-                Line::from("  ? 或 help - 显示此帮助信息"),
-                Line::from("  debug - 进入开发者模式"),
-                Line::from("  Ctrl+Z - 挂起程序到后台"),
-                Line::from("  fg - 恢复挂起的程序"),
-                Line::from("  q 或 Ctrl+C - 退出程序"),
-                Line::from(""),
-                Line::from("状态说明:"),
-                Line::from("  年龄: 宠物的年龄, 每5分钟增长1岁"),
-                Line::from("  健康: 宠物的健康状况, 受其他状态影响"),
-                Line::from("  饥饿: 饥饿度会随时间增加, 过高会影响健康"),
-                Line::from("  清洁: 清洁度会随时间减少, 过低会影响健康"),
-                Line::from("  心情: 心情会随时间减少, 过低会影响健康"),
-                Line::from(""),
-                Line::from("提示:"),
-                Line::from("  - 宠物需要定期照顾以保持健康"),
-                Line::from("  - 老年宠物需要更多的关注和照顾"),
-                Line::from("  - 长时间不照顾宠物会导致宠物生病甚至死亡"),
-                Line::from(""),
-                Line::from(Span::styled(
-                    "按任意键返回游戏",
-                    Style::default().add_modifier(Modifier::ITALIC),
-                )),
-            ];
+            let (prompt, body) = lines
+                .split_last()
+                .map(|(prompt, body)| (prompt.as_str(), body))
+                .unwrap_or_default();
+            let mut help_text: Vec<Line> =
+                body.iter().map(|line| Line::from(line.as_str())).collect();
+            help_text.push(Line::from(Span::styled(
+                prompt,
+                Style::default().add_modifier(Modifier::ITALIC),
+            )));
 
             let paragraph = Paragraph::new(help_text)
                 .alignment(Alignment::Left)
-                .wrap(Wrap { trim: true });
+                // Keep the indentation of key lists and art
+                .wrap(Wrap { trim: false });
             f.render_widget(paragraph, f.area());
         })?;
 
@@ -85,352 +189,3316 @@ fn show_help_screen(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>)
     Ok(())
 }
 
-pub async fn run_ui(pet: &mut Pet) -> Result<()> {
-    // setup terminal
-    crossterm::terminal::enable_raw_mode()?;
+/// The help screen, ending with the "press any key" prompt
+fn help_lines(ascii: AsciiMode) -> Vec<&'static str> {
+    if ascii.is_on() {
+        return vec![
+            "",
+            "Petty - terminal pet help",
+            "",
+            "Basics:",
+            "  f - feed a full meal (lowers hunger, a little health)",
+            "  n - snack (lowers hunger a little, no digesting)",
+            "  w - wash (cleanliness back to full)",
+            "  p - play (raises mood, but adds hunger)",
+            "  t - train (raises health and mood, but adds a lot of hunger)",
+            "  s - sleep (heals while asleep, only 'u' works until it wakes)",
+            "  u - wake the pet up",
+            "  a - spa day (wash, pampering and some health for 10 coins)",
+            "  m - medicine (health back, a few doses a day)",
+            "",
+            "Other keys:",
+            "  ? or help - show this help",
+            "  g - growth photo gallery",
+            "  k - skill levels (training raises them)",
+            "  r - show or hide the radar chart",
+            "  debug - enter developer mode",
+            "  Ctrl+Z - suspend to the background",
+            "  fg - resume a suspended petty",
+            "  q or Ctrl+C - quit",
+            "",
+            "Stats:",
+            "  Age: goes up by 1 every 5 minutes",
+            "  Health: affected by every other stat",
+            "  Hunger: rises over time, too high hurts health",
+            "  Cleanliness: drops over time, too low hurts health",
+            "  Mood: drops over time, too low hurts health",
+            "",
+            "Tips:",
+            "  - Look after your pet regularly to keep it healthy",
+            "  - Elderly pets need more attention",
+            "  - A neglected pet gets sick and may even die",
+            "",
+            "Press any key to go back",
+        ];
+    }
+    vec![
+        "",
+        "Petty - 终端电子宠物 帮助信息",
+        "",
+        "基本操作:",
+        "  f - 喂食正餐 (降低饥饿度, 稍微增加健康值)",
+        "  n - 零食 (稍微降低饥饿度, 不用消化)",
+        "  w - 洗澡 (提高清洁度到最大值)",
+        "  p - 玩耍 (提高心情, 但会增加饥饿度)",
+        "  t - 训练 (提高健康值和心情, 但会大幅增加饥饿度)",
+        "  s - 睡觉 (睡眠时会恢复健康值, 醒来前只能按 'u')",
+        "  u - 叫醒宠物",
+        "  a - SPA (洗澡、按摩并恢复少量健康, 花费 10 金币)",
+        "  m - 喂药 (恢复健康, 每天只有几剂)",
+        "",
+        "其他操作:",
+        "  ? 或 help - 显示此帮助信息",
+        "  g - 成长相册",
+        "  k - 技能等级 (训练可以提升)",
+        "  r - 显示/隐藏雷达图",
+        "  debug - 进入开发者模式",
+        "  Ctrl+Z - 挂起程序到后台",
+        "  fg - 恢复挂起的程序",
+        "  q 或 Ctrl+C - 退出程序",
+        "",
+        "状态说明:",
+        "  年龄: 宠物的年龄, 每5分钟增长1岁",
+        "  健康: 宠物的健康状况, 受其他状态影响",
+        "  饥饿: 饥饿度会随时间增加, 过高会影响健康",
+        "  清洁: 清洁度会随时间减少, 过低会影响健康",
+        "  心情: 心情会随时间减少, 过低会影响健康",
+        "",
+        "提示:",
+        "  - 宠物需要定期照顾以保持健康",
+        "  - 老年宠物需要更多的关注和照顾",
+        "  - 长时间不照顾宠物会导致宠物生病甚至死亡",
+        "",
+        "按任意键返回游戏",
+    ]
+}
+
+/// Show the swatch panel and a sample stat card for `theme` until a key is pressed
+pub fn show_theme_preview(name: &str, theme: &Theme, ascii: AsciiMode) -> Result<()> {
+    enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
-    let mut ticker = interval(Duration::from_secs(1));
-    let mut seconds_elapsed = 0;
-    let mut event_stream = futures_util::StreamExt::fuse(event::EventStream::new());
-    let mut input_buffer = String::new();
+    let mut sample = Pet::new("Petty".to_string());
+    sample.hunger = 65;
+    sample.cleanliness = 15;
 
-    loop {
-        terminal.draw(|f| ui(f, pet))?;
+    let result = (|| -> Result<()> {
+        loop {
+            terminal.draw(|f| {
+                let layout = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .margin(1)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(f.area());
 
-        if pet.status == PetStatus::Abandoned {
-            // If abandoned, only allow quitting
-            if let Some(Ok(Event::Key(key))) = event_stream.next().await
-                && (key.code == KeyCode::Char('q')
-                    || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL))
+                let mut swatches = theme.swatch_lines(ascii);
+                swatches.push(Line::from(""));
+                swatches.push(Line::from(Span::styled(
+                    if ascii.is_on() {
+                        "Press any key to exit"
+                    } else {
+                        "按任意键退出"
+                    },
+                    Style::default()
+                        .fg(theme.hint)
+                        .add_modifier(Modifier::ITALIC),
+                )));
+                let swatch_view = themed_block(format!("Theme: {name}"), theme, ascii);
+                f.render_widget(Paragraph::new(swatches).block(swatch_view), layout[0]);
+
+                let stats_view = themed_block("Stats".to_string(), theme, ascii);
+                f.render_widget(
+                    Paragraph::new(stats_lines(&sample, &Config::default(), theme, ascii))
+                        .block(stats_view),
+                    layout[1],
+                );
+            })?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
             {
-                break;
+                return Ok(());
             }
-            continue;
         }
+    })();
 
-        tokio::select! {
-            _ = ticker.tick() => {
-                seconds_elapsed += 1;
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+    result
+}
 
-                if pet.debug_mode {
-                    continue; // Pause state changes in debug mode
-                }
+/// How many ticks a notice replaces the hint bar
+const NOTICE_TTL: u64 = 3;
+/// The "while away" summary gets longer to be read
+const AWAY_NOTICE_TTL: u64 = 10;
 
-                // Age the pet every 5 minutes (300 seconds)
-                if seconds_elapsed > 0 && seconds_elapsed % 300 == 0 {
-                    pet.age = pet.age.saturating_add(1);
-                }
+/// `name` as it goes into a message. Control characters, like a newline
+/// or the escape that starts a terminal sequence, become spaces so a
+/// name can't break the layout or reach the terminal; braces and the
+/// rest are text like any other.
+fn spoken(name: &str) -> Cow<'_, str> {
+    if name.chars().any(char::is_control) {
+        Cow::Owned(
+            name.chars()
+                .map(|c| if c.is_control() { ' ' } else { c })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(name)
+    }
+}
 
-                if pet.is_sleeping {
-                    pet.health = pet.health.saturating_add(1);
-                    // Elderly pets heal slower
-                    if pet.life_stage() == "elderly" {
-                        pet.health = pet.health.saturating_sub(1);
-                    }
-                } else {
-                    if pet.mood > 0 {
-                        pet.mood = pet.mood.saturating_sub(2); // Mood drops faster
-                    }
-                    if seconds_elapsed % 3 == 0 { // Status changes every 3 seconds
-                        pet.hunger = pet.hunger.saturating_add(2);
-                        pet.cleanliness = pet.cleanliness.saturating_sub(3);
-
-                        // Health decreases if stats are poor
-                        // More nuanced health decline based on severity
-                        if pet.hunger > 90 {
-                            pet.health = pet.health.saturating_sub(3);
-                        } else if pet.hunger > 80 {
-                            pet.health = pet.health.saturating_sub(2);
-                        } else if pet.hunger > 70 {
-                            pet.health = pet.health.saturating_sub(1);
-                        }
-
-                        if pet.cleanliness < 10 {
-                            pet.health = pet.health.saturating_sub(3);
-                        } else if pet.cleanliness < 20 {
-                            pet.health = pet.health.saturating_sub(2);
-                        } else if pet.cleanliness < 30 {
-                            pet.health = pet.health.saturating_sub(1);
-                        }
-
-                        if pet.mood < 10 {
-                            pet.health = pet.health.saturating_sub(3);
-                        } else if pet.mood < 20 {
-                            pet.health = pet.health.saturating_sub(2);
-                        } else if pet.mood < 30 {
-                            pet.health = pet.health.saturating_sub(1);
-                        }
-
-                        // Age affects health decline - older pets decline faster
-                        if pet.age > 50 {
-                            // Elderly pet - health declines faster
-                            if pet.hunger > 60 || pet.cleanliness < 40 || pet.mood < 40 {
-                                pet.health = pet.health.saturating_sub(1);
-                            }
-                        } else if pet.age > 20 {
-                            // Adult pet - normal health decline
-                            // No additional effect
-                        }
-
-                        // Check for sickness when health is low
-                        if pet.health < 20 && pet.status == PetStatus::Alive {
-                            pet.status = PetStatus::Sick;
-                        }
-
-                        // Check for death when health is extremely low
-                        if pet.health == 0 {
-                            // Pet dies - we'll handle this in the UI
-                        }
-                    }
-                }
-            },
+/// The window title, like `Petty ❤ 82`, with how the pet is doing
+fn title_text(pet: &Pet, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let english = lang == Lang::En;
+    let heart = if ascii.is_on() { "<3" } else { "❤" };
+    let gone = match (&pet.status, lang) {
+        (PetStatus::Abandoned, Lang::En) => Some("ran away"),
+        (PetStatus::Abandoned, Lang::Zh) => Some("离家出走"),
+        (PetStatus::Retired, Lang::En) => Some("retired"),
+        (PetStatus::Retired, Lang::Zh) => Some("已退休"),
+        _ if pet.health > 0 => None,
+        (_, Lang::En) => Some("passed away"),
+        (_, Lang::Zh) => Some("已离开"),
+    };
+    if let Some(gone) = gone {
+        return format!("{} ({gone})", spoken(&pet.name));
+    }
+    let mut title = format!("{} {heart} {}", spoken(&pet.name), pet.health);
+    if pet.status == PetStatus::Sick {
+        title.push_str(if english { " (sick)" } else { " (生病)" });
+    }
+    if pet.hiding {
+        title.push_str(if english {
+            " (hiding)"
+        } else {
+            " (躲起来了)"
+        });
+    }
+    if pet.is_sleeping {
+        title.push_str(" Zzz");
+    }
+    title
+}
 
-            event = event_stream.select_next_some() => {
-                if let Ok(Event::Key(key)) = event
-                    && key.kind == KeyEventKind::Press {
-                        // Always allow exit
-                        if key.code == KeyCode::Char('q') || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL) {
-                            break;
-                        }
-
-                        // Handle Ctrl+Z
-                        if key.code == KeyCode::Char('z') && key.modifiers == KeyModifiers::CONTROL {
-                            suspend_and_restore();
-                            // Recreate terminal after resume
-                            terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-                            continue;
-                        }
-
-                        if pet.debug_mode {
-                            match key.code {
-                                KeyCode::Char('h') => pet.hunger = pet.hunger.saturating_add(10),
-                                KeyCode::Char('j') => pet.hunger = pet.hunger.saturating_sub(10),
-                                KeyCode::Char('m') => pet.mood = pet.mood.saturating_add(10),
-                                KeyCode::Char('n') => pet.mood = pet.mood.saturating_sub(10),
-                                KeyCode::Char('c') => pet.cleanliness = pet.cleanliness.saturating_add(10),
-                                KeyCode::Char('v') => pet.cleanliness = pet.cleanliness.saturating_sub(10),
-                                KeyCode::Esc => {
-                                    pet.debug_mode = false;
-                                    input_buffer.clear();
-                                }
-                                _ => {}
-                            }
-                        } else {
-                            if let KeyCode::Char(c) = key.code {
-                                input_buffer.push(c);
-                                if input_buffer.ends_with("debug") {
-                                    pet.debug_mode = true;
-                                    input_buffer.clear();
-                                } else if input_buffer.ends_with("?") || input_buffer.ends_with("help") {
-                                    // Show help screen
-                                    show_help_screen(&mut terminal)?;
-                                    input_buffer.clear();
-                                    continue;
-                                }
-                            } else {
-                                input_buffer.clear();
-                            }
-
-                            if pet.is_sleeping && key.code != KeyCode::Char('s') {
-                                continue;
-                            }
-                            match key.code {
-                                KeyCode::Char('f') => {
-                                    pet.feed();
-                                    // Elderly pets get less benefit from feeding
-                                    if pet.life_stage() == "elderly" {
-                                        pet.health = pet.health.saturating_sub(2);
-                                    }
-                                },
-                                KeyCode::Char('w') => {
-                                    pet.wash();
-                                    // Elderly pets get stressed from washing
-                                    if pet.life_stage() == "elderly" {
-                                        pet.mood = pet.mood.saturating_sub(5);
-                                    }
-                                },
-                                KeyCode::Char('p') => {
-                                    pet.play();
-                                    // Elderly pets get tired more easily
-                                    if pet.life_stage() == "elderly" {
-                                        pet.health = pet.health.saturating_sub(3);
-                                    }
-                                },
-                                KeyCode::Char('t') => {
-                                    pet.train();
-                                    // Elderly pets get tired more easily from training
-                                    if pet.life_stage() == "elderly" {
-                                        pet.health = pet.health.saturating_sub(2);
-                                    }
-                                },
-                                KeyCode::Char('s') => pet.sleep(),
-                                _ => {}
-                            }
-                        }
-                    }
-            }
+/// Why care just did less good than usual
+fn overstimulated_text(pet: &Pet, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    match lang {
+        Lang::En => format!(
+            "{} is getting overwhelmed. Give it a moment.",
+            spoken(&pet.name)
+        ),
+        Lang::Zh => format!("{} 有点应接不暇了, 让它缓一缓吧。", spoken(&pet.name)),
+    }
+}
+
+/// Why a pastime just cheered the pet up less than usual
+fn bored_text(pet: &Pet, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    match lang {
+        Lang::En => format!(
+            "{} is bored of the same old thing. Try something else.",
+            spoken(&pet.name)
+        ),
+        Lang::Zh => format!("{} 玩腻了老一套, 换个花样吧。", spoken(&pet.name)),
+    }
+}
+
+/// What to say when a pomodoro phase runs out
+fn focus_text(pet: &Pet, ended: Phase, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    match (ended, lang) {
+        (Phase::Work, Lang::En) => {
+            format!(
+                "Work done! {} is proud of you. Time for a break.",
+                spoken(&pet.name)
+            )
         }
+        (Phase::Work, Lang::Zh) => format!(
+            "完成一个番茄钟! {} 为你骄傲, 休息一下吧。",
+            spoken(&pet.name)
+        ),
+        (_, Lang::En) => "Break's over, back to work!".to_string(),
+        (_, Lang::Zh) => "休息结束, 继续专注吧!".to_string(),
     }
+}
 
-    // restore terminal
-    crossterm::terminal::disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
+/// A short message for the most important transition in a tick report
+fn tick_notice(
+    pet: &Pet,
+    report: &TickReport,
+    config: &Config,
+    ascii: AsciiMode,
+) -> Option<String> {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let name = spoken(&pet.name);
+    let cost = config.caretaker.cost;
+    let notices = match lang {
+        Lang::En => [
+            (TickEvent::Died, format!("{name} has passed away...")),
+            (
+                TickEvent::ReachedCritical,
+                format!("{name}'s health is critical!"),
+            ),
+            (TickEvent::GotSick, format!("{name} got sick!")),
+            (
+                TickEvent::WentIntoHiding,
+                format!("{name} is sulking and hid away. Keep it happy to coax it out."),
+            ),
+            (TickEvent::CameOut, format!("{name} came out of hiding!")),
+            (TickEvent::Healed, format!("{name} is well again!")),
+            (TickEvent::Aged, format!("{name} is a year older!")),
+            (
+                TickEvent::MadeMess,
+                format!("{name} made a mess, clean it up (w)!"),
+            ),
+            (
+                TickEvent::DozedOff,
+                format!("{name} was too tired and fell asleep!"),
+            ),
+            (
+                TickEvent::AutoFed,
+                format!("The caretaker fed {name} for {cost} coins"),
+            ),
+            (
+                TickEvent::AutoWashed,
+                format!("The caretaker washed {name} for {cost} coins"),
+            ),
+        ],
+        Lang::Zh => [
+            (TickEvent::Died, format!("{name} 离开了...")),
+            (TickEvent::ReachedCritical, format!("{name} 的健康告急!")),
+            (TickEvent::GotSick, format!("{name} 生病了!")),
+            (
+                TickEvent::WentIntoHiding,
+                format!("{name} 闹脾气躲起来了, 一直哄它开心才会出来。"),
+            ),
+            (TickEvent::CameOut, format!("{name} 不生气了, 出来啦!")),
+            (TickEvent::Healed, format!("{name} 康复了!")),
+            (TickEvent::Aged, format!("{name} 长大了一岁!")),
+            (
+                TickEvent::MadeMess,
+                format!("{name} 拉便便了, 记得清理 (w)!"),
+            ),
+            (TickEvent::DozedOff, format!("{name} 累得睡着了...")),
+            (
+                TickEvent::AutoFed,
+                format!("看护喂了 {name}, 花费 {cost} 金币"),
+            ),
+            (
+                TickEvent::AutoWashed,
+                format!("看护给 {name} 洗了澡, 花费 {cost} 金币"),
+            ),
+        ],
+    };
+    notices
+        .into_iter()
+        .find(|(event, _)| report.has(*event))
+        .map(|(_, message)| message)
+}
 
-    Ok(())
+/// How many ticks the milestone overlay stays up
+const CELEBRATION_TTL: u64 = 4;
+/// How many ticks an emote stays on the pet's face
+const EMOTE_TTL: u64 = 2;
+/// Seconds between looks at the calendar
+const CALENDAR_SECONDS: u64 = 60;
+
+/// Text for the celebration overlay when a tick crosses a life stage or a round age.
+/// ASCII mode always celebrates in English.
+fn celebration_text(
+    name: &str,
+    report: &TickReport,
+    lang: Lang,
+    ascii: AsciiMode,
+) -> Option<String> {
+    let (lang, party) = if ascii.is_on() {
+        (Lang::En, "*")
+    } else {
+        (lang, "🎉")
+    };
+    report.events.iter().find_map(|event| match (event, lang) {
+        (TickEvent::GrewUp(LifeStage::Adult), Lang::Zh) => {
+            Some(format!("{party} {name} 长大成年了!"))
+        }
+        (TickEvent::GrewUp(LifeStage::Adult), Lang::En) => {
+            Some(format!("{party} {name} is now an adult!"))
+        }
+        (TickEvent::GrewUp(LifeStage::Elderly), Lang::Zh) => {
+            Some(format!("{party} {name} 步入了老年!"))
+        }
+        (TickEvent::GrewUp(LifeStage::Elderly), Lang::En) => {
+            Some(format!("{party} {name} is now a wise elder!"))
+        }
+        (TickEvent::Milestone(age), Lang::Zh) => Some(format!("{party} {name} {age} 岁啦!")),
+        (TickEvent::Milestone(age), Lang::En) => Some(format!("{party} {name} turned {age}!")),
+        _ => None,
+    })
 }
 
-fn ui(frame: &mut Frame, pet: &Pet) {
-    if pet.status == PetStatus::Abandoned {
-        let message = vec![
-            Line::from(""),
-            Line::from("你的宠物因为被忽视太久，离家出走了..."),
-            Line::from(""),
-            Line::from(Span::styled(
-                "按 'q' 或 'ctrl-c' 退出，下次启动将开始新的旅程。",
-                Style::default().add_modifier(Modifier::ITALIC),
-            )),
-        ];
-        let paragraph = Paragraph::new(message)
-            .alignment(Alignment::Center)
-            .wrap(Wrap { trim: true });
-        frame.render_widget(paragraph, frame.area());
-        return;
+/// Overlay text for a newly unlocked achievement
+fn achievement_toast(achievement: Achievement, lang: Lang, ascii: AsciiMode) -> String {
+    match (lang, ascii.is_on()) {
+        (_, true) => format!("* Achievement unlocked: {}", achievement.title(Lang::En)),
+        (Lang::Zh, false) => format!("🏆 解锁成就: {}", achievement.title(lang)),
+        (Lang::En, false) => format!("🏆 Achievement unlocked: {}", achievement.title(lang)),
     }
+}
 
-    // Check if pet is dead
-    if pet.health == 0 {
-        let message = vec![
-            Line::from(""),
-            Line::from("你的宠物已经离开了..."),
-            Line::from(""),
-            Line::from(Span::styled(
-                "按 'q' 或 'ctrl-c' 退出，下次启动将开始新的旅程。",
-                Style::default().add_modifier(Modifier::ITALIC),
+fn level_up_text(skill: Skill, level: u8, lang: Lang, ascii: AsciiMode) -> String {
+    match (lang, ascii.is_on()) {
+        (_, true) => format!("{} reached level {level}!", skill.name(Lang::En)),
+        (Lang::Zh, false) => format!("{}升到了 {level} 级!", skill.name(lang)),
+        (Lang::En, false) => format!("{} reached level {level}!", skill.name(lang)),
+    }
+}
+
+/// Why `action` would be wasted on `pet`, for the actions `Pet::would_waste` checks
+fn waste_text(pet: &Pet, action: Action, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let name = spoken(&pet.name);
+    match (action, lang) {
+        (Action::Wash, Lang::En) => format!("{name} is already clean"),
+        (Action::Wash, Lang::Zh) => format!("{name} 已经很干净了"),
+        (Action::Medicine, Lang::En) => format!("{name} is already in perfect health"),
+        (Action::Medicine, Lang::Zh) => format!("{name} 已经很健康了"),
+        (_, Lang::En) => format!("{name} is already full"),
+        (_, Lang::Zh) => format!("{name} 已经吃饱了"),
+    }
+}
+
+/// Why medicine can't be given until `ready`: the doses ran out, or the
+/// last one is still cooling down
+fn medicine_text(pet: &Pet, ready: DateTime<Utc>, config: &Config, ascii: AsciiMode) -> String {
+    let now = Utc::now();
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let wait = format_duration((ready - now).to_std().unwrap_or_default(), lang);
+    let out = pet.doses.left(&config.medicine, now) == 0;
+    match (out, lang) {
+        (true, Lang::En) => format!("No medicine left; more in {wait}"),
+        (true, Lang::Zh) => format!("药吃完了, {wait}后补上"),
+        (false, Lang::En) => format!("Too soon for another dose; wait {wait}"),
+        (false, Lang::Zh) => format!("刚吃过药, 再等{wait}"),
+    }
+}
+
+/// The summary and body of the desktop notification for a pet whose health
+/// has turned critical
+fn critical_notification(pet: &Pet, config: &Config, ascii: AsciiMode) -> (String, String) {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let name = spoken(&pet.name);
+    match lang {
+        Lang::En => (
+            format!("{name}'s health is critical!"),
+            format!("Only {} health left; come and look after it.", pet.health),
+        ),
+        Lang::Zh => (
+            format!("{name} 的健康告急!"),
+            format!("健康值只剩 {}，快来照顾它吧。", pet.health),
+        ),
+    }
+}
+
+/// Why the pet won't eat until `done`: it's still digesting a full meal
+fn digest_text(pet: &Pet, done: DateTime<Utc>, config: &Config, ascii: AsciiMode) -> String {
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let wait = format_duration((done - Utc::now()).to_std().unwrap_or_default(), lang);
+    let name = spoken(&pet.name);
+    match lang {
+        Lang::En => format!("{name} is still digesting; wait {wait}"),
+        Lang::Zh => format!("{name} 还在消化, 再等{wait}"),
+    }
+}
+
+/// Session switches that change how the UI behaves
+#[derive(Debug, Default)]
+pub struct UiOptions {
+    /// Render and tick for display, but ignore care actions
+    pub readonly: bool,
+    pub ascii: AsciiMode,
+    /// Where `save_on_action` saves to; `None` never saves mid-session
+    pub state_file: Option<StateFile>,
+    /// No reaction frames or rotating text; alerts stay on screen instead
+    pub reduced_motion: bool,
+    /// What offline catch-up did before the session, shown when it starts
+    pub away: AwayDiff,
+    /// Stack the pet above its stats whatever the width
+    pub compact: bool,
+    /// Seconds of work and of break for a pomodoro timer, if focusing
+    pub focus: Option<(u64, u64)>,
+    /// Draw below the prompt instead of on the alternate screen
+    pub inline: bool,
+    /// Walk the owner through each care action, for `petty tutorial`
+    pub tutorial: bool,
+}
+
+/// Whether frames that come and go on their own, like reactions and the
+/// rotating chatter, should play
+fn should_animate(options: &UiOptions) -> bool {
+    !options.reduced_motion
+}
+
+/// Whether the pet may be saved before the session ends. Abandoned,
+/// retired and dead pets are deleted on exit, so they're never saved.
+fn can_save_mid_session(options: &UiOptions, pet: &Pet) -> bool {
+    !options.readonly
+        && options.state_file.is_some()
+        && !matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired)
+        && pet.health > 0
+}
+
+/// Whether a care action that just happened should be saved right away
+fn should_save_after_action(config: &Config, options: &UiOptions, pet: &Pet) -> bool {
+    config.save_on_action && can_save_mid_session(options, pet)
+}
+
+/// Whether the tick at `second` is due for an autosave
+fn should_autosave(config: &Config, options: &UiOptions, pet: &Pet, second: u64) -> bool {
+    config.autosave_seconds > 0
+        && second.is_multiple_of(config.autosave_seconds)
+        && can_save_mid_session(options, pet)
+}
+
+/// Where key presses come from. Both implementations feed the same
+/// `Session::on_key` dispatch.
+trait InputSource {
+    /// Wait for the next terminal event. An error means this source can't
+    /// deliver events anymore.
+    async fn next_event(&mut self) -> io::Result<Event>;
+}
+
+/// Events from crossterm's async `EventStream`
+struct StreamInput<S> {
+    stream: S,
+}
+
+impl<S: Stream<Item = io::Result<Event>> + Unpin> InputSource for StreamInput<S> {
+    async fn next_event(&mut self) -> io::Result<Event> {
+        match self.stream.next().await {
+            Some(event) => event,
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "event stream ended",
             )),
-        ];
-        let paragraph = Paragraph::new(message)
-            .alignment(Alignment::Center)
-            .wrap(Wrap { trim: true });
-        frame.render_widget(paragraph, frame.area());
-        return;
+        }
     }
+}
 
-    let main_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(0),    // Main content
-            Constraint::Length(1), // Hint bar
-        ])
-        .split(frame.area());
+/// How often the polling fallback checks for input
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
-    let top_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .margin(1)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-        .split(main_layout[0]);
+/// Fallback that polls for events without blocking the runtime. `poll`
+/// returns an event if one is ready.
+struct PollInput<P> {
+    poll: P,
+}
 
-    let pet_art_lines = if pet.debug_mode {
-        vec![
-            "",
-            r"     /\_/\",
-            r"     ( o_o )",
-            r"     > ^ <",
-            "别再戳我了，我在休假！",
-        ]
-    } else if pet.status == PetStatus::Sick {
-        // Sick pet
-        vec![
-            "",
-            r"     /\_/\",
-            r"     ( x_x )",
-            r"     > ^ <",
-            "生病了...",
-        ]
-    } else if pet.is_sleeping {
-        vec![
-            "",
-            r"      /\_/\",
-            r"           ( - . - ) Zzz",
-            r"      > ^ <",
-            "",
-        ]
+impl<P: FnMut() -> io::Result<Option<Event>>> InputSource for PollInput<P> {
+    async fn next_event(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(event) = (self.poll)()? {
+                return Ok(event);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+fn poll_terminal() -> io::Result<Option<Event>> {
+    if event::poll(Duration::ZERO)? {
+        event::read().map(Some)
     } else {
-        match pet.life_stage() {
-            "elderly" => {
-                // Elderly pet
-                vec!["", r"     /\_/\", r"     ( -_- )", r"     > v <", ""]
+        Ok(None)
+    }
+}
+
+type TerminalPoll = fn() -> io::Result<Option<Event>>;
+
+/// The terminal's input source, chosen at runtime. Starts on the event
+/// stream (unless `PETTY_INPUT=poll`) and switches to polling for good if
+/// the stream fails or ends.
+enum TerminalInput {
+    Stream(StreamInput<event::EventStream>),
+    Poll(PollInput<TerminalPoll>),
+}
+
+impl TerminalInput {
+    fn detect() -> Self {
+        if std::env::var("PETTY_INPUT").is_ok_and(|v| v == "poll") {
+            Self::polling()
+        } else {
+            TerminalInput::Stream(StreamInput {
+                stream: event::EventStream::new(),
+            })
+        }
+    }
+
+    fn polling() -> Self {
+        TerminalInput::Poll(PollInput {
+            poll: poll_terminal as TerminalPoll,
+        })
+    }
+}
+
+impl InputSource for TerminalInput {
+    async fn next_event(&mut self) -> io::Result<Event> {
+        if let TerminalInput::Stream(stream) = self {
+            match stream.next_event().await {
+                Ok(event) => return Ok(event),
+                Err(_) => *self = Self::polling(),
             }
-            "adult" => {
-                // Adult pet
-                vec!["", r"     /\_/\", r"     ( ._. )", r"     > ^ <", ""]
+        }
+        match self {
+            TerminalInput::Poll(poll) => poll.next_event().await,
+            TerminalInput::Stream(_) => unreachable!("switched to polling above"),
+        }
+    }
+}
+
+/// A stat the debug keymap can adjust
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DebugStat {
+    Hunger,
+    Mood,
+    Cleanliness,
+    Health,
+    Age,
+}
+
+/// Debug keys as (increase, decrease, stat). Adding a row here is all it
+/// takes to make a stat editable and list it in the hint bar.
+const DEBUG_KEYS: [(char, char, DebugStat); 5] = [
+    ('h', 'j', DebugStat::Hunger),
+    ('m', 'n', DebugStat::Mood),
+    ('c', 'v', DebugStat::Cleanliness),
+    ('k', 'l', DebugStat::Health),
+    ('a', 'z', DebugStat::Age),
+];
+
+/// Debug key that switches between healthy and sick
+const DEBUG_STATUS_KEY: char = 'x';
+
+impl DebugStat {
+    fn label(self) -> &'static str {
+        match self {
+            DebugStat::Hunger => "Hunger",
+            DebugStat::Mood => "Mood",
+            DebugStat::Cleanliness => "Cleanliness",
+            DebugStat::Health => "Health",
+            DebugStat::Age => "Age",
+        }
+    }
+
+    /// How far one key press moves the stat
+    fn step(self) -> i32 {
+        match self {
+            DebugStat::Age => 1,
+            _ => 10,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DebugEdit {
+    Adjust(DebugStat, i32),
+    /// Alive and sick only; an abandoned pet would lock the debug keys out
+    ToggleSick,
+}
+
+impl DebugEdit {
+    fn apply(self, pet: &mut Pet) {
+        let adjust = |value: u8, delta: i32| (value as i32 + delta).clamp(0, u8::MAX as i32) as u8;
+        match self {
+            DebugEdit::Adjust(DebugStat::Hunger, delta) => pet.hunger = adjust(pet.hunger, delta),
+            DebugEdit::Adjust(DebugStat::Mood, delta) => pet.mood = adjust(pet.mood, delta),
+            DebugEdit::Adjust(DebugStat::Cleanliness, delta) => {
+                pet.cleanliness = adjust(pet.cleanliness, delta)
             }
-            _ => {
-                // Young pet
-                if pet.mood < 20 {
-                    // Sad pet
-                    vec!["", r"     /\_/\", r"     ( T.T )", r"     > ^ <", ""]
-                } else if pet.hunger > 60 {
-                    // Hungry pet
-                    vec!["", r"     /\_/\", r"     ( o_o )", r"     > ^ <", ""]
-                } else if pet.cleanliness < 40 {
-                    // Dirty pet
-                    vec!["", r"     /\_/\", r"     ( >.< )", r"     > ^ <", ""]
-                } else if pet.mood > 80 {
-                    // Happy pet
-                    vec!["", r"     /\_/\", r"     ( ^.^ )", r"     > ^ <", ""]
-                } else {
-                    // Neutral young pet
-                    vec!["", r"     /\_/\", r"     ( o.o )", r"     > ^ <", ""]
+            DebugEdit::Adjust(DebugStat::Health, delta) => pet.health = adjust(pet.health, delta),
+            DebugEdit::Adjust(DebugStat::Age, delta) => {
+                pet.age = pet.age.saturating_add_signed(delta)
+            }
+            DebugEdit::ToggleSick => {
+                pet.status = match pet.status {
+                    PetStatus::Sick => PetStatus::Alive,
+                    _ => PetStatus::Sick,
                 }
             }
         }
+    }
+}
+
+fn debug_edit(code: KeyCode) -> Option<DebugEdit> {
+    let KeyCode::Char(c) = code else {
+        return None;
     };
-    let pet_art = Paragraph::new(pet_art_lines.join("\n")).alignment(Alignment::Center);
+    if c == DEBUG_STATUS_KEY {
+        return Some(DebugEdit::ToggleSick);
+    }
+    DEBUG_KEYS.iter().find_map(|&(up, down, stat)| {
+        if c == up {
+            Some(DebugEdit::Adjust(stat, stat.step()))
+        } else if c == down {
+            Some(DebugEdit::Adjust(stat, -stat.step()))
+        } else {
+            None
+        }
+    })
+}
 
-    let pet_view = Block::default().title("Pet").borders(Borders::ALL);
-    frame.render_widget(pet_art.block(pet_view), top_layout[0]);
+fn debug_hint() -> String {
+    let stats: Vec<String> = DEBUG_KEYS
+        .iter()
+        .map(|(up, down, stat)| format!("({up}/{down}) {}", stat.label()))
+        .collect();
+    format!(
+        " [Debug Mode] {} | ({DEBUG_STATUS_KEY}) Sick | (Esc) Exit ",
+        stats.join(" | ")
+    )
+}
 
-    let stats_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([Constraint::Length(1); 7].as_ref())
-        .split(top_layout[1]);
+/// What the run loop has to do after a key press, beyond updating the session
+#[derive(Debug, PartialEq)]
+enum KeyOutcome {
+    Continue,
+    Quit,
+    Suspend,
+    ShowHelp,
+    ShowGallery,
+    ShowSkills,
+    /// A care action happened and `save_on_action` wants it on disk
+    Save,
+}
 
-    let stats_view = Block::default().title("Stats").borders(Borders::ALL);
+/// Keys that only change what's on screen, so they work even while the pet
+/// sleeps or in readonly mode
+const GALLERY_KEY: char = 'g';
+const SKILLS_KEY: char = 'k';
 
-    let name = Paragraph::new(format!("Name: {}", pet.name));
-    let age = Paragraph::new(format!("Age: {} ({})", pet.age, pet.life_stage()));
-    let health_text = match pet.status {
-        PetStatus::Sick => format!("Health: {} (生病)", pet.health),
-        PetStatus::Abandoned => format!("Health: {} (被遗弃)", pet.health),
-        _ => format!("Health: {}", pet.health),
-    };
-    let health = Paragraph::new(health_text);
-    let hunger = Paragraph::new(format!("Hunger: {}", pet.hunger));
-    let cleanliness = Paragraph::new(format!("Cleanliness: {}", pet.cleanliness));
-    let mood = Paragraph::new(format!("Mood: {}", pet.mood));
-    let status = Paragraph::new(format!(
-        "Status: {}",
-        if pet.is_sleeping { "Sleeping" } else { "Awake" }
-    ));
-
-    frame.render_widget(stats_view, top_layout[1]);
-    frame.render_widget(name, stats_layout[0]);
-    frame.render_widget(age, stats_layout[1]);
-    frame.render_widget(health, stats_layout[2]);
-    frame.render_widget(hunger, stats_layout[3]);
-    frame.render_widget(cleanliness, stats_layout[4]);
-    frame.render_widget(mood, stats_layout[5]);
-    frame.render_widget(status, stats_layout[6]);
-
-    let hints = if pet.debug_mode {
-        Paragraph::new(" [Debug Mode] (h/j) Hunger | (m/n) Mood | (c/v) Cleanliness | (Esc) Exit ")
-            .alignment(Alignment::Center)
-    } else {
-        Paragraph::new(" (f)eed | (w)ash | (p)lay | (t)rain | (s)leep | (q)uit | ctrl-c | ctrl-z ")
-            .alignment(Alignment::Center)
-    };
-    frame.render_widget(hints, main_layout[1]);
+/// Wakes a sleeping pet. It's not `s`, so going to sleep can't be undone by
+/// pressing the same key twice.
+const WAKE_KEY: char = 'u';
+const RADAR_KEY: char = 'r';
+const SPA_KEY: char = 'a';
+const MEDICINE_KEY: char = 'm';
+const SNACK_KEY: char = 'n';
+/// Keys remembered for typed commands, enough for the longest one
+const MAX_TYPED: usize = "debug".len();
+
+/// Everything the run loop tracks for one session of play
+struct Session<'a> {
+    pet: &'a mut Pet,
+    config: &'a Config,
+    options: &'a UiOptions,
+    seconds_elapsed: u64,
+    reactions: ReactionQueue,
+    notice: Option<(String, u64)>,
+    celebration: Option<(String, u64)>,
+    /// The emote on show and when it goes
+    emote: Option<(Emote, u64)>,
+    /// The last emote shown, so a trend shows once as it starts rather
+    /// than every time a tick carries it on
+    last_emote: Option<Emote>,
+    notifier: Notifier,
+    cooldowns: Cooldowns,
+    stimulation: Stimulation,
+    host: Sampler,
+    input_buffer: String,
+    /// Last key press, or the session start. Counts as the owner being
+    /// around for the abandonment clock.
+    last_activity: DateTime<Utc>,
+    /// Show the radar chart under the stats
+    radar: bool,
+    sounds: SoundPlayer,
+    /// `options.away` written out, if anything happened
+    away: Option<String>,
+    /// Where the mouse was last seen, with `mouse` capture on
+    cursor: Option<(u16, u16)>,
+    focus: Option<Pomodoro>,
+    tutorial: Option<Tutorial>,
+    /// What the calendar has drawn above the pet today
+    decoration: Option<String>,
+}
+
+impl<'a> Session<'a> {
+    fn new(pet: &'a mut Pet, config: &'a Config, options: &'a UiOptions) -> Self {
+        let lang = if options.ascii.is_on() {
+            Lang::En
+        } else {
+            config.lang
+        };
+        let away = options.away.summary(lang);
+        let mut session = Self {
+            pet,
+            config,
+            options,
+            seconds_elapsed: 0,
+            reactions: ReactionQueue::default(),
+            notice: away.clone().map(|summary| (summary, AWAY_NOTICE_TTL)),
+            celebration: None,
+            emote: None,
+            last_emote: None,
+            notifier: Notifier::default(),
+            cooldowns: Cooldowns::default(),
+            stimulation: Stimulation::default(),
+            host: Sampler::default(),
+            input_buffer: String::new(),
+            last_activity: Utc::now(),
+            radar: config.radar_chart,
+            sounds: SoundPlayer::default(),
+            away,
+            cursor: None,
+            focus: options.focus.map(|(work, rest)| Pomodoro::new(work, rest)),
+            tutorial: options.tutorial.then(Tutorial::default),
+            decoration: None,
+        };
+        session.check_calendar();
+        session
+    }
+
+    /// Look up what's on today, giving the pet its birthday bonus if that's
+    /// today and there's a save to keep it in
+    fn check_calendar(&mut self) {
+        let (today, calendar) = (Local::now().date_naive(), &self.config.calendar);
+        let occasions = calendar::occasions(today, self.pet.birth.date_naive(), calendar);
+        self.decoration = calendar::decoration(&occasions, self.options.ascii.is_on());
+        if self.options.readonly || self.options.state_file.is_none() {
+            return;
+        }
+        if let Some(years) = calendar::celebrate(self.pet, today, calendar) {
+            let lang = if self.options.ascii.is_on() {
+                Lang::En
+            } else {
+                self.config.lang
+            };
+            let message = calendar::birthday_text(&spoken(&self.pet.name), years, lang);
+            self.celebration = Some((message, self.seconds_elapsed + CELEBRATION_TTL));
+        }
+    }
+
+    fn view(&self) -> View<'_> {
+        View {
+            reaction: self.reactions.current(self.seconds_elapsed),
+            notice: self.notice.as_ref().map(|(message, _)| message.as_str()),
+            celebration: self
+                .celebration
+                .as_ref()
+                .map(|(message, _)| message.as_str()),
+            readonly: self.options.readonly,
+            ascii: self.options.ascii,
+            last_activity: self.last_activity,
+            radar: self.radar,
+            chatter: self.chatter(),
+            emote: self.emote_text(),
+            reduced_motion: self.options.reduced_motion,
+            away: self.away.as_deref(),
+            compact: self.options.compact,
+            tick: self.seconds_elapsed,
+            thresholds: &self.config.thresholds,
+            cursor: self.cursor,
+            focus: self.focus.as_ref(),
+            tutorial: self.tutorial.as_ref(),
+            decoration: self.decoration.as_deref(),
+        }
+    }
+
+    /// The pet's flavor-text line, unless it's busy with a reaction or debugging
+    fn chatter(&self) -> Option<&'static str> {
+        let busy = self.pet.debug_mode || self.reactions.current(self.seconds_elapsed).is_some();
+        if !self.config.pet_talks || busy {
+            return None;
+        }
+        let lang = if self.options.ascii.is_on() {
+            Lang::En
+        } else {
+            self.config.lang
+        };
+        // Stick to one line rather than cycling through them
+        let tick = if should_animate(self.options) {
+            self.seconds_elapsed
+        } else {
+            0
+        };
+        Some(chatter(self.pet, &self.config.thresholds, tick, lang))
+    }
+
+    fn emote_text(&self) -> Option<&'static str> {
+        let (emote, _) = self.emote?;
+        let lang = if self.options.ascii.is_on() {
+            Lang::En
+        } else {
+            self.config.lang
+        };
+        Some(emote.text(lang))
+    }
+
+    fn ring(&mut self, pattern: &str) {
+        if self.config.sounds.enabled {
+            self.sounds.play(pattern, Instant::now());
+        }
+    }
+
+    fn on_view_key(&mut self, code: KeyCode) -> Option<KeyOutcome> {
+        match code {
+            KeyCode::Char(GALLERY_KEY) => Some(KeyOutcome::ShowGallery),
+            KeyCode::Char(SKILLS_KEY) => Some(KeyOutcome::ShowSkills),
+            KeyCode::Char(RADAR_KEY) => {
+                self.radar = !self.radar;
+                Some(KeyOutcome::Continue)
+            }
+            _ => None,
+        }
+    }
+
+    fn on_tick(&mut self) {
+        let pet = &mut *self.pet;
+        if matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired) {
+            return;
+        }
+
+        self.seconds_elapsed += 1;
+        let now = self.seconds_elapsed;
+        self.reactions.prune(now);
+        if self
+            .notice
+            .as_ref()
+            .is_some_and(|(_, expires_at)| *expires_at <= now)
+        {
+            self.notice = None;
+        }
+        if self
+            .celebration
+            .as_ref()
+            .is_some_and(|(_, expires_at)| *expires_at <= now)
+        {
+            self.celebration = None;
+        }
+        if self.emote.is_some_and(|(_, expires_at)| expires_at <= now) {
+            self.emote = None;
+        }
+
+        if pet.debug_mode {
+            return; // Pause state changes in debug mode
+        }
+
+        // The date may have changed since the session started
+        if now.is_multiple_of(CALENDAR_SECONDS) {
+            self.check_calendar();
+        }
+        let pet = &mut *self.pet;
+
+        if let Some(ended) = self.focus.as_mut().and_then(Pomodoro::tick) {
+            if ended == Phase::Work {
+                focus::reward(pet, &self.config.focus);
+            }
+            let message = focus_text(pet, ended, self.config, self.options.ascii);
+            self.notice = Some((message, now + NOTICE_TTL));
+        }
+
+        // A busy machine wears the pet out; sleep still rests it as usual
+        let host_load = &self.config.host_load;
+        if host_load.enabled
+            && !pet.is_sleeping
+            && now.is_multiple_of(host_load.sample_seconds)
+            && let Some(load) = self.host.sample()
+        {
+            pet.fatigue = pet.fatigue.max(host::fatigue_floor(load, host_load));
+        }
+
+        // Unlocked even when a milestone takes the overlay, so nothing is missed
+        let (report, unlocked) = pet.live(self.config, now, Utc::now());
+        let ascii = self.options.ascii;
+        if let Some(message) = tick_notice(pet, &report, self.config, ascii) {
+            self.notice = Some((message, now + NOTICE_TTL));
+        }
+        // Emotes come and go, so they're left out with reduced motion
+        let emote = (self.config.pet_emotes && should_animate(self.options))
+            .then(|| emote::emote(&report, pet, &self.config.thresholds))
+            .flatten();
+        if let Some(emote) = emote
+            && self.last_emote != Some(emote)
+        {
+            self.emote = Some((emote, now + EMOTE_TTL));
+            self.last_emote = Some(emote);
+        }
+        let lang = self.config.lang;
+        if let Some(message) =
+            celebration_text(&spoken(&pet.name), &report, lang, ascii).or_else(|| {
+                unlocked
+                    .first()
+                    .map(|achievement| achievement_toast(*achievement, lang, ascii))
+            })
+        {
+            self.celebration = Some((message, now + CELEBRATION_TTL));
+        }
+
+        let sounds = &self.config.sounds;
+        for (event, pattern) in [
+            (TickEvent::Died, &sounds.died),
+            (TickEvent::ReachedCritical, &sounds.critical),
+            (TickEvent::GotSick, &sounds.sick),
+        ] {
+            if report.has(event) {
+                self.ring(pattern);
+                break;
+            }
+        }
+
+        let pet = &mut *self.pet;
+        let critical = pet.health > 0 && pet.health < CRITICAL_HEALTH;
+        if self.config.desktop_notify
+            && self
+                .notifier
+                .should_notify(critical, Instant::now(), NOTIFY_COOLDOWN)
+        {
+            let (summary, body) = critical_notification(pet, self.config, self.options.ascii);
+            notify::send(summary, body);
+        }
+    }
+
+    /// Track typed words like "debug" and "help"; returns the outcome if one completed
+    fn on_typed(&mut self, code: KeyCode) -> Option<KeyOutcome> {
+        let KeyCode::Char(c) = code else {
+            self.input_buffer.clear();
+            return None;
+        };
+        self.input_buffer.push(c);
+        // Only the tail can finish a typed command, so that's all that's kept
+        if let Some((start, _)) = self.input_buffer.char_indices().rev().nth(MAX_TYPED - 1) {
+            self.input_buffer.drain(..start);
+        }
+        if self.input_buffer.ends_with("debug") && !self.options.readonly {
+            self.pet.debug_mode = true;
+            self.input_buffer.clear();
+        } else if self.input_buffer.ends_with("?") || self.input_buffer.ends_with("help") {
+            self.input_buffer.clear();
+            return Some(KeyOutcome::ShowHelp);
+        }
+        None
+    }
+
+    /// Whether the last keys start a typed word without finishing it yet.
+    /// The key that continues one is held back rather than acted on, so
+    /// the `u` in "debug" doesn't wake the pet on the way.
+    fn mid_word(&self) -> bool {
+        self.input_buffer.char_indices().any(|(start, _)| {
+            let typed = &self.input_buffer[start..];
+            typed.chars().count() > 1
+                && ["debug", "help"]
+                    .iter()
+                    .any(|word| word.len() > typed.len() && word.starts_with(typed))
+        })
+    }
+
+    fn on_key(&mut self, key: KeyEvent) -> KeyOutcome {
+        if key.kind != KeyEventKind::Press {
+            return KeyOutcome::Continue;
+        }
+        self.last_activity = Utc::now();
+        self.pet.interact();
+
+        // Always allow exit
+        if key.code == KeyCode::Char('q')
+            || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL)
+        {
+            return KeyOutcome::Quit;
+        }
+
+        // If abandoned or retired, only allow quitting
+        if matches!(self.pet.status, PetStatus::Abandoned | PetStatus::Retired) {
+            return KeyOutcome::Continue;
+        }
+
+        if key.code == KeyCode::Char('z') && key.modifiers == KeyModifiers::CONTROL {
+            return KeyOutcome::Suspend;
+        }
+
+        if self.options.readonly {
+            return self
+                .on_typed(key.code)
+                .or_else(|| self.on_view_key(key.code))
+                .unwrap_or(KeyOutcome::Continue);
+        }
+
+        let pet = &mut *self.pet;
+        if pet.debug_mode {
+            if key.code == KeyCode::Esc {
+                pet.debug_mode = false;
+                self.input_buffer.clear();
+            } else if let Some(edit) = debug_edit(key.code) {
+                edit.apply(pet);
+            }
+            return KeyOutcome::Continue;
+        }
+
+        if let Some(outcome) = self.on_typed(key.code) {
+            return outcome;
+        }
+        if self.pet.debug_mode || self.mid_word() {
+            return KeyOutcome::Continue;
+        }
+        if let Some(outcome) = self.on_view_key(key.code) {
+            return outcome;
+        }
+
+        let action = match key.code {
+            KeyCode::Char('f') => Some(Action::Feed),
+            KeyCode::Char('w') => Some(Action::Wash),
+            KeyCode::Char('p') => Some(Action::Play),
+            KeyCode::Char('t') => Some(Action::Train),
+            KeyCode::Char('s') => Some(Action::Sleep),
+            KeyCode::Char(WAKE_KEY) => Some(Action::Wake),
+            KeyCode::Char(SPA_KEY) => Some(Action::Spa),
+            KeyCode::Char(MEDICINE_KEY) => Some(Action::Medicine),
+            KeyCode::Char(SNACK_KEY) => Some(Action::Snack),
+            _ => None,
+        };
+        let lang = if self.options.ascii.is_on() {
+            Lang::En
+        } else {
+            self.config.lang
+        };
+        if let Some(action) = action
+            && !self.pet.can_act(action)
+        {
+            // Say how to wake a sleeping pet instead of waking it by accident
+            if self.pet.is_sleeping {
+                let message = match lang {
+                    Lang::En => "Shh, it's sleeping. Press 'u' to wake it up.",
+                    Lang::Zh => "嘘, 它在睡觉。按 'u' 叫醒它。",
+                };
+                self.notice = Some((message.to_string(), self.seconds_elapsed + NOTICE_TTL));
+            } else if action == Action::Spa {
+                let message = match lang {
+                    Lang::En => format!(
+                        "A spa day costs {SPA_COST} coins; you have {}",
+                        self.pet.coins
+                    ),
+                    Lang::Zh => format!("SPA 需要 {SPA_COST} 金币, 你只有 {} 枚", self.pet.coins),
+                };
+                self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            }
+            return KeyOutcome::Continue;
+        }
+        if let Some(action) = action
+            && self.pet.would_waste(action)
+        {
+            let message = waste_text(self.pet, action, self.config, self.options.ascii);
+            self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            if self.config.skip_wasteful {
+                return KeyOutcome::Continue;
+            }
+        }
+        if action == Some(Action::Medicine)
+            && let Some(ready) = self.pet.doses.ready_at(&self.config.medicine, Utc::now())
+        {
+            let message = medicine_text(self.pet, ready, self.config, self.options.ascii);
+            self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            return KeyOutcome::Continue;
+        }
+        if matches!(action, Some(Action::Feed | Action::Snack))
+            && let Some(done) = self.pet.digesting(Utc::now())
+        {
+            let message = digest_text(self.pet, done, self.config, self.options.ascii);
+            self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            return KeyOutcome::Continue;
+        }
+        if let Some(action) = action {
+            let cooldown = self.config.cooldowns.get(action);
+            if !self.cooldowns.try_use(action, Instant::now(), cooldown) {
+                let message = match lang {
+                    Lang::En => "Not yet!",
+                    Lang::Zh => "还不行, 稍等一下!",
+                };
+                // Only until the next tick, so it doesn't hide other notices for long
+                self.notice = Some((message.to_string(), self.seconds_elapsed + 1));
+                return KeyOutcome::Continue;
+            }
+            let skills = self.pet.skills;
+            let before = self.pet.clone();
+            let now = Utc::now();
+            let bored = self.pet.variety(action, self.config, now) < 1.0;
+            self.pet.act(action, self.config, now);
+            if let Some(tutorial) = &mut self.tutorial {
+                tutorial.observe(action);
+            }
+            if bored {
+                let message = bored_text(self.pet, self.config, self.options.ascii);
+                self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            }
+            if self.config.overstimulation.enabled && bond::earned_by(action) > 0 {
+                let share = self
+                    .stimulation
+                    .interact(Instant::now(), &self.config.overstimulation);
+                if share < 1.0 {
+                    stimulation::damp(&before, self.pet, share);
+                    let message = overstimulated_text(self.pet, self.config, self.options.ascii);
+                    self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+                }
+            }
+            if let Some((skill, level)) = self.pet.skills.leveled_up(&skills) {
+                let message = level_up_text(skill, level, self.config.lang, self.options.ascii);
+                self.notice = Some((message, self.seconds_elapsed + NOTICE_TTL));
+            }
+            self.ring(self.config.sounds.for_action(action));
+            if let Some(reaction) = Reaction::for_action(action)
+                && should_animate(self.options)
+            {
+                self.reactions.push(reaction, self.seconds_elapsed);
+            }
+            if should_save_after_action(self.config, self.options, self.pet) {
+                return KeyOutcome::Save;
+            }
+        }
+        KeyOutcome::Continue
+    }
+
+    /// Stop the pomodoro timer, letting the pet down if it was mid-work
+    fn end_focus(&mut self) {
+        if let Some(pomodoro) = &mut self.focus
+            && pomodoro.stop()
+            && !self.options.readonly
+            && !matches!(self.pet.status, PetStatus::Abandoned | PetStatus::Retired)
+        {
+            focus::quit_early(self.pet, &self.config.focus);
+        }
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let Some(file) = &self.options.state_file else {
+            return Ok(());
+        };
+        // Keep offline catch-up from replaying time already played if we crash
+        self.pet.last_seen = Utc::now();
+        Ok(save_state_to(file, self.pet)?)
+    }
+}
+
+pub async fn run_ui(pet: &mut Pet, config: &Config, options: &UiOptions) -> Result<()> {
+    let theme = config.active_theme();
+    let mut input = TerminalInput::detect();
+
+    // setup terminal
+    crossterm::terminal::enable_raw_mode()?;
+    enter_screen(&mut stdout(), options.inline, config.mouse)?;
+    if config.terminal_title {
+        // A terminal that can't set titles just ignores these
+        stdout().execute(PushTitle).ok();
+    }
+    let mut terminal = new_terminal(options.inline)?;
+
+    let mut session = Session::new(pet, config, options);
+    let result = run_loop(&mut terminal, &mut session, &theme, &mut input).await;
+    session.end_focus();
+
+    // restore terminal
+    if options.inline {
+        // Leave the last frame in the scrollback with the prompt below it
+        let bottom = terminal.get_frame().area().bottom();
+        terminal.set_cursor_position((0, bottom.saturating_sub(1)))?;
+        terminal.show_cursor()?;
+    }
+    if config.terminal_title {
+        stdout().execute(PopTitle).ok();
+    }
+    crossterm::terminal::disable_raw_mode()?;
+    leave_screen(&mut stdout(), options.inline, config.mouse)?;
+    if options.inline {
+        println!();
+    }
+
+    result
+}
+
+async fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    session: &mut Session<'_>,
+    theme: &Theme,
+    input: &mut impl InputSource,
+) -> Result<()> {
+    let mut ticker = interval(Duration::from_secs(1));
+    let mut frames = FrameLimiter::new(session.config.max_fps);
+    // Leave the loop normally on termination so the state is saved and the lock released
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut title = String::new();
+
+    loop {
+        if session.config.terminal_title {
+            let current = title_text(session.pet, session.config, session.options.ascii);
+            if current != title {
+                stdout().execute(SetTitle(&current)).ok();
+                title = current;
+            }
+        }
+        if frames.should_draw(Instant::now()) {
+            terminal.draw(|f| ui(f, session.pet, session.config, theme, &session.view()))?;
+        }
+        // Only wakes up for a redraw that came in too soon after the last frame
+        let next_frame = frames.wait(Instant::now());
+
+        tokio::select! {
+            _ = terminate.recv() => break,
+            _ = hangup.recv() => break,
+            _ = tokio::time::sleep(next_frame), if frames.is_pending() => {}
+            _ = ticker.tick() => {
+                session.on_tick();
+                frames.request();
+                if should_autosave(session.config, session.options, session.pet, session.seconds_elapsed) {
+                    session.save()?;
+                }
+            }
+            event = input.next_event() => {
+                // Resizes and the like need a redraw too
+                frames.request();
+                let key = match event? {
+                    Event::Key(key) => key,
+                    Event::Mouse(mouse) => {
+                        session.cursor = Some((mouse.column, mouse.row));
+                        continue;
+                    }
+                    _ => continue,
+                };
+                match session.on_key(key) {
+                    KeyOutcome::Continue => {}
+                    KeyOutcome::Quit => break,
+                    KeyOutcome::Suspend => {
+                        suspend_and_restore(session.options.inline, session.config.mouse);
+                        // Recreate terminal after resume
+                        *terminal = new_terminal(session.options.inline)?;
+                    }
+                    KeyOutcome::ShowHelp => show_help_screen(terminal, session.options.ascii)?,
+                    KeyOutcome::ShowGallery => show_gallery_screen(
+                        terminal,
+                        session.pet,
+                        session.config.lang,
+                        session.options.ascii,
+                    )?,
+                    KeyOutcome::ShowSkills => show_skills_screen(
+                        terminal,
+                        session.pet,
+                        session.config.lang,
+                        session.options.ascii,
+                    )?,
+                    KeyOutcome::Save => session.save()?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Play `moments` back full screen at `speed` seconds of the pet's life
+/// per second. Any key skips to the next moment; `q` or ctrl-c stops.
+pub async fn run_replay(
+    moments: &[Moment],
+    config: &Config,
+    ascii: AsciiMode,
+    speed: u64,
+) -> Result<()> {
+    let theme = config.active_theme();
+    let mut input = TerminalInput::detect();
+
+    enable_raw_mode()?;
+    enter_screen(&mut stdout(), false, false)?;
+    let mut terminal = new_terminal(false)?;
+    let result = replay_loop(
+        &mut terminal,
+        moments,
+        config,
+        &theme,
+        ascii,
+        speed,
+        &mut input,
+    )
+    .await;
+    disable_raw_mode()?;
+    leave_screen(&mut stdout(), false, false)?;
+
+    result
+}
+
+async fn replay_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    moments: &[Moment],
+    config: &Config,
+    theme: &Theme,
+    ascii: AsciiMode,
+    speed: u64,
+    input: &mut impl InputSource,
+) -> Result<()> {
+    // Nobody can run away from a replay, so there's no away clock to show
+    let config = Config {
+        mode: TimeMode::Session,
+        ..config.clone()
+    };
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+
+    for (i, moment) in moments.iter().enumerate() {
+        let caption = format!(
+            "{} | {}/{} | (q)uit",
+            replay::caption(moment, lang),
+            i + 1,
+            moments.len()
+        );
+        let view = View {
+            reaction: None,
+            notice: Some(&caption),
+            celebration: None,
+            readonly: true,
+            ascii,
+            last_activity: moment.at,
+            radar: false,
+            chatter: None,
+            emote: None,
+            reduced_motion: true,
+            away: None,
+            compact: false,
+            tick: 0,
+            thresholds: &config.thresholds,
+            cursor: None,
+            focus: None,
+            tutorial: None,
+            decoration: None,
+        };
+        // The stats panel counts how long the pet has lived up to now
+        let mut pet = moment.pet.clone();
+        pet.birth = Utc::now() - moment.at.signed_duration_since(pet.birth);
+        terminal.draw(|f| ui(f, &pet, &config, theme, &view))?;
+
+        // The last moment stays up until a key is pressed
+        let next = moments.get(i + 1);
+        let wait = next.map_or(Duration::ZERO, |next| {
+            replay::pause(moment.at, next.at, speed)
+        });
+        tokio::select! {
+            _ = tokio::time::sleep(wait), if next.is_some() => {}
+            event = input.next_event() => {
+                if let Event::Key(key) = event?
+                    && key.kind == KeyEventKind::Press
+                    && (key.code == KeyCode::Char('q')
+                        || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL))
+                {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Per-frame state owned by the run loop rather than the pet
+struct View<'a> {
+    reaction: Option<Reaction>,
+    notice: Option<&'a str>,
+    celebration: Option<&'a str>,
+    readonly: bool,
+    ascii: AsciiMode,
+    last_activity: DateTime<Utc>,
+    radar: bool,
+    /// What the pet is saying, if anything
+    chatter: Option<&'static str>,
+    /// A passing look for where its stats are heading, said in place of
+    /// the chatter
+    emote: Option<&'static str>,
+    reduced_motion: bool,
+    /// What happened while the owner was away
+    away: Option<&'a str>,
+    compact: bool,
+    /// Seconds since the session started
+    tick: u64,
+    thresholds: &'a Thresholds,
+    /// Column and row of the mouse, if it has moved
+    cursor: Option<(u16, u16)>,
+    focus: Option<&'a Pomodoro>,
+    /// The tutorial's next step, in `petty tutorial`
+    tutorial: Option<&'a Tutorial>,
+    /// Festive art from the calendar, drawn above the pet
+    decoration: Option<&'a str>,
+}
+
+/// Below this many columns the pet and its stats don't fit side by side
+const COMPACT_WIDTH: u16 = 60;
+
+/// How the pet and stats panels share the screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Arrangement {
+    SideBySide,
+    /// The pet above its stats, for narrow terminals or `--compact`
+    Stacked,
+}
+
+fn arrangement(area: Rect, compact: bool) -> Arrangement {
+    if compact || area.width < COMPACT_WIDTH {
+        Arrangement::Stacked
+    } else {
+        Arrangement::SideBySide
+    }
+}
+
+fn ui(frame: &mut Frame, pet: &Pet, config: &Config, theme: &Theme, view: &View) {
+    if let Some((farewell, prompt)) = farewell_text(pet, config, view.ascii) {
+        let mut message = vec![Line::from(""), Line::from(farewell), Line::from("")];
+        // Explain how it came to this if it happened offline
+        if let Some(away) = view.away {
+            message.extend([Line::from(away), Line::from("")]);
+        }
+        message.push(Line::from(Span::styled(
+            prompt,
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+        let paragraph = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, frame.area());
+        return;
+    }
+
+    let main_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Main content
+            Constraint::Length(1), // Hint bar
+        ])
+        .split(frame.area());
+
+    // Hold the dream still along with the other animations
+    let tick = if view.reduced_motion { 0 } else { view.tick };
+    let lonely = pet.loneliness(config) >= WISTFUL;
+    let mut pet_art_lines = pet_art(pet, view.reaction, tick, view.ascii, lonely);
+    if let Some(decoration) = view.decoration {
+        pet_art_lines.splice(0..0, [decoration.to_string(), String::new()]);
+    }
+    let arranged = arrangement(frame.area(), view.compact);
+    if config.big_name {
+        // What's left of the pet's panel inside the margin and its borders
+        let inner = main_layout[0].width.saturating_sub(2);
+        let width = match arranged {
+            Arrangement::SideBySide => inner / 2,
+            Arrangement::Stacked => inner,
+        };
+        let mut name = banner::name_lines(&spoken(&pet.name), width.saturating_sub(2).into());
+        name.push(String::new());
+        pet_art_lines.splice(0..0, name);
+    }
+    if let Some(emote) = view.emote {
+        pet_art_lines.extend([String::new(), format!("~ {emote} ~")]);
+    } else if let Some(line) = view.chatter {
+        pet_art_lines.extend([String::new(), format!("\"{line}\"")]);
+    }
+
+    let (direction, constraints) = match arranged {
+        Arrangement::SideBySide => (
+            Direction::Horizontal,
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        ),
+        // The art keeps its height plus borders and the stats get the rest
+        Arrangement::Stacked => (
+            Direction::Vertical,
+            [
+                Constraint::Length(pet_art_lines.len() as u16 + 2),
+                Constraint::Min(0),
+            ],
+        ),
+    };
+    let top_layout = Layout::default()
+        .direction(direction)
+        .margin(1)
+        .constraints(constraints)
+        .split(main_layout[0]);
+    if !pet.is_sleeping {
+        look(&mut pet_art_lines, gaze(top_layout[0], view.cursor));
+    }
+    let pet_art = Paragraph::new(pet_art_lines.join("\n"))
+        .style(Style::default().fg(theme.art))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+
+    let pet_view = themed_block("Pet".to_string(), theme, view.ascii);
+    frame.render_widget(pet_art.block(pet_view), top_layout[0]);
+
+    let stats_view = themed_block("Stats".to_string(), theme, view.ascii);
+    let mut stats = stats_lines(pet, config, theme, view.ascii);
+    // Nobody runs away in session mode, so there's no clock to show
+    if config.mode == TimeMode::Realtime {
+        stats.push(Line::styled(
+            presence_text(view.last_activity, Utc::now()),
+            Style::default().fg(theme.hint),
+        ));
+    }
+    let lang = if view.ascii.is_on() {
+        Lang::En
+    } else {
+        config.lang
+    };
+    if let Some(pomodoro) = view.focus {
+        stats.push(Line::styled(
+            pomodoro.line(lang),
+            Style::default().fg(theme.good),
+        ));
+    }
+    if let Some(tutorial) = view.tutorial {
+        stats.extend([
+            Line::from(""),
+            Line::styled(tutorial.prompt(lang), Style::default().fg(theme.good)),
+        ]);
+    }
+    // Canvas markers are all Unicode, so ASCII mode sticks to the numbers
+    if view.radar && !view.ascii.is_on() {
+        let stats_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Percentage(50)])
+            .split(top_layout[1]);
+        frame.render_widget(stats_panel(stats, stats_view), stats_layout[0]);
+        frame.render_widget(radar_chart(pet, theme), stats_layout[1]);
+    } else {
+        frame.render_widget(stats_panel(stats, stats_view), top_layout[1]);
+    }
+
+    let hints = match critical_banner(pet, view) {
+        Some(banner) => Paragraph::new(banner).style(
+            Style::default()
+                .fg(theme.danger)
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        ),
+        None => Paragraph::new(hint_text(pet, view)).style(Style::default().fg(theme.hint)),
+    };
+    frame.render_widget(hints.alignment(Alignment::Center), main_layout[1]);
+
+    if let Some(celebration) = view.celebration {
+        let line = Line::from(celebration);
+        let width = (line.width() as u16 + 4).min(frame.area().width);
+        let area = Rect {
+            x: frame.area().width.saturating_sub(width) / 2,
+            y: frame.area().height.saturating_sub(3) / 2,
+            width,
+            height: 3.min(frame.area().height),
+        };
+        let overlay = Paragraph::new(line)
+            .style(Style::default().fg(theme.good))
+            .alignment(Alignment::Center)
+            .block(themed_block(String::new(), theme, view.ascii));
+        frame.render_widget(Clear, area);
+        frame.render_widget(overlay, area);
+    }
+}
+
+/// Long labels wrap rather than run off the edge of a narrow panel
+fn stats_panel<'a>(stats: Vec<Line<'a>>, block: Block<'a>) -> Paragraph<'a> {
+    Paragraph::new(stats)
+        .block(block)
+        .wrap(Wrap { trim: false })
+}
+
+/// The stats as a radar chart with one spoke per entry in `radar::SPOKES`
+fn radar_chart(pet: &Pet, theme: &Theme) -> impl Widget {
+    let points = radar_points(&spoke_values(pet));
+    let (axis, shape, label) = (theme.hint, theme.good, theme.text);
+    Canvas::default()
+        .block(themed_block("Radar".to_string(), theme, AsciiMode(false)))
+        // Room for the labels past the spoke ends
+        .x_bounds([-RADIUS * 1.4, RADIUS * 1.4])
+        .y_bounds([-RADIUS * 1.2, RADIUS * 1.2])
+        .paint(move |ctx| {
+            for (i, name) in SPOKES.iter().enumerate() {
+                let (x, y) = spoke_point(i, SPOKES.len(), 100);
+                ctx.draw(&canvas::Line::new(0.0, 0.0, x, y, axis));
+                ctx.print(x, y, Span::styled(*name, Style::default().fg(label)));
+            }
+            for (i, &(x1, y1)) in points.iter().enumerate() {
+                let (x2, y2) = points[(i + 1) % points.len()];
+                ctx.draw(&canvas::Line::new(x1, y1, x2, y2, shape));
+            }
+        })
+}
+
+/// With reduced motion the critical-health notice would come and go like
+/// anything else, so it becomes a steady banner for as long as it holds
+fn critical_banner(pet: &Pet, view: &View) -> Option<&'static str> {
+    if !view.reduced_motion || pet.health == 0 || pet.health >= CRITICAL_HEALTH {
+        return None;
+    }
+    Some(if view.ascii.is_on() {
+        " !! Health critical, look after your pet now !! "
+    } else {
+        " !! 健康告急, 快照顾宠物 !! "
+    })
+}
+
+/// The message and quit prompt once the pet is gone, if it is. The pet is
+/// called by its name with `name_in_messages` on, and "your pet" without
+/// it or for a name that's all blanks.
+fn farewell_text(pet: &Pet, config: &Config, ascii: AsciiMode) -> Option<(String, &'static str)> {
+    let name = spoken(&pet.name);
+    let named = config.name_in_messages && !name.trim().is_empty();
+    // A name is set apart from the Chinese that follows it, as elsewhere
+    let name = match (named, ascii.is_on()) {
+        (true, true) => name.trim().to_string(),
+        (true, false) => format!("{} ", name.trim()),
+        (false, true) => "Your pet".to_string(),
+        (false, false) => "你的宠物".to_string(),
+    };
+    let farewell = match (&pet.status, pet.health == 0, ascii.is_on()) {
+        (PetStatus::Abandoned, _, true) => {
+            format!("{name} was neglected for too long and ran away...")
+        }
+        (PetStatus::Abandoned, _, false) => format!("{name}因为被忽视太久，离家出走了..."),
+        (PetStatus::Retired, _, true) => {
+            format!("After a long and happy life, {name} has retired to the hall of fame!")
+        }
+        (PetStatus::Retired, _, false) => {
+            format!("度过了漫长而幸福的一生，{name}光荣退休，进入了名人堂!")
+        }
+        (_, true, true) => format!("{name} has passed away..."),
+        (_, true, false) => format!("{name}已经离开了..."),
+        (_, false, _) => return None,
+    };
+    let waiting = pet.status == PetStatus::Abandoned && config.on_abandon == OnAbandon::Suspend;
+    let prompt = match (waiting, ascii.is_on()) {
+        (true, true) => "Press 'q' or 'ctrl-c' to quit. It will find its way home next launch.",
+        (true, false) => "按 'q' 或 'ctrl-c' 退出，下次启动时它会自己回家。",
+        (false, true) => "Press 'q' or 'ctrl-c' to quit. A new journey begins next launch.",
+        (false, false) => "按 'q' 或 'ctrl-c' 退出，下次启动将开始新的旅程。",
+    };
+    Some((farewell, prompt))
+}
+
+/// Which way the pet's eyes point
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Gaze {
+    Left,
+    Ahead,
+    Right,
+}
+
+/// The eyes follow the cursor into the outer thirds of `panel`, and look
+/// straight ahead without one
+fn gaze(panel: Rect, cursor: Option<(u16, u16)>) -> Gaze {
+    let Some((column, _)) = cursor else {
+        return Gaze::Ahead;
+    };
+    let third = panel.width / 3;
+    if column < panel.x + third {
+        Gaze::Left
+    } else if column >= panel.right() - third {
+        Gaze::Right
+    } else {
+        Gaze::Ahead
+    }
+}
+
+/// Slide the face on the first line like `( o_o )` over to the side
+/// `gaze` points to, e.g. `(o_o  )`
+fn look(lines: &mut [String], gaze: Gaze) {
+    let Some(face) = lines
+        .iter_mut()
+        .find(|line| line.contains("( ") && line.contains(" )"))
+    else {
+        return;
+    };
+    *face = match gaze {
+        Gaze::Ahead => return,
+        Gaze::Left => face.replacen("( ", "(", 1).replacen(" )", "  )", 1),
+        Gaze::Right => face.replacen("( ", "(  ", 1).replacen(" )", ")", 1),
+    };
+}
+
+/// Pet art, most specific first: debug > reaction > sick > sleeping >
+/// `lonely` > stage and mood. A sleeping pet's dream moves along with `tick`.
+pub fn pet_art(
+    pet: &Pet,
+    reaction: Option<Reaction>,
+    tick: u64,
+    ascii: AsciiMode,
+    lonely: bool,
+) -> Vec<String> {
+    let dream: String;
+    let lines = if pet.debug_mode {
+        vec![
+            "",
+            r"     /\_/\",
+            r"     ( o_o )",
+            r"     > ^ <",
+            if ascii.is_on() {
+                "Stop poking me, I'm on vacation!"
+            } else {
+                "别再戳我了，我在休假！"
+            },
+        ]
+    } else if let Some(reaction) = reaction {
+        reaction.art(ascii)
+    } else if pet.hiding {
+        // Peeking out of a box
+        vec![
+            "",
+            r"     _______",
+            r"    | o   o |",
+            r"    |_______|",
+            if ascii.is_on() {
+                "Hiding in a box, sulking..."
+            } else {
+                "躲在箱子里生闷气..."
+            },
+        ]
+    } else if pet.status == PetStatus::Sick {
+        // Sick pet
+        vec![
+            "",
+            r"     /\_/\",
+            r"     ( x_x )",
+            r"     > ^ <",
+            if ascii.is_on() {
+                "Sick..."
+            } else {
+                "生病了..."
+            },
+        ]
+    } else if pet.is_sleeping {
+        dream = format!("               {}", Dream::for_log(&pet.log).bubble(tick));
+        vec![
+            &dream,
+            r"      /\_/\",
+            r"           ( - . - ) Zzz",
+            r"      > ^ <",
+            "",
+        ]
+    } else if lonely {
+        // Gazing at the door
+        vec![
+            "",
+            r"     /\_/\",
+            r"     ( ,_, )",
+            r"     > ^ <",
+            if ascii.is_on() {
+                "Waiting for you to come back..."
+            } else {
+                "一直在等你回来..."
+            },
+        ]
+    } else {
+        match pet.life_stage() {
+            "elderly" => {
+                // Elderly pet
+                vec!["", r"     /\_/\", r"     ( -_- )", r"     > v <", ""]
+            }
+            "adult" => {
+                // Adult pet
+                vec!["", r"     /\_/\", r"     ( ._. )", r"     > ^ <", ""]
+            }
+            _ => {
+                // Young pet
+                if pet.mood < 20 {
+                    // Sad pet
+                    vec!["", r"     /\_/\", r"     ( T.T )", r"     > ^ <", ""]
+                } else if pet.hunger > 60 {
+                    // Hungry pet
+                    vec!["", r"     /\_/\", r"     ( o_o )", r"     > ^ <", ""]
+                } else if pet.cleanliness < 40 {
+                    // Dirty pet
+                    vec!["", r"     /\_/\", r"     ( >.< )", r"     > ^ <", ""]
+                } else if pet.mood > 80 {
+                    // Happy pet
+                    vec!["", r"     /\_/\", r"     ( ^.^ )", r"     > ^ <", ""]
+                } else {
+                    // Neutral young pet
+                    vec!["", r"     /\_/\", r"     ( o.o )", r"     > ^ <", ""]
+                }
+            }
+        }
+    };
+    let mut lines: Vec<String> = lines.into_iter().map(String::from).collect();
+    if pet.messes > 0 {
+        let mess = if ascii.is_on() { "(@)" } else { "💩" };
+        lines.push(mess.repeat(pet.messes as usize));
+    }
+    if let Some(companion) = &pet.companion {
+        let face = if companion.is_neglected() {
+            "(=;_;=)"
+        } else {
+            "(=^.^=)"
+        };
+        lines.push(String::new());
+        lines.push(format!("{face} {}", companion.name));
+    }
+    lines
+}
+
+/// How long after the last key press the owner still counts as around
+const PRESENCE_WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+/// The abandonment clock. Leaving now gives the owner the full
+/// `ABANDON_AFTER`; sitting idle in front of the terminal runs the clock
+/// down from the last key press.
+fn presence_text(last_activity: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    if now.signed_duration_since(last_activity) < PRESENCE_WINDOW {
+        return format!(
+            "Away clock: here ({} if you leave)",
+            format_remaining(ABANDON_AFTER)
+        );
+    }
+    let remaining = abandonment_remaining(last_activity, now);
+    format!("Away clock: idle, {} left", format_remaining(remaining))
+}
+
+/// A duration as its two largest units, e.g. `2d 5h` or `12m`
+fn format_remaining(remaining: TimeDelta) -> String {
+    let (days, hours, minutes) = (
+        remaining.num_days(),
+        remaining.num_hours() % 24,
+        remaining.num_minutes() % 60,
+    );
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// The hint bar. A notice replaces the key hints for a few ticks.
+fn hint_text(pet: &Pet, view: &View) -> String {
+    if let Some(notice) = view.notice {
+        format!(" {notice} ")
+    } else if view.readonly && view.ascii.is_on() {
+        " [Read-only] nothing is saved and care is disabled | (q)uit | ctrl-c | ctrl-z ".to_string()
+    } else if view.readonly {
+        " [只读模式] 不会保存, 也不能照顾宠物 | (q)uit | ctrl-c | ctrl-z ".to_string()
+    } else if pet.debug_mode {
+        debug_hint()
+    } else if pet.is_sleeping && view.ascii.is_on() {
+        " Zzz... sleeping | (u) wake up | (q)uit | ctrl-c | ctrl-z ".to_string()
+    } else if pet.is_sleeping {
+        " Zzz... 睡觉中 | (u) 叫醒 | (q)uit | ctrl-c | ctrl-z ".to_string()
+    } else {
+        care_hint(pet, view.thresholds, view.ascii)
+    }
+}
+
+/// Care keys with an arrow on the action for the most urgent need, or a
+/// content mark when nothing is urgent
+fn care_hint(pet: &Pet, thresholds: &Thresholds, ascii: AsciiMode) -> String {
+    let (arrow, content) = if ascii.is_on() {
+        ("> ", ":)")
+    } else {
+        ("→ ", "♥")
+    };
+    let urgent = pet.most_urgent_need(thresholds).map(Need::action);
+    let actions: Vec<String> = [
+        (Action::Feed, "(f)eed"),
+        (Action::Snack, "s(n)ack"),
+        (Action::Wash, "(w)ash"),
+        (Action::Play, "(p)lay"),
+        (Action::Train, "(t)rain"),
+        (Action::Sleep, "(s)leep"),
+        (Action::Spa, "sp(a)"),
+        (Action::Medicine, "(m)eds"),
+    ]
+    .into_iter()
+    .map(|(action, label)| {
+        if urgent == Some(action) {
+            format!("{arrow}{label}")
+        } else {
+            label.to_string()
+        }
+    })
+    .collect();
+    let mood = if urgent.is_none() {
+        format!("{content} ")
+    } else {
+        String::new()
+    };
+    format!(" {mood}{} | (q)uit | ctrl-c | ctrl-z ", actions.join(" | "))
+}
+
+fn themed_block(title: String, theme: &Theme, ascii: AsciiMode) -> Block<'static> {
+    let block = Block::default()
+        .title(Span::styled(title, Style::default().fg(theme.title)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    if ascii.is_on() {
+        block.border_set(ASCII_BORDER)
+    } else {
+        block
+    }
+}
+
+/// Pick the good/warn/danger color for a stat
+fn level_style(theme: &Theme, danger: bool, warn: bool) -> Style {
+    let color = if danger {
+        theme.danger
+    } else if warn {
+        theme.warn
+    } else {
+        theme.good
+    };
+    Style::default().fg(color)
+}
+
+/// Doses of medicine left, and when they refill once they're all gone
+fn medicine_line(pet: &Pet, config: &Config, lang: Lang) -> String {
+    let (rules, now) = (&config.medicine, Utc::now());
+    let left = pet.doses.left(rules, now);
+    let line = format!("Medicine: {left}/{}", rules.daily_doses);
+    match pet.doses.refills_at(rules) {
+        Some(refill) if left == 0 => {
+            let wait = format_duration((refill - now).to_std().unwrap_or_default(), lang);
+            match lang {
+                Lang::En => format!("{line} (more in {wait})"),
+                Lang::Zh => format!("{line} ({wait}后补上)"),
+            }
+        }
+        _ => line,
+    }
+}
+
+fn stats_lines(pet: &Pet, config: &Config, theme: &Theme, ascii: AsciiMode) -> Vec<Line<'static>> {
+    let text = Style::default().fg(theme.text);
+    let care = pet.care_score(&config.weights);
+    let limits = &config.thresholds;
+    let lang = if ascii.is_on() { Lang::En } else { config.lang };
+    let stat = |stat, value| format_stat(stat, value, config.display_mode, lang);
+    let health = stat(Stat::Health, pet.health);
+    // Out of sight, only the owner's guess at how it's doing
+    let seen = |stat_text: String| {
+        if pet.hiding {
+            "?".to_string()
+        } else {
+            stat_text
+        }
+    };
+    let lived = Utc::now().signed_duration_since(pet.birth);
+    let health_text = match (&pet.status, ascii.is_on()) {
+        (PetStatus::Sick, false) => format!("Health: {health} (生病)"),
+        (PetStatus::Sick, true) => format!("Health: {health} (sick)"),
+        (PetStatus::Abandoned, false) => format!("Health: {health} (被遗弃)"),
+        (PetStatus::Abandoned, true) => format!("Health: {health} (abandoned)"),
+        _ => format!("Health: {health}"),
+    };
+
+    let spurt = match (pet.in_growth_spurt(config), ascii.is_on()) {
+        (false, _) => "",
+        (true, true) => " growth spurt!",
+        (true, false) => " 长身体中!",
+    };
+
+    let digesting = match (pet.digesting(Utc::now()), ascii.is_on()) {
+        (None, _) => "",
+        (Some(_), true) => " (digesting)",
+        (Some(_), false) => " (消化中)",
+    };
+
+    let mut lines = vec![
+        Line::styled(format!("Name: {}", pet.name), text),
+        Line::styled(
+            format!(
+                "Age: {} ({}, {}){spurt}",
+                pet.age,
+                pet.life_stage(),
+                format_duration(lived.to_std().unwrap_or_default(), lang)
+            ),
+            text,
+        ),
+        Line::styled(
+            health_text,
+            level_style(
+                theme,
+                pet.health < limits.health.danger,
+                pet.health < limits.health.warn,
+            ),
+        ),
+        Line::styled(
+            format!("Hunger: {}{digesting}", stat(Stat::Hunger, pet.hunger)),
+            level_style(
+                theme,
+                pet.hunger > limits.hunger.danger,
+                pet.hunger > limits.hunger.warn,
+            ),
+        ),
+        Line::styled(
+            format!(
+                "Cleanliness: {}",
+                seen(stat(Stat::Cleanliness, pet.cleanliness))
+            ),
+            level_style(
+                theme,
+                pet.cleanliness < limits.cleanliness.danger,
+                pet.cleanliness < limits.cleanliness.warn,
+            ),
+        ),
+        Line::styled(
+            format!("Mood: {}", stat(Stat::Mood, pet.mood)),
+            level_style(
+                theme,
+                pet.mood < limits.mood.danger,
+                pet.mood < limits.mood.warn,
+            ),
+        ),
+        Line::styled(
+            format!("Fatigue: {}", seen(stat(Stat::Fatigue, pet.fatigue))),
+            level_style(
+                theme,
+                pet.fatigue >= config.rules.fatigue.tired,
+                pet.fatigue > 60,
+            ),
+        ),
+        Line::styled(
+            format!(
+                "Status: {}",
+                match (pet.hiding, pet.is_sleeping) {
+                    (true, _) => "Hiding",
+                    (_, true) => "Sleeping",
+                    _ => "Awake",
+                }
+            ),
+            text,
+        ),
+        Line::styled(
+            format!("Care: {}", seen(care.to_string())),
+            level_style(theme, care < 30, care < 60),
+        ),
+        Line::styled(format!("Coins: {}", pet.coins), text),
+        Line::styled(format!("Weight: {} g", pet.weight), text),
+        Line::styled(medicine_line(pet, config, lang), text),
+    ];
+    if config.show_bond {
+        lines.push(Line::styled(
+            format!("Bond: {}", bond::heart_row(pet.bond, ascii.is_on())),
+            text,
+        ));
+    }
+    if let Some(companion) = &pet.companion {
+        lines.push(Line::styled(
+            format!(
+                "Companion: {} (Hunger: {})",
+                companion.name,
+                stat(Stat::Hunger, companion.hunger)
+            ),
+            level_style(
+                theme,
+                companion.is_neglected(),
+                companion.hunger > limits.hunger.warn,
+            ),
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ActionCooldowns;
+    use crate::favorites::{Favorites, Food};
+    use crate::history::MAX_LOG_ENTRIES;
+    use crate::pet::{COINS_PER_ACTION, Doses};
+    use crate::talk::{Topic, pool};
+    use std::collections::VecDeque;
+
+    fn key_events() -> Vec<Event> {
+        let keys = [
+            KeyCode::Char('f'),
+            KeyCode::Char('p'),
+            KeyCode::Char('d'),
+            KeyCode::Char('e'),
+            KeyCode::Char('b'),
+            KeyCode::Char('u'),
+            KeyCode::Char('g'),
+            KeyCode::Char('h'),
+            KeyCode::Esc,
+            KeyCode::Char('w'),
+            KeyCode::Char('s'),
+            KeyCode::Char('f'),
+        ];
+        keys.into_iter()
+            .map(|code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+            .collect()
+    }
+
+    async fn drive(input: &mut impl InputSource, count: usize) -> (Pet, Vec<KeyOutcome>) {
+        let config = Config::default();
+        let options = UiOptions::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        // Nothing either pet gets is a favorite
+        pet.favorites = Favorites {
+            food: Food::Cookies,
+            activity: Action::Train,
+        };
+        pet.hunger = 50;
+        pet.cleanliness = 30;
+        pet.mood = 40;
+
+        let mut outcomes = Vec::new();
+        let mut session = Session::new(&mut pet, &config, &options);
+        for _ in 0..count {
+            if let Event::Key(key) = input.next_event().await.unwrap() {
+                outcomes.push(session.on_key(key));
+            }
+        }
+        (pet, outcomes)
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_is_identical_for_each_input_source() {
+        let events = key_events();
+
+        let mut stream = StreamInput {
+            stream: futures_util::stream::iter(events.clone().into_iter().map(Ok)),
+        };
+        let mut queue: VecDeque<Event> = events.clone().into();
+        let mut polled = 0;
+        let mut poll = PollInput {
+            poll: || {
+                // Only every other poll has an event ready
+                polled += 1;
+                Ok(if polled % 2 == 0 {
+                    queue.pop_front()
+                } else {
+                    None
+                })
+            },
+        };
+
+        let (streamed_pet, streamed_outcomes) = drive(&mut stream, events.len()).await;
+        let (polled_pet, polled_outcomes) = drive(&mut poll, events.len()).await;
+
+        assert_eq!(streamed_outcomes, polled_outcomes);
+        assert_eq!(
+            (
+                streamed_pet.hunger,
+                streamed_pet.mood,
+                streamed_pet.cleanliness
+            ),
+            (polled_pet.hunger, polled_pet.mood, polled_pet.cleanliness)
+        );
+        assert_eq!(streamed_pet.is_sleeping, polled_pet.is_sleeping);
+        let events = |pet: &Pet| pet.log.iter().map(|e| e.event).collect::<Vec<_>>();
+        assert_eq!(events(&streamed_pet), events(&polled_pet));
+
+        // Feed, play, debug +10 hunger, wash, then asleep so the last feed is ignored
+        assert_eq!(streamed_pet.hunger, 45);
+        assert_eq!(streamed_pet.cleanliness, 100);
+        assert!(streamed_pet.is_sleeping);
+        assert!(!streamed_pet.debug_mode);
+    }
+
+    #[test]
+    fn test_care_hint_points_at_urgent_need() {
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hunger, pet.cleanliness, pet.mood) = (0, 100, 100);
+        assert!(care_hint(&pet, &Thresholds::default(), AsciiMode(false)).starts_with(" ♥ (f)eed"));
+
+        pet.cleanliness = 25;
+        let hint = care_hint(&pet, &Thresholds::default(), AsciiMode(true));
+        assert!(hint.contains("> (w)ash"));
+        assert!(!hint.contains(":)"));
+    }
+
+    #[test]
+    fn test_presence_text() {
+        let now = Utc::now();
+        assert_eq!(
+            presence_text(now, now),
+            "Away clock: here (3d 0h if you leave)"
+        );
+        assert_eq!(
+            presence_text(now - TimeDelta::hours(5), now),
+            "Away clock: idle, 2d 19h left"
+        );
+        assert_eq!(
+            presence_text(now - TimeDelta::days(3), now),
+            "Away clock: idle, 0m left"
+        );
+        assert_eq!(format_remaining(TimeDelta::minutes(125)), "2h 5m");
+    }
+
+    #[test]
+    fn test_debug_keymap() {
+        assert_eq!(
+            debug_edit(KeyCode::Char('h')),
+            Some(DebugEdit::Adjust(DebugStat::Hunger, 10))
+        );
+        assert_eq!(
+            debug_edit(KeyCode::Char('l')),
+            Some(DebugEdit::Adjust(DebugStat::Health, -10))
+        );
+        assert_eq!(
+            debug_edit(KeyCode::Char('a')),
+            Some(DebugEdit::Adjust(DebugStat::Age, 1))
+        );
+        assert_eq!(debug_edit(KeyCode::Char('x')), Some(DebugEdit::ToggleSick));
+        assert_eq!(debug_edit(KeyCode::Char('f')), None);
+        assert_eq!(debug_edit(KeyCode::Esc), None);
+
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 5;
+        DebugEdit::Adjust(DebugStat::Health, -10).apply(&mut pet);
+        DebugEdit::Adjust(DebugStat::Age, -1).apply(&mut pet);
+        DebugEdit::ToggleSick.apply(&mut pet);
+        assert_eq!((pet.health, pet.age), (0, 0));
+        assert_eq!(pet.status, PetStatus::Sick);
+        DebugEdit::ToggleSick.apply(&mut pet);
+        assert_eq!(pet.status, PetStatus::Alive);
+
+        let hint = debug_hint();
+        for (up, down, stat) in DEBUG_KEYS {
+            assert!(hint.contains(&format!("({up}/{down}) {}", stat.label())));
+        }
+    }
+
+    #[test]
+    fn test_emote_shows_briefly_as_a_trend_starts() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.birth = Utc::now() - TimeDelta::days(30);
+        pet.hunger = config.thresholds.hunger.warn - emote::NEAR;
+        let options = UiOptions::default();
+        let mut session = Session::new(&mut pet, &config, &options);
+
+        // Needs run down every third second
+        for _ in 0..3 {
+            session.on_tick();
+        }
+        assert_eq!(session.view().emote, Some("有点饿了..."));
+        for _ in 0..EMOTE_TTL {
+            session.on_tick();
+        }
+        assert_eq!(session.view().emote, None);
+        // Still getting hungrier, but that's been said
+        for _ in 0..3 {
+            session.on_tick();
+        }
+        assert_eq!(session.view().emote, None);
+
+        let quiet = Config {
+            pet_emotes: false,
+            ..Config::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.birth = Utc::now() - TimeDelta::days(30);
+        pet.hunger = quiet.thresholds.hunger.warn;
+        let mut session = Session::new(&mut pet, &quiet, &options);
+        for _ in 0..3 {
+            session.on_tick();
+        }
+        assert_eq!(session.view().emote, None);
+    }
+
+    #[test]
+    fn test_reduced_motion_holds_still() {
+        let feed = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE);
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+
+        let options = UiOptions::default();
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(feed);
+        assert!(session.view().reaction.is_some());
+
+        let options = UiOptions {
+            reduced_motion: true,
+            ..UiOptions::default()
+        };
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(feed);
+        assert!(session.view().reaction.is_none());
+        let first = session.view().chatter;
+        session.seconds_elapsed += crate::talk::ROTATE_SECONDS;
+        assert_eq!(session.view().chatter, first);
+
+        session.pet.health = CRITICAL_HEALTH - 1;
+        assert!(critical_banner(session.pet, &session.view()).is_some());
+        session.pet.health = CRITICAL_HEALTH;
+        assert!(critical_banner(session.pet, &session.view()).is_none());
+    }
+
+    #[test]
+    fn test_away_summary_greets_the_session() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let options = UiOptions {
+            ascii: AsciiMode(true),
+            away: AwayDiff {
+                hunger: 40,
+                ..AwayDiff::default()
+            },
+            ..UiOptions::default()
+        };
+        let session = Session::new(&mut pet, &config, &options);
+        assert_eq!(session.view().notice, Some("While away: hunger +40"));
+        assert_eq!(session.view().away, Some("While away: hunger +40"));
+
+        let options = UiOptions::default();
+        let session = Session::new(&mut pet, &config, &options);
+        assert_eq!(session.view().notice, None);
+    }
+
+    #[test]
+    fn test_focus_session_rewards_finished_work() {
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.mood = 50;
+        let options = UiOptions {
+            focus: Some((2, 60)),
+            ..UiOptions::default()
+        };
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_tick();
+        let mood = session.pet.mood;
+        session.on_tick();
+        assert_eq!(session.pet.mood, mood + config.focus.mood);
+        assert!(session.notice.is_some());
+        assert_eq!(session.view().focus.unwrap().phase, Phase::Break);
+
+        // Quitting during the break costs nothing
+        session.end_focus();
+        assert_eq!(session.pet.mood, mood + config.focus.mood);
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        let mood = session.pet.mood;
+        session.end_focus();
+        assert_eq!(session.pet.mood, mood - config.focus.quit_mood);
+    }
+
+    #[test]
+    fn test_inline_mode_keeps_the_main_screen() {
+        let commands = |inline, mouse| {
+            let (mut enter, mut leave) = (Vec::new(), Vec::new());
+            enter_screen(&mut enter, inline, mouse).unwrap();
+            leave_screen(&mut leave, inline, mouse).unwrap();
+            (enter, leave)
+        };
+        let (enter, leave) = commands(false, false);
+        assert!(!enter.is_empty() && !leave.is_empty());
+        assert_eq!(commands(true, false), (Vec::new(), Vec::new()));
+
+        // Mouse capture is still switched on and off
+        let (enter, leave) = commands(true, true);
+        let (mut capture, mut release) = (Vec::new(), Vec::new());
+        capture.queue(EnableMouseCapture).unwrap();
+        release.queue(DisableMouseCapture).unwrap();
+        assert_eq!((enter, leave), (capture, release));
+    }
+
+    #[test]
+    fn test_growth_spurt_shows_by_the_age() {
+        let (mut config, theme) = (Config::default(), Theme::default());
+        config.growth_spurts.enabled = true;
+        let mut pet = Pet::new("TestPet".to_string());
+        let age_line = |pet: &Pet, config: &Config| {
+            stats_lines(pet, config, &theme, AsciiMode(true))[1].to_string()
+        };
+        assert!(!age_line(&pet, &config).contains("growth spurt"));
+        pet.age = config.growth_spurts.every;
+        assert!(age_line(&pet, &config).ends_with(") growth spurt!"));
+        assert!(!age_line(&pet, &Config::default()).contains("growth spurt"));
+    }
+
+    #[test]
+    fn test_hiding_pet_is_shown_apart_from_abandoned() {
+        let (config, theme, options) = (Config::default(), Theme::default(), UiOptions::default());
+        let mut pet = Pet::new("TestPet".to_string());
+        (pet.hiding, pet.cleanliness) = (true, 30);
+        let stats: Vec<String> = stats_lines(&pet, &config, &theme, AsciiMode(true))
+            .iter()
+            .map(Line::to_string)
+            .collect();
+        assert!(stats.contains(&"Status: Hiding".to_string()));
+        assert!(stats.contains(&"Cleanliness: ?".to_string()));
+        assert!(stats.contains(&"Health: 100".to_string()));
+        assert!(
+            pet_art(&pet, None, 0, AsciiMode(true), false)
+                .contains(&"Hiding in a box, sulking...".to_string())
+        );
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "TestPet <3 100 (hiding)"
+        );
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE));
+        assert_eq!(session.pet.cleanliness, 30);
+    }
+
+    #[test]
+    fn test_title_shows_name_health_and_state() {
+        let mut pet = Pet::new("Petty".to_string());
+        pet.health = 82;
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(false)),
+            "Petty ❤ 82"
+        );
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "Petty <3 82"
+        );
+
+        pet.is_sleeping = true;
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "Petty <3 82 Zzz"
+        );
+        (pet.is_sleeping, pet.status, pet.health) = (false, PetStatus::Sick, 15);
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(false)),
+            "Petty ❤ 15 (生病)"
+        );
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "Petty <3 15 (sick)"
+        );
+
+        pet.health = 0;
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "Petty (passed away)"
+        );
+        pet.status = PetStatus::Abandoned;
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "Petty (ran away)"
+        );
+        let english = Config {
+            lang: Lang::En,
+            ..Config::default()
+        };
+        assert_eq!(
+            title_text(&pet, &english, AsciiMode(false)),
+            "Petty (ran away)"
+        );
+    }
+
+    #[test]
+    fn test_critical_notification_speaks_the_configured_language() {
+        let mut pet = Pet::new("Petty".to_string());
+        pet.health = 12;
+        let english = Config {
+            lang: Lang::En,
+            ..Config::default()
+        };
+        assert_eq!(
+            critical_notification(&pet, &english, AsciiMode(false)),
+            (
+                "Petty's health is critical!".to_string(),
+                "Only 12 health left; come and look after it.".to_string()
+            )
+        );
+        let (summary, _) = critical_notification(&pet, &Config::default(), AsciiMode(false));
+        assert_eq!(summary, "Petty 的健康告急!");
+        // ASCII mode stays in English whatever the language
+        let (summary, _) = critical_notification(&pet, &Config::default(), AsciiMode(true));
+        assert_eq!(summary, "Petty's health is critical!");
+    }
+
+    #[test]
+    fn test_messages_follow_the_configured_language() {
+        let pet = Pet::new("Petty".to_string());
+        let (english, chinese) = (
+            Config {
+                lang: Lang::En,
+                ..Config::default()
+            },
+            Config::default(),
+        );
+        let waste =
+            |config: &Config, ascii: bool| waste_text(&pet, Action::Feed, config, AsciiMode(ascii));
+        assert_eq!(waste(&english, false), "Petty is already full");
+        assert_eq!(waste(&chinese, false), "Petty 已经吃饱了");
+        // ASCII mode stays in English whatever the language
+        assert_eq!(waste(&chinese, true), "Petty is already full");
+
+        assert_eq!(
+            focus_text(&pet, Phase::Break, &english, AsciiMode(false)),
+            "Break's over, back to work!"
+        );
+        assert_eq!(
+            focus_text(&pet, Phase::Break, &chinese, AsciiMode(false)),
+            "休息结束, 继续专注吧!"
+        );
+        assert_eq!(
+            overstimulated_text(&pet, &english, AsciiMode(false)),
+            "Petty is getting overwhelmed. Give it a moment."
+        );
+        assert_eq!(
+            bored_text(&pet, &english, AsciiMode(false)),
+            "Petty is bored of the same old thing. Try something else."
+        );
+
+        // Every notice in a tick, and on a key press, speaks it too
+        let report = TickReport {
+            events: vec![TickEvent::GotSick],
+            ..TickReport::default()
+        };
+        let notice = |config: &Config| tick_notice(&pet, &report, config, AsciiMode(false));
+        assert_eq!(notice(&english).as_deref(), Some("Petty got sick!"));
+        assert_eq!(notice(&chinese).as_deref(), Some("Petty 生病了!"));
+        let (options, mut asleep) = (UiOptions::default(), pet.clone());
+        asleep.is_sleeping = true;
+        let mut session = Session::new(&mut asleep, &english, &options);
+        session.on_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        let (notice, _) = session.notice.unwrap();
+        assert_eq!(notice, "Shh, it's sleeping. Press 'u' to wake it up.");
+    }
+
+    #[test]
+    fn test_farewell_calls_the_pet_by_name() {
+        let config = Config::default();
+        let farewell = |pet: &Pet, config: &Config, ascii: bool| {
+            farewell_text(pet, config, AsciiMode(ascii)).map(|(text, _)| text)
+        };
+        let mut pet = Pet::new("Petty".to_string());
+        assert_eq!(farewell(&pet, &config, true), None);
+
+        pet.status = PetStatus::Abandoned;
+        assert_eq!(
+            farewell(&pet, &config, true).unwrap(),
+            "Petty was neglected for too long and ran away..."
+        );
+        assert_eq!(
+            farewell(&pet, &config, false).unwrap(),
+            "Petty 因为被忽视太久，离家出走了..."
+        );
+        pet.status = PetStatus::Retired;
+        assert!(
+            farewell(&pet, &config, true)
+                .unwrap()
+                .contains(", Petty has retired")
+        );
+        (pet.status, pet.health) = (PetStatus::Alive, 0);
+        assert_eq!(
+            farewell(&pet, &config, true).unwrap(),
+            "Petty has passed away..."
+        );
+        assert_eq!(
+            farewell(&pet, &config, false).unwrap(),
+            "Petty 已经离开了..."
+        );
+
+        let unnamed = Config {
+            name_in_messages: false,
+            ..Config::default()
+        };
+        assert_eq!(
+            farewell(&pet, &unnamed, true).unwrap(),
+            "Your pet has passed away..."
+        );
+        assert_eq!(
+            farewell(&pet, &unnamed, false).unwrap(),
+            "你的宠物已经离开了..."
+        );
+    }
+
+    #[test]
+    fn test_names_that_would_break_a_message_are_tamed() {
+        assert!(matches!(spoken("Mochi"), Cow::Borrowed("Mochi")));
+        assert_eq!(spoken("Mo\nchi\x1b]0;pwned\x07"), "Mo chi ]0;pwned ");
+        // Braces and percent signs are only text, never a template
+        assert_eq!(spoken("{name} 100%"), "{name} 100%");
+
+        let mut pet = Pet::new("{0}\n{name}".to_string());
+        pet.health = 0;
+        let (farewell, _) = farewell_text(&pet, &Config::default(), AsciiMode(true)).unwrap();
+        assert_eq!(farewell, "{0} {name} has passed away...");
+        assert_eq!(
+            title_text(&pet, &Config::default(), AsciiMode(true)),
+            "{0} {name} (passed away)"
+        );
+        assert_eq!(
+            bored_text(&pet, &Config::default(), AsciiMode(true)),
+            "{0} {name} is bored of the same old thing. Try something else."
+        );
+        // A name of only blanks falls back to "your pet"
+        pet.name = "\t\r\n".to_string();
+        let (farewell, _) = farewell_text(&pet, &Config::default(), AsciiMode(true)).unwrap();
+        assert_eq!(farewell, "Your pet has passed away...");
+    }
+
+    #[test]
+    fn test_frantic_care_is_damped_when_enabled() {
+        let play = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+        let mut config = Config::default();
+        config.cooldowns.play = 0.0;
+        let options = UiOptions::default();
+        let mood_after_playing = |config: &Config| {
+            let mut pet = Pet::new("TestPet".to_string());
+            (pet.mood, pet.hunger) = (0, 0);
+            // Keep the favorite-activity bonus out of the comparison
+            pet.favorites.activity = Action::Train;
+            let mut session = Session::new(&mut pet, config, &options);
+            for _ in 0..6 {
+                session.on_key(play);
+            }
+            (session.pet.mood, session.pet.hunger)
+        };
+        let (calm_mood, calm_hunger) = mood_after_playing(&config);
+        config.overstimulation.enabled = true;
+        let (frantic_mood, frantic_hunger) = mood_after_playing(&config);
+        assert!(frantic_mood < calm_mood, "{frantic_mood} vs {calm_mood}");
+        // Playing still costs the same hunger
+        assert_eq!(frantic_hunger, calm_hunger);
+    }
+
+    #[test]
+    fn test_tutorial_follows_the_keys_pressed() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let config = Config::default();
+        let options = UiOptions {
+            tutorial: true,
+            ..UiOptions::default()
+        };
+        let mut pet = crate::tutorial::practice_pet();
+        let mut session = Session::new(&mut pet, &config, &options);
+        let step = |session: &Session| session.tutorial.as_ref().unwrap().expected();
+
+        // Playing first doesn't count; a snack does
+        session.on_key(key('p'));
+        assert_eq!(step(&session), Some(Action::Snack));
+        session.on_key(key(SNACK_KEY));
+        assert_eq!(step(&session), Some(Action::Feed));
+        // Nor does a key that isn't a care action
+        session.on_key(key('h'));
+        assert_eq!(step(&session), Some(Action::Feed));
+        session.on_key(key('f'));
+        assert_eq!(step(&session), Some(Action::Wash));
+    }
+
+    #[test]
+    fn test_medicine_runs_out_for_the_session() {
+        let medicine = KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+        let mut config = Config::default();
+        config.medicine.cooldown_minutes = 0;
+        let options = UiOptions::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 10;
+        let mut session = Session::new(&mut pet, &config, &options);
+        for _ in 0..config.medicine.daily_doses {
+            session.on_key(medicine);
+        }
+        let health = session.pet.health;
+        assert_eq!(health, 10 + 3 * config.medicine.heal);
+        session.on_key(medicine);
+        assert_eq!(session.pet.health, health);
+        assert!(session.notice.as_ref().unwrap().0.contains("药吃完了"));
+        assert!(
+            medicine_line(session.pet, &config, Lang::En).starts_with("Medicine: 0/3 (more in")
+        );
+    }
+
+    #[test]
+    fn test_any_key_keeps_the_pet_company() {
+        let mut config = Config::default();
+        config.loneliness.enabled = true;
+        let options = UiOptions::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.idle_seconds = 3600;
+        assert!(pet.loneliness(&config) >= WISTFUL);
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert_eq!(session.pet.loneliness(&config), 0.0);
+    }
+
+    #[test]
+    fn test_long_session_stays_within_its_caps() {
+        // Let every press through so the log fills up too
+        let config = Config {
+            cooldowns: ActionCooldowns {
+                feed: 0.0,
+                wash: 0.0,
+                play: 0.0,
+                train: 0.0,
+                sleep: 0.0,
+                spa: 0.0,
+            },
+            ..Config::default()
+        };
+        let options = UiOptions::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        let mut session = Session::new(&mut pet, &config, &options);
+        let keys = "fwptsuamgkr?xyzdebu中";
+        for (n, c) in keys.chars().cycle().take(100_000).enumerate() {
+            session.on_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            assert!(session.input_buffer.chars().count() <= MAX_TYPED);
+            if n % 50 == 0 {
+                session.on_tick();
+            }
+        }
+        assert!(session.pet.log.len() <= MAX_LOG_ENTRIES);
+        assert!(session.pet.recorded > MAX_LOG_ENTRIES as u64);
+        // Typed commands still work after all that
+        for c in "debug".chars() {
+            session.on_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert!(session.pet.debug_mode);
+    }
+
+    #[test]
+    fn test_typing_debug_does_not_wake_the_pet() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let (config, options) = (Config::default(), UiOptions::default());
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.is_sleeping = true;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        for c in "debug".chars() {
+            session.on_key(key(c));
+        }
+        assert!(session.pet.debug_mode);
+        assert!(session.pet.is_sleeping);
+
+        session.on_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        // On its own the key still wakes it
+        session.on_key(key(WAKE_KEY));
+        assert!(!session.pet.is_sleeping);
+    }
+
+    #[test]
+    fn test_view_keys_work_while_asleep() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let (config, options) = (Config::default(), UiOptions::default());
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.is_sleeping = true;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        assert!(!session.radar);
+        assert_eq!(session.on_key(key(RADAR_KEY)), KeyOutcome::Continue);
+        assert!(session.radar);
+        assert_eq!(session.on_key(key(GALLERY_KEY)), KeyOutcome::ShowGallery);
+        assert_eq!(session.on_key(key(SKILLS_KEY)), KeyOutcome::ShowSkills);
+    }
+
+    #[test]
+    fn test_arrangement_follows_width() {
+        let area = |width| Rect::new(0, 0, width, 30);
+        assert_eq!(
+            arrangement(area(COMPACT_WIDTH - 1), false),
+            Arrangement::Stacked
+        );
+        assert_eq!(
+            arrangement(area(COMPACT_WIDTH), false),
+            Arrangement::SideBySide
+        );
+        assert_eq!(
+            arrangement(area(COMPACT_WIDTH + 1), false),
+            Arrangement::SideBySide
+        );
+        assert_eq!(
+            arrangement(area(COMPACT_WIDTH + 1), true),
+            Arrangement::Stacked
+        );
+    }
+
+    /// The rows of a `width` by `height` screen showing `pet` in ASCII
+    fn draw(pet: &Pet, config: &Config, width: u16, height: u16) -> Vec<String> {
+        let theme = config.active_theme();
+        let mut terminal =
+            Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        let view = View {
+            reaction: None,
+            notice: None,
+            celebration: None,
+            readonly: false,
+            ascii: AsciiMode(true),
+            last_activity: Utc::now(),
+            radar: false,
+            chatter: None,
+            emote: None,
+            reduced_motion: false,
+            away: None,
+            compact: false,
+            tick: 0,
+            thresholds: &config.thresholds,
+            cursor: None,
+            focus: None,
+            tutorial: None,
+            decoration: None,
+        };
+        terminal
+            .draw(|frame| ui(frame, pet, config, &theme, &view))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stacked_layout_keeps_stats_readable() {
+        let pet = Pet::new("TestPet".to_string());
+        let rows = draw(&pet, &Config::default(), 30, 40);
+        let pet_row = rows.iter().position(|row| row.contains("Pet")).unwrap();
+        let stats_row = rows.iter().position(|row| row.contains("Stats")).unwrap();
+        assert!(pet_row < stats_row);
+        for label in ["Name: TestPet", "Hunger: 0", "Cleanliness: 100", "Care:"] {
+            assert!(rows.iter().any(|row| row.contains(label)), "{label}");
+        }
+    }
+
+    #[test]
+    fn test_big_name_heads_the_pet_when_it_fits() {
+        let config = Config {
+            big_name: true,
+            ..Config::default()
+        };
+        let pet = Pet::new("Mochi".to_string());
+        let big = banner::big_text("Mochi", 80).unwrap();
+        let rows = draw(&pet, &config, 30, 40);
+        let top = rows.iter().position(|row| row.contains(&big[1])).unwrap();
+        assert!(rows[top + 1].contains(&big[2]));
+        assert!(rows.iter().position(|row| row.contains("Stats")).unwrap() > top);
+
+        // Too narrow for big letters, so the plain name stands in
+        let rows = draw(&pet, &config, big[0].len() as u16 + 3, 40);
+        assert!(!rows.iter().any(|row| row.contains(&big[1])));
+        assert!(
+            rows.iter()
+                .any(|row| row.trim_matches([' ', '|']) == "Mochi")
+        );
+    }
+
+    #[test]
+    fn test_only_the_wake_key_wakes_a_sleeping_pet() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let config = Config::default();
+        let options = UiOptions {
+            ascii: AsciiMode(true),
+            ..UiOptions::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 50;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(key('s'));
+        assert!(session.pet.is_sleeping);
+        assert!(hint_text(session.pet, &session.view()).contains("(u) wake up"));
+
+        for c in ['s', 'f'] {
+            session.on_key(key(c));
+            assert!(session.pet.is_sleeping);
+            let (notice, _) = session.notice.take().unwrap();
+            assert!(notice.contains("Press 'u'"));
+        }
+        assert_eq!(session.pet.hunger, 50);
+
+        session.on_key(key(WAKE_KEY));
+        assert!(!session.pet.is_sleeping);
+        assert!(session.notice.is_none());
+        // The blocked feed didn't start its cooldown
+        session.on_key(key('f'));
+        assert!(session.pet.hunger < 50);
+        assert!(session.notice.is_none());
+        // Waking an awake pet does nothing
+        session.on_key(key(WAKE_KEY));
+        assert!(!session.pet.is_sleeping);
+    }
+
+    #[test]
+    fn test_spa_key_costs_coins_and_cools_down() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let config = Config::default();
+        let options = UiOptions {
+            ascii: AsciiMode(true),
+            ..UiOptions::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.cleanliness = 10;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(key(SPA_KEY));
+        assert_eq!(session.pet.cleanliness, 10);
+        let (notice, _) = session.notice.take().unwrap();
+        assert!(notice.contains("costs 10 coins"), "{notice}");
+
+        session.pet.coins = 2 * SPA_COST;
+        session.on_key(key(SPA_KEY));
+        assert_eq!(
+            (session.pet.cleanliness, session.pet.coins),
+            (100, SPA_COST)
+        );
+        session.pet.cleanliness = 10;
+        session.on_key(key(SPA_KEY));
+        assert_eq!(session.pet.cleanliness, 10);
+        assert_eq!(session.notice.take().unwrap().0, "Not yet!");
+    }
+
+    #[test]
+    fn test_eyes_follow_the_cursor() {
+        let panel = Rect::new(10, 0, 30, 10);
+        assert_eq!(gaze(panel, None), Gaze::Ahead);
+        assert_eq!(gaze(panel, Some((0, 5))), Gaze::Left);
+        assert_eq!(gaze(panel, Some((19, 5))), Gaze::Left);
+        assert_eq!(gaze(panel, Some((20, 5))), Gaze::Ahead);
+        assert_eq!(gaze(panel, Some((29, 5))), Gaze::Ahead);
+        assert_eq!(gaze(panel, Some((30, 5))), Gaze::Right);
+        assert_eq!(gaze(panel, Some((80, 0))), Gaze::Right);
+
+        let face = |gaze| {
+            let mut lines = vec![r"     /\_/\".to_string(), r"     ( o_o )".to_string()];
+            look(&mut lines, gaze);
+            lines.remove(1)
+        };
+        assert_eq!(face(Gaze::Ahead), r"     ( o_o )");
+        assert_eq!(face(Gaze::Left), r"     (o_o  )");
+        assert_eq!(face(Gaze::Right), r"     (  o_o)");
+    }
+
+    #[test]
+    fn test_stats_are_colored_by_the_thresholds() {
+        let mut config = Config::default();
+        let theme = Theme::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.hunger = 45;
+        let hunger_style = |config: &Config| {
+            stats_lines(&pet, config, &theme, AsciiMode(true))
+                .into_iter()
+                .find(|line| line.to_string().starts_with("Hunger"))
+                .unwrap()
+                .style
+        };
+        assert_eq!(hunger_style(&config).fg, Some(theme.good));
+
+        config.thresholds.hunger.warn = 30;
+        config.thresholds.hunger.danger = 50;
+        assert_eq!(hunger_style(&config).fg, Some(theme.warn));
+        config.thresholds.hunger.danger = 40;
+        assert_eq!(hunger_style(&config).fg, Some(theme.danger));
+    }
+
+    #[test]
+    fn test_wasteful_care_is_pointed_out_or_skipped() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut config = Config::default();
+        let options = UiOptions {
+            ascii: AsciiMode(true),
+            ..UiOptions::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.health = 50;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(key('f'));
+        assert_eq!(session.notice.take().unwrap().0, "TestPet is already full");
+        assert!(session.pet.health > 50);
+
+        config.skip_wasteful = true;
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(key('w'));
+        assert_eq!(session.notice.take().unwrap().0, "TestPet is already clean");
+        assert_eq!(session.pet.coins, COINS_PER_ACTION);
+    }
+
+    #[test]
+    fn test_level_up_is_announced() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let config = Config::default();
+        let options = UiOptions {
+            ascii: AsciiMode(true),
+            ..UiOptions::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.skills.agility = crate::skills::xp_for_level(1) - crate::skills::TRAIN_XP;
+        pet.skills.intelligence = pet.skills.agility;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        session.on_key(key('t'));
+        let (notice, _) = session.notice.as_ref().unwrap();
+        assert_eq!(notice, "Agility reached level 1!");
+    }
+
+    #[test]
+    fn test_save_on_action() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), crate::state::StateFormat::Json);
+        let feed = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE);
+        let options = UiOptions {
+            state_file: Some(file.clone()),
+            ..UiOptions::default()
+        };
+        let mut pet = Pet::new("TestPet".to_string());
+
+        let config = Config::default();
+        let mut session = Session::new(&mut pet, &config, &options);
+        assert_eq!(session.on_key(feed), KeyOutcome::Continue);
+
+        // Done digesting, so it eats again
+        pet.digested_at = None;
+        let config = Config {
+            save_on_action: true,
+            ..Config::default()
+        };
+        let mut session = Session::new(&mut pet, &config, &options);
+        assert_eq!(session.on_key(feed), KeyOutcome::Save);
+        session.save().unwrap();
+        assert!(file.path.exists());
+
+        pet.status = PetStatus::Abandoned;
+        assert!(!should_save_after_action(&config, &options, &pet));
+        pet.status = PetStatus::Alive;
+        pet.health = 0;
+        assert!(!should_save_after_action(&config, &options, &pet));
+    }
+
+    #[test]
+    fn test_autosave_keeps_crash_recovery_current() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), crate::state::StateFormat::Json);
+        let options = UiOptions {
+            state_file: Some(file.clone()),
+            ..UiOptions::default()
+        };
+        let config = Config::default();
+        let mut pet = Pet::new("TestPet".to_string());
+        pet.last_seen = Utc::now() - TimeDelta::hours(1);
+        pet.birth = pet.last_seen;
+
+        let mut session = Session::new(&mut pet, &config, &options);
+        for _ in 0..config.autosave_seconds {
+            session.on_tick();
+            if should_autosave(&config, &options, session.pet, session.seconds_elapsed) {
+                session.save().unwrap();
+            }
+        }
+
+        // After a crash, catch-up only covers the time since the autosave
+        // instead of replaying the hour that was already played live
+        let recovered = crate::state::load_state(&file).unwrap();
+        assert!(Utc::now().signed_duration_since(recovered.last_seen) < TimeDelta::seconds(5));
+        assert_eq!(recovered.hunger, pet.hunger);
+        assert!(pet.hunger > 0);
+
+        assert!(!should_autosave(
+            &config,
+            &options,
+            &pet,
+            config.autosave_seconds + 1
+        ));
+        let readonly = UiOptions {
+            readonly: true,
+            ..options
+        };
+        assert!(!should_autosave(
+            &config,
+            &readonly,
+            &pet,
+            config.autosave_seconds
+        ));
+    }
+
+    #[test]
+    fn test_ascii_mode_is_pure_ascii() {
+        let ascii = AsciiMode(true);
+        let config = Config::default();
+        let theme = Theme::default();
+        let mut texts: Vec<String> = Vec::new();
+
+        let mut pets = Vec::new();
+        for (age, mood, hunger, cleanliness) in [
+            (0, 10, 0, 100),
+            (0, 50, 70, 100),
+            (0, 50, 0, 30),
+            (0, 90, 0, 100),
+            (0, 50, 0, 100),
+            (30, 50, 0, 100),
+            (60, 50, 0, 100),
+        ] {
+            let mut pet = Pet::new("TestPet".to_string());
+            (pet.age, pet.mood, pet.hunger, pet.cleanliness) = (age, mood, hunger, cleanliness);
+            pet.messes = 2;
+            pets.push(pet);
+        }
+        let mut debug = Pet::new("TestPet".to_string());
+        debug.debug_mode = true;
+        let mut sick = Pet::new("TestPet".to_string());
+        sick.status = PetStatus::Sick;
+        let mut sleeping = Pet::new("TestPet".to_string());
+        sleeping.is_sleeping = true;
+        let mut abandoned = Pet::new("TestPet".to_string());
+        abandoned.status = PetStatus::Abandoned;
+        abandoned.companion = Some(crate::pet::Companion::new("Buddy".to_string()));
+        let mut dead = Pet::new("TestPet".to_string());
+        dead.health = 0;
+        let mut retired = Pet::new("TestPet".to_string());
+        retired.status = PetStatus::Retired;
+        let mut hiding = Pet::new("TestPet".to_string());
+        hiding.hiding = true;
+        let mut full = Pet::new("TestPet".to_string());
+        full.digested_at = Some(Utc::now() + TimeDelta::hours(1));
+        pets.extend([
+            debug, sick, sleeping, abandoned, dead, retired, hiding, full,
+        ]);
+
+        for pet in &pets {
+            texts.extend(pet_art(pet, None, 0, ascii, false));
+            texts.extend(pet_art(pet, None, 0, ascii, true));
+            texts.extend(
+                stats_lines(pet, &config, &theme, ascii)
+                    .iter()
+                    .map(Line::to_string),
+            );
+            for (on_abandon, name_in_messages) in
+                [(OnAbandon::Delete, true), (OnAbandon::Suspend, false)]
+            {
+                let config = Config {
+                    on_abandon,
+                    name_in_messages,
+                    ..Config::default()
+                };
+                if let Some((farewell, prompt)) = farewell_text(pet, &config, ascii) {
+                    texts.extend([farewell, prompt.to_string()]);
+                }
+            }
+            for readonly in [false, true] {
+                let view = View {
+                    reaction: None,
+                    notice: None,
+                    celebration: None,
+                    readonly,
+                    ascii,
+                    last_activity: Utc::now(),
+                    radar: false,
+                    chatter: None,
+                    emote: None,
+                    reduced_motion: true,
+                    away: None,
+                    compact: false,
+                    tick: 0,
+                    thresholds: &config.thresholds,
+                    cursor: None,
+                    focus: None,
+                    tutorial: None,
+                    decoration: None,
+                };
+                texts.extend(critical_banner(pet, &view).map(String::from));
+                texts.push(hint_text(pet, &view));
+            }
+        }
+        for reaction in [
+            Reaction::Eating,
+            Reaction::Happy,
+            Reaction::ShakingOff,
+            Reaction::Training,
+        ] {
+            texts.extend(reaction.art(ascii).into_iter().map(String::from));
+        }
+
+        let report = TickReport {
+            health: 0,
+            hunger: 0,
+            cleanliness: 0,
+            mood: 0,
+            events: vec![
+                TickEvent::GrewUp(LifeStage::Adult),
+                TickEvent::GrewUp(LifeStage::Elderly),
+                TickEvent::Milestone(10),
+            ],
+        };
+        texts.extend(celebration_text("TestPet", &report, Lang::Zh, ascii));
+        for event in [
+            TickEvent::Died,
+            TickEvent::ReachedCritical,
+            TickEvent::GotSick,
+            TickEvent::Healed,
+            TickEvent::Aged,
+            TickEvent::MadeMess,
+            TickEvent::DozedOff,
+            TickEvent::AutoFed,
+            TickEvent::AutoWashed,
+            TickEvent::WentIntoHiding,
+            TickEvent::CameOut,
+        ] {
+            let report = TickReport {
+                events: vec![event],
+                ..report.clone()
+            };
+            texts.extend(tick_notice(&pets[0], &report, &config, ascii));
+        }
+        texts.extend(help_lines(ascii).into_iter().map(String::from));
+        texts.extend(skill_lines(&pets[0].skills, Lang::En));
+        texts.push(level_up_text(Skill::Agility, 2, Lang::Zh, ascii));
+        texts.push(waste_text(&pets[0], Action::Feed, &config, ascii));
+        texts.push(waste_text(&pets[0], Action::Wash, &config, ascii));
+        texts.push(waste_text(&pets[0], Action::Medicine, &config, ascii));
+        let mut dosed = Pet::new("TestPet".to_string());
+        let soon = Utc::now() + TimeDelta::minutes(5);
+        texts.push(medicine_text(&dosed, soon, &config, ascii));
+        texts.push(digest_text(&dosed, soon, &config, ascii));
+        let (summary, body) = critical_notification(&dosed, &config, ascii);
+        texts.extend([summary, body]);
+        dosed.doses = Doses {
+            taken: config.medicine.daily_doses,
+            since: Some(Utc::now()),
+            last: Some(Utc::now()),
+        };
+        texts.push(medicine_text(&dosed, soon, &config, ascii));
+        texts.push(medicine_line(&dosed, &config, Lang::En));
+        texts.extend(pets.iter().map(|pet| title_text(pet, &config, ascii)));
+        texts.push(overstimulated_text(&pets[0], &config, ascii));
+        texts.push(bored_text(&pets[0], &config, ascii));
+        texts.extend(Emote::ALL.map(|emote| emote.text(Lang::En).to_string()));
+        let mut spurts = config.clone();
+        spurts.growth_spurts.enabled = true;
+        let mut growing = Pet::new("TestPet".to_string());
+        growing.age = spurts.growth_spurts.every;
+        texts.extend(
+            stats_lines(&growing, &spurts, &theme, ascii)
+                .iter()
+                .map(Line::to_string),
+        );
+        texts.push(focus_text(&pets[0], Phase::Work, &config, ascii));
+        texts.push(focus_text(&pets[0], Phase::Break, &config, ascii));
+        texts.push(Pomodoro::new(60, 60).line(Lang::En));
+        texts.push(calendar::birthday_text("TestPet", 2, Lang::En));
+        let birthday = calendar::Occasion::Birthday { years: 1 };
+        texts.extend(calendar::decoration(&[birthday], true));
+        for holiday in &config.calendar.holidays {
+            let occasion = calendar::Occasion::Holiday(holiday);
+            texts.extend(calendar::decoration(&[occasion], true));
+        }
+        let mut tutorial = Tutorial::default();
+        texts.push(tutorial.prompt(Lang::En));
+        for action in crate::tutorial::STEPS {
+            tutorial.observe(action);
+            texts.push(tutorial.prompt(Lang::En));
+        }
+        for topic in Topic::ALL {
+            texts.extend(pool(topic, Lang::En).iter().map(|line| line.to_string()));
+        }
+        for achievement in Achievement::ALL {
+            texts.push(achievement_toast(achievement, Lang::Zh, ascii));
+        }
+        texts.extend(theme.swatch_lines(ascii).iter().map(Line::to_string));
+        texts.extend(
+            [
+                ASCII_BORDER.top_left,
+                ASCII_BORDER.top_right,
+                ASCII_BORDER.bottom_left,
+                ASCII_BORDER.bottom_right,
+                ASCII_BORDER.vertical_left,
+                ASCII_BORDER.vertical_right,
+                ASCII_BORDER.horizontal_top,
+                ASCII_BORDER.horizontal_bottom,
+            ]
+            .map(String::from),
+        );
+
+        for text in texts {
+            assert!(text.is_ascii(), "not ASCII: {text}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_end_is_an_error() {
+        let mut stream = StreamInput {
+            stream: futures_util::stream::empty::<io::Result<Event>>(),
+        };
+        assert!(stream.next_event().await.is_err());
+    }
 }
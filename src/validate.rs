@@ -0,0 +1,183 @@
+use crate::config::{Config, load_config_at};
+use crate::error::Error;
+use crate::state::{StateFile, load_state};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How one file `petty validate` looked at turned out
+#[derive(Debug)]
+pub enum Outcome {
+    /// Nothing there yet, which is fine
+    Missing,
+    /// It loads, with a word on what's in it
+    Valid(String),
+    Invalid(Error),
+}
+
+/// One file checked by `petty validate`
+#[derive(Debug)]
+pub struct Check {
+    /// What the file is, like "config"
+    pub what: &'static str,
+    pub path: PathBuf,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    /// The report for this file, like "config ~/.petty/config.toml: ok".
+    /// A problem follows on its own lines, indented.
+    pub fn report(&self) -> String {
+        let head = format!("{} {}", self.what, self.path.display());
+        match &self.outcome {
+            Outcome::Missing => format!("{head}: not there yet, nothing to check"),
+            Outcome::Valid(summary) => format!("{head}: ok ({summary})"),
+            Outcome::Invalid(error) => {
+                let detail = error.to_string();
+                let lines: Vec<String> = detail.lines().map(|line| format!("  {line}")).collect();
+                format!("{head}: invalid\n{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+/// Load the config at `path` the way petty would, with `profile` picking the
+/// profile. A config that loads as a whole but trips on a value has its
+/// error looked up again with the line it's on, where one can be found.
+pub fn check_config(path: &Path, profile: Option<&str>) -> (Check, Option<Config>) {
+    let (outcome, config) = match load_config_at(path, profile) {
+        Ok(config) if !path.exists() => (Outcome::Missing, Some(config)),
+        Ok(config) => (Outcome::Valid(describe_config(&config)), Some(config)),
+        Err(Error::Config(reason)) => {
+            (Outcome::Invalid(Error::Config(located(path, reason))), None)
+        }
+        Err(error) => (Outcome::Invalid(error), None),
+    };
+    let check = Check {
+        what: "config",
+        path: path.to_path_buf(),
+        outcome,
+    };
+    (check, config)
+}
+
+fn describe_config(config: &Config) -> String {
+    match &config.active_pet {
+        Some(pet) => format!("theme {}, active pet {pet}", config.theme),
+        None => format!("theme {}", config.theme),
+    }
+}
+
+/// `reason` for the config at `path`, swapped for the one a straight parse
+/// gives if that one says where in the file it is. Going through profiles
+/// loses the position of a bad value.
+fn located(path: &Path, reason: String) -> String {
+    if reason.contains("line ") {
+        return reason;
+    }
+    let Ok(data) = fs::read_to_string(path) else {
+        return reason;
+    };
+    match toml::from_str::<Config>(&data) {
+        Err(error) if error.span().is_some() => error.to_string(),
+        _ => reason,
+    }
+}
+
+/// Load the save in `file`, journals and all, the way petty would
+pub fn check_state(file: &StateFile) -> Check {
+    let outcome = match load_state(file) {
+        Ok(pet) => Outcome::Valid(format!("{}, age {}", pet.name, pet.age)),
+        Err(Error::NotFound(_)) => Outcome::Missing,
+        Err(error) => Outcome::Invalid(error),
+    };
+    Check {
+        what: "save",
+        path: file.path.clone(),
+        outcome,
+    }
+}
+
+/// Whether `petty validate` passes, or which files let it down, like
+/// "the config and the save have problems"
+pub fn verdict(checks: &[Check]) -> Result<(), String> {
+    let failed: Vec<&str> = checks
+        .iter()
+        .filter(|check| matches!(check.outcome, Outcome::Invalid(_)))
+        .map(|check| check.what)
+        .collect();
+    match failed.as_slice() {
+        [] => Ok(()),
+        [one] => Err(format!("the {one} has problems")),
+        [rest @ .., last] => Err(format!(
+            "the {} and the {last} have problems",
+            rest.join(", the ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pet::Pet;
+    use crate::state::{StateFormat, save_state_to};
+
+    #[test]
+    fn test_malformed_config_is_reported_with_its_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let (check, _) = check_config(&path, None);
+        assert!(matches!(check.outcome, Outcome::Missing));
+
+        fs::write(&path, "theme = \"dark\"\nmax_fps = \"fast\"\n").unwrap();
+        let (check, config) = check_config(&path, None);
+        assert!(config.is_none());
+        let report = check.report();
+        assert!(report.starts_with(&format!("config {}: invalid\n", path.display())));
+        assert!(report.contains("line 2"), "{report}");
+        assert!(report.contains("max_fps = \"fast\""), "{report}");
+        assert!(matches!(check.outcome, Outcome::Invalid(Error::Config(_))));
+        assert_eq!(
+            verdict(&[check]),
+            Err("the config has problems".to_string())
+        );
+
+        fs::write(&path, "[rules.fatigue]\ntired = 101\n").unwrap();
+        let (check, _) = check_config(&path, None);
+        assert!(matches!(check.outcome, Outcome::Invalid(Error::Config(_))));
+
+        fs::write(&path, "theme = \"dark\"\n").unwrap();
+        let (check, config) = check_config(&path, None);
+        assert_eq!(
+            check.report(),
+            format!("config {}: ok (theme dark)", path.display())
+        );
+        assert_eq!(config.unwrap().theme, "dark");
+    }
+
+    #[test]
+    fn test_corrupt_save_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = StateFile::in_dir(dir.path(), StateFormat::Json);
+        assert!(matches!(check_state(&file).outcome, Outcome::Missing));
+
+        save_state_to(&file, &Pet::new("Mochi".to_string())).unwrap();
+        let check = check_state(&file);
+        assert!(check.report().ends_with(": ok (Mochi, age 0)"));
+        assert_eq!(verdict(&[check]), Ok(()));
+
+        fs::write(&file.path, "{\"name\": \"Mochi\",").unwrap();
+        let check = check_state(&file);
+        let report = check.report();
+        assert!(report.starts_with(&format!("save {}: invalid\n  ", file.path.display())));
+        assert!(matches!(
+            check.outcome,
+            Outcome::Invalid(Error::Corrupt { .. })
+        ));
+
+        let (config, _) = check_config(&dir.path().join("config.toml"), None);
+        assert_eq!(
+            verdict(&[config, check]),
+            Err("the save has problems".to_string())
+        );
+    }
+}
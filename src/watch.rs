@@ -0,0 +1,105 @@
+use crate::config::Config;
+use crate::pet::{Pet, PetStatus, TickReport};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::io::Write;
+use std::time::Duration;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::time::interval;
+
+/// One line of `petty watch --jsonl` output
+#[derive(Serialize)]
+struct WatchLine<'a> {
+    at: DateTime<Utc>,
+    tick: u64,
+    name: &'a str,
+    age: u32,
+    health: u8,
+    hunger: u8,
+    cleanliness: u8,
+    mood: u8,
+    is_sleeping: bool,
+    status: &'a PetStatus,
+    delta: &'a TickReport,
+}
+
+/// Serialize the pet's state after a tick, with the tick's report, as a single JSON line
+pub fn report_line(pet: &Pet, report: &TickReport, tick: u64, at: DateTime<Utc>) -> String {
+    let line = WatchLine {
+        at,
+        tick,
+        name: &pet.name,
+        age: pet.age,
+        health: pet.health,
+        hunger: pet.hunger,
+        cleanliness: pet.cleanliness,
+        mood: pet.mood,
+        is_sleeping: pet.is_sleeping,
+        status: &pet.status,
+        delta: report,
+    };
+    // Serializing plain fields to a string can't fail
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+/// Run the simulation without a UI, writing one JSON line per tick to `out`
+/// until interrupted or the pet dies
+pub async fn run_watch(pet: &mut Pet, config: &Config, out: &mut impl Write) -> Result<()> {
+    let mut ticker = interval(Duration::from_secs(1));
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut tick = 0;
+
+    // The first tick of an interval completes immediately
+    ticker.tick().await;
+
+    while pet.health > 0 && !matches!(pet.status, PetStatus::Abandoned | PetStatus::Retired) {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+            _ = ticker.tick() => {
+                tick += 1;
+                let (report, _) = pet.live(config, tick, Utc::now());
+                writeln!(out, "{}", report_line(pet, &report, tick, Utc::now()))?;
+                out.flush()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pet::TickEvent;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_report_line() {
+        let mut pet = Pet::new("Petty".to_string());
+        pet.health = 21;
+        pet.hunger = 95;
+        pet.cleanliness = 50;
+        pet.mood = 50;
+
+        let mut config = Config::default();
+        config.rules.starving_grace = 0;
+        config.rules.honeymoon_minutes = 0;
+        let report = pet.tick(&config, 3, pet.birth);
+        assert!(report.has(TickEvent::GotSick));
+
+        let at = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let line = report_line(&pet, &report, 3, at);
+        assert_eq!(
+            line,
+            concat!(
+                r#"{"at":"2025-01-02T03:04:05Z","tick":3,"name":"Petty","age":0,"#,
+                r#""health":18,"hunger":97,"cleanliness":47,"mood":48,"is_sleeping":false,"#,
+                r#""status":"Sick","delta":{"health":-3,"hunger":2,"cleanliness":-3,"#,
+                r#""mood":-2,"events":["GotSick"]}}"#
+            )
+        );
+        assert!(!line.contains('\n'));
+    }
+}